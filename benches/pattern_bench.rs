@@ -1,7 +1,7 @@
 // benches/pattern_bench.rs
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use patternhunt::predicates::FileType;
-use patternhunt::{GlobOptions, GlobOptionsBuilder, PatternHunt, Predicates};
+use patternhunt::{GlobOptions, GlobOptionsBuilder, PatternHunt, Patterns, PredicatesBuilder};
 use std::time::{Duration, SystemTime};
 
 fn bench_basic_patterns(c: &mut Criterion) {
@@ -56,16 +56,13 @@ fn bench_regex_patterns(c: &mut Criterion) {
 }
 
 fn bench_with_predicates(c: &mut Criterion) {
-    let predicates = Predicates {
-        min_size: Some(1024),
-        max_size: Some(1024 * 1024),
-        file_type: Some(FileType::File),
-        mtime_after: Some(SystemTime::now() - Duration::from_secs(24 * 3600)),
-        mtime_before: Some(SystemTime::now()),
-        ctime_after: None,
-        ctime_before: None,
-        follow_symlinks: false,
-    };
+    let predicates = PredicatesBuilder::new()
+        .min_size(1024)
+        .max_size(1024 * 1024)
+        .file_type(FileType::File)
+        .mtime_after(SystemTime::now() - Duration::from_secs(24 * 3600))
+        .mtime_before(SystemTime::now())
+        .build();
 
     let options = GlobOptionsBuilder::new().predicates(predicates).build();
 
@@ -87,7 +84,7 @@ fn bench_with_predicates(c: &mut Criterion) {
 fn bench_multiple_roots(c: &mut Criterion) {
     let options = GlobOptions::default();
     let patterns = vec!["*.rs", "*.toml"];
-    let roots = vec!["src", "tests", "examples"];
+    let roots = vec!["src", "tests", "benches"];
 
     c.bench_function("multiple_roots", |b| {
         b.iter(|| {
@@ -101,6 +98,128 @@ fn bench_multiple_roots(c: &mut Criterion) {
     });
 }
 
+fn bench_parallel_sync(c: &mut Criterion) {
+    let patterns = vec!["**/*.rs", "**/*.toml"];
+    let roots = vec!["."];
+    let predicates = PredicatesBuilder::new().file_type(FileType::File).build();
+
+    for threads in [1, 4] {
+        let options = GlobOptionsBuilder::new()
+            .predicates(predicates.clone())
+            .threads(threads)
+            .build();
+        c.bench_function(&format!("sync_threads_{}", threads), |b| {
+            b.iter(|| {
+                let result = PatternHunt::sync(
+                    black_box(&patterns),
+                    black_box(&roots),
+                    black_box(options.clone()),
+                );
+                black_box(result.unwrap())
+            })
+        });
+    }
+}
+
+fn bench_regex_set_vs_sequential(c: &mut Criterion) {
+    let patterns: Vec<String> = (0..50)
+        .map(|i| format!("src/module{}/@(lib|mod|main).rs", i))
+        .collect();
+    let pats = Patterns::compile_many(&patterns, &GlobOptions::default()).unwrap();
+    let paths: Vec<String> = (0..200)
+        .map(|i| format!("src/module{}/file{}.rs", i % 60, i))
+        .collect();
+
+    c.bench_function("regex_sequential", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|p| pats.regexes.iter().any(|re| re.is_match(black_box(p))))
+                .count()
+        })
+    });
+
+    c.bench_function("regex_set", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|p| pats.matches_str(black_box(p)))
+                .count()
+        })
+    });
+}
+
+fn bench_glob_only_fast_path(c: &mut Criterion) {
+    let globs = ["src/**/*.rs", "**/*.toml", "docs/*.md"];
+    let glob_only = Patterns::compile_many(globs, &GlobOptions::default()).unwrap();
+    let with_regex = Patterns::compile_many(
+        globs.iter().copied().chain(["re:^target/.*\\.d$"]),
+        &GlobOptions::default(),
+    )
+    .unwrap();
+    let paths: Vec<String> = (0..500)
+        .map(|i| format!("crates/part{}/module{}/file{}.txt", i % 7, i % 13, i))
+        .collect();
+
+    c.bench_function("match_glob_only", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|p| glob_only.matches_str(black_box(p)))
+                .count()
+        })
+    });
+
+    c.bench_function("match_glob_with_regex", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|p| with_regex.matches_str(black_box(p)))
+                .count()
+        })
+    });
+}
+
+fn bench_directory_pruning(c: &mut Criterion) {
+    // Thousands of directories, of which only `src` can hold a match
+    let dir = tempfile::tempdir().unwrap();
+    for pkg in 0..200 {
+        for module in 0..10 {
+            let module_dir = dir.path().join(format!("pkg{}/module{}", pkg, module));
+            std::fs::create_dir_all(&module_dir).unwrap();
+            std::fs::write(module_dir.join("mod.rs"), "").unwrap();
+        }
+    }
+    for module in 0..10 {
+        let module_dir = dir.path().join(format!("src/module{}", module));
+        std::fs::create_dir_all(&module_dir).unwrap();
+        std::fs::write(module_dir.join("mod.rs"), "").unwrap();
+    }
+    let options = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .patterns_relative_to_root(true)
+        .build();
+    let roots = vec!["."];
+
+    // The leading `**` leaves nothing to prune, so every directory is read
+    for (name, pattern) in [
+        ("prune_literal_prefix", "src/**/mod.rs"),
+        ("prune_none", "**/src/**/mod.rs"),
+    ] {
+        let patterns = vec![pattern];
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                let result = PatternHunt::sync(
+                    black_box(&patterns),
+                    black_box(&roots),
+                    black_box(options.clone()),
+                );
+                black_box(result.unwrap())
+            })
+        });
+    }
+}
+
 #[cfg(feature = "async")]
 fn bench_async_search(c: &mut Criterion) {
     use futures::{pin_mut, StreamExt};
@@ -135,6 +254,43 @@ fn bench_async_search(c: &mut Criterion) {
     });
 }
 
+#[cfg(feature = "async")]
+fn bench_async_inline_vs_blocking(c: &mut Criterion) {
+    use futures::StreamExt;
+    use tokio::runtime::Runtime;
+
+    let rt = Runtime::new().unwrap();
+    let patterns = vec!["**/*.rs", "**/*.toml"];
+    let roots = vec!["."];
+    let predicates = PredicatesBuilder::new().file_type(FileType::File).build();
+
+    // Pattern-only matching runs on the executor, predicates need the blocking pool
+    for (name, options) in [
+        ("async_patterns_only", GlobOptions::default()),
+        (
+            "async_with_predicates",
+            GlobOptionsBuilder::new().predicates(predicates).build(),
+        ),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let stream = PatternHunt::stream(
+                        black_box(&patterns),
+                        black_box(&roots),
+                        options.clone(),
+                    )
+                    .unwrap();
+                    stream
+                        .filter(|r| futures::future::ready(r.is_ok()))
+                        .count()
+                        .await
+                })
+            })
+        });
+    }
+}
+
 // Создаем группу бенчмарков в зависимости от наличия фичи async
 #[cfg(not(feature = "async"))]
 criterion_group!(
@@ -143,7 +299,11 @@ criterion_group!(
     bench_complex_patterns,
     bench_regex_patterns,
     bench_with_predicates,
-    bench_multiple_roots
+    bench_multiple_roots,
+    bench_parallel_sync,
+    bench_regex_set_vs_sequential,
+    bench_glob_only_fast_path,
+    bench_directory_pruning
 );
 
 #[cfg(feature = "async")]
@@ -154,7 +314,12 @@ criterion_group!(
     bench_regex_patterns,
     bench_with_predicates,
     bench_multiple_roots,
-    bench_async_search
+    bench_parallel_sync,
+    bench_regex_set_vs_sequential,
+    bench_glob_only_fast_path,
+    bench_directory_pruning,
+    bench_async_search,
+    bench_async_inline_vs_blocking
 );
 
 criterion_main!(benches);
@@ -1,23 +1,33 @@
 // async_glob.rs
 #[cfg(feature = "async")]
 use crate::{
-    batch_io::BatchIO, error::GlobError, patterns::Patterns, predicates::Predicates, GlobOptions,
+    batch_io::BatchIO,
+    error::GlobError,
+    fs::RealFileSystem,
+    options::{OnNonUtf8, Spend, TraversalOrder},
+    patterns::Patterns,
+    predicates::Predicates,
+    sync::strip_dot_prefix,
+    windows, GlobOptions,
 };
 #[cfg(feature = "async")]
 use async_stream::stream;
 #[cfg(feature = "async")]
-use camino::Utf8PathBuf;
-#[cfg(feature = "async")]
 use futures::Stream;
 #[cfg(feature = "async")]
 use std::{
-    collections::HashSet,
+    borrow::Cow,
+    collections::{HashSet, VecDeque},
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::Instant,
 };
 #[cfg(feature = "async")]
-use tokio::{fs, sync::Semaphore, task};
+use tokio::{
+    fs,
+    sync::{OwnedSemaphorePermit, Semaphore},
+    task,
+};
 
 #[cfg(feature = "async")]
 /// Checks for symlink cycles during directory traversal
@@ -41,6 +51,43 @@ fn check_for_cycles(path: &Path, visited: &mut HashSet<PathBuf>) -> bool {
     false
 }
 
+#[cfg(all(feature = "async", unix))]
+/// Returns the device id of the filesystem containing `path`
+async fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).await.ok().map(|m| m.dev())
+}
+
+#[cfg(all(feature = "async", not(unix)))]
+/// Filesystem boundaries are only detected on Unix
+async fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(feature = "async")]
+/// Checks whether a directory entry name marks it as hidden
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_string_lossy().starts_with('.')
+}
+
+#[cfg(feature = "async")]
+/// Returns the string a path is matched against under the given policy
+///
+/// # Returns
+///
+/// `Ok(None)` if the path should be skipped, or `Err(GlobError::NonUtf8Path)`
+/// for non-UTF-8 paths under `OnNonUtf8::Error`
+fn path_for_matching(path: &Path, policy: OnNonUtf8) -> Result<Option<Cow<'_, str>>, GlobError> {
+    match path.to_str() {
+        Some(s) => Ok(Some(Cow::Borrowed(s))),
+        None => match policy {
+            OnNonUtf8::Skip => Ok(None),
+            OnNonUtf8::Error => Err(GlobError::NonUtf8Path(path.to_path_buf())),
+            OnNonUtf8::Lossy => Ok(Some(path.to_string_lossy())),
+        },
+    }
+}
+
 #[cfg(feature = "async")]
 /// Checks if a path is allowed based on root directory restrictions
 ///
@@ -50,16 +97,57 @@ fn check_for_cycles(path: &Path, visited: &mut HashSet<PathBuf>) -> bool {
 /// # Arguments
 ///
 /// * `path` - The path to check
-/// * `root_dir` - Optional root directory restriction
+/// * `root` - Root directory the walk started from
 ///
 /// # Returns
 ///
 /// `true` if the path is allowed, `false` otherwise
-fn is_path_allowed(path: &Path, root_dir: &Option<PathBuf>) -> bool {
-    if let Some(root) = root_dir {
-        path.starts_with(root)
-    } else {
-        true
+fn is_path_allowed(path: &Path, root: &Path) -> bool {
+    // The drive letter of a root typed as `c:\` may differ in case
+    windows::normalize_drive_letter_path(path)
+        .starts_with(windows::normalize_drive_letter_path(root))
+}
+
+#[cfg(feature = "async")]
+/// Checks whether the root directory itself is a match
+///
+/// # Arguments
+///
+/// * `target` - Path of the root as patterns see it, relative to the
+///   match root when patterns are; converted to a string per `non_utf8`
+/// * `root` - The root directory, for predicates
+/// * `patterns` - Patterns the root must match as a directory
+/// * `predicates` - Optional predicates the root must pass
+/// * `batch_io` - Metadata cache for the predicates
+/// * `non_utf8` - What to do if `target` isn't valid UTF-8
+///
+/// # Returns
+///
+/// `Ok(true)` if the root should be yielded
+fn root_matches(
+    target: &Path,
+    root: &Path,
+    patterns: &Patterns,
+    predicates: Option<&Predicates>,
+    batch_io: &BatchIO,
+    non_utf8: OnNonUtf8,
+) -> Result<bool, GlobError> {
+    let Some(path_str) = path_for_matching(target, non_utf8)? else {
+        return Ok(false);
+    };
+    if !patterns.matches_entry_str(&path_str, true) {
+        return Ok(false);
+    }
+    match predicates {
+        Some(preds) => {
+            let meta = batch_io.stat(&RealFileSystem, root)?;
+            Ok(preds.matches_path_only(root)
+                && preds.matches_metadata(&meta)
+                && preds.matches_content(false, |_| Ok(Vec::new()))?
+                && preds.matches_xattr(|name| batch_io.read_xattr(&RealFileSystem, root, name))?
+                && preds.matches_readable(|| batch_io.is_readable(&RealFileSystem, root))?)
+        }
+        None => Ok(true),
     }
 }
 
@@ -77,128 +165,377 @@ fn is_path_allowed(path: &Path, root_dir: &Option<PathBuf>) -> bool {
 ///
 /// # Returns
 ///
-/// A stream that yields `Result<PathBuf, GlobError>` values
+/// A stream that yields `Result<PathBuf, GlobError>` values. When
+/// `opts.timeout` is set it is an overall deadline for the whole walk:
+/// once exceeded, the stream yields `GlobError::Timeout` and ends.
+/// Likewise, once `opts.cancel` is set the stream yields
+/// `GlobError::Cancelled` and ends without yielding further matches.
+/// An entry matching the patterns that can't get a concurrency permit
+/// within `opts.permit_timeout` is reported as `GlobError::PermitTimeout`
+/// and the walk continues; entries are never dropped silently.
+/// Unless `opts.cross_filesystems` is set, directories on a different
+/// filesystem than the root are not entered (detected on Unix only).
 pub fn glob_stream(
     patterns: Patterns,
     opts: GlobOptions,
     predicates: Option<Predicates>,
 ) -> impl Stream<Item = Result<PathBuf, GlobError>> {
     let semaphore = Arc::new(Semaphore::new(opts.max_inflight));
+    // Each reader in `open` holds one of these; without a limit there are
+    // enough permits that acquiring one never fails
+    let dir_permits = Arc::new(Semaphore::new(
+        opts.max_open_dirs
+            .map_or(Semaphore::MAX_PERMITS, |n| n.max(1)),
+    ));
     let patterns = Arc::new(patterns);
+    // Predicates may ask to stat through symlinks even when the walk doesn't
+    let stat_follows =
+        opts.follow_symlinks || predicates.as_ref().is_some_and(|p| p.follow_symlinks);
     let predicates = Arc::new(predicates);
-    let batch_io = Arc::new(BatchIO::new(1000, opts.follow_symlinks));
-    let root = opts.root_dir.clone().unwrap_or_else(|| PathBuf::from("."));
+    let batch_io = Arc::new(match &opts.batch_io {
+        Some(shared) => shared.following(stat_follows),
+        None => BatchIO::new(1000, stat_follows),
+    });
+    let root = opts.walk_root();
+    // Candidates are matched relative to this prefix when requested
+    let match_root = Arc::new(root.clone());
+    let relative = opts.patterns_relative_to_root;
+    let match_dirs = opts.matches_directories();
+    let non_utf8 = opts.non_utf8;
+    let keep_prefix = opts.keep_long_path_prefix;
+    let strip_dot = opts.strip_dot_prefix;
+    let has_budget = opts.max_total_bytes.is_some();
+    // Pattern matching alone is cheap CPU work on in-memory strings; only
+    // entries that must be stat'ed or read are sent to the blocking pool
+    let needs_io = predicates.is_some() || has_budget;
+    let match_resolved = opts.match_resolved_path;
+    let return_resolved = opts.return_resolved_path;
 
     stream! {
+        let deadline = opts.timeout.and_then(|t| Instant::now().checked_add(t));
         let mut visited_links = HashSet::new();
-        let mut stack = vec![(root, 0)]; // (directory, depth)
+        let mut bytes_spent = 0;
+        let root_dev = if opts.cross_filesystems {
+            None
+        } else {
+            device_id(&root).await
+        };
+        let confined_root = if opts.follow_symlinks && opts.confine_to_root {
+            Some(fs::canonicalize(&root).await.unwrap_or_else(|_| root.clone()))
+        } else {
+            None
+        };
+        // The root is a candidate only with `include_root`
+        if opts.include_root && match_dirs && opts.min_depth.unwrap_or(0) == 0 {
+            let target = if relative { Path::new(".") } else { root.as_path() };
+            match root_matches(target, &root, &patterns, predicates.as_ref().as_ref(), &batch_io, non_utf8) {
+                Ok(true) => yield Ok(root.clone()),
+                Ok(false) => {}
+                Err(e) => yield Err(e),
+            }
+        }
+
+        // Directories being read, innermost last, each with its depth and
+        // the canonical targets of the symlinks followed to reach it. A
+        // depth-first walk opens a subdirectory as soon as it is found, so
+        // it holds one reader per level; a breadth-first walk queues
+        // subdirectories in `pending` and reads one at a time, as does a
+        // depth-first walk at `max_open_dirs`. Long paths are walked
+        // through their `\\?\` form on Windows
+        let mut open: Vec<(fs::ReadDir, usize, Arc<Vec<PathBuf>>, OwnedSemaphorePermit)> =
+            Vec::new();
+        let mut pending = VecDeque::from([(
+            windows::ensure_long_path_prefix(&root),
+            0,
+            Arc::new(Vec::new()),
+        )]);
+
+        'walk: loop {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                yield Err(GlobError::Timeout);
+                break 'walk;
+            }
+            if opts.is_cancelled() {
+                yield Err(GlobError::Cancelled);
+                break 'walk;
+            }
+
+            let Some((rd, depth, links, _)) = open.last_mut() else {
+                let Some((dir, depth, links)) = pending.pop_front() else {
+                    break 'walk;
+                };
+                // Entries of `dir` sit one level below it, as in WalkDir where
+                // the root is depth 0 and `max_depth(1)` yields only its children
+                if opts.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                    continue;
+                }
+                // Every reader has been closed, so a permit is free
+                let Ok(permit) = Arc::clone(&dir_permits).try_acquire_owned() else {
+                    yield Err(GlobError::Other("open directory semaphore closed".into()));
+                    break 'walk;
+                };
+                match fs::read_dir(&dir).await {
+                    Ok(rd) => open.push((rd, depth, links, permit)),
+                    Err(e) => yield Err(GlobError::Io(e)),
+                }
+                continue;
+            };
+            let (depth, links) = (*depth, Arc::clone(links));
 
-        while let Some((dir, depth)) = stack.pop() {
-            let mut rd = match fs::read_dir(&dir).await {
-                Ok(rd) => rd,
+            let entry = match rd.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    open.pop();
+                    continue;
+                }
                 Err(e) => {
+                    open.pop();
                     yield Err(GlobError::Io(e));
                     continue;
                 }
             };
 
-            loop {
-                let entry = match rd.next_entry().await {
-                    Ok(Some(entry)) => entry,
-                    Ok(None) => break,
-                    Err(e) => {
-                        yield Err(GlobError::Io(e));
-                        break;
-                    }
-                };
+            // Skipping a hidden directory here prunes its whole subtree
+            if opts.skip_hidden && is_hidden(&entry.file_name()) {
+                continue;
+            }
 
-                let path = entry.path();
+            // I/O goes through `path`; matching sees the unprefixed form
+            let path = entry.path();
+            if !is_path_allowed(&windows::strip_long_path_prefix(&path), &root) {
+                continue;
+            }
 
-                if !is_path_allowed(&path, &opts.root_dir) {
+            if opts.follow_symlinks && check_for_cycles(&path, &mut visited_links) {
+                continue;
+            }
+
+            let file_type = match entry.file_type().await {
+                Ok(ft) => ft,
+                Err(e) => {
+                    yield Err(GlobError::Io(e));
                     continue;
                 }
+            };
 
-                if opts.follow_symlinks && check_for_cycles(&path, &mut visited_links) {
-                    continue;
+            // Followed symlinks take their target's type; unfollowed and
+            // broken ones are leaf entries, as in WalkDir
+            let is_symlink = file_type.is_symlink();
+            let follow = is_symlink && opts.follow_symlinks;
+            let is_dir = if follow {
+                fs::metadata(&path).await.is_ok_and(|m| m.is_dir())
+            } else {
+                file_type.is_dir()
+            };
+
+            // A symlink past the hop limit is skipped with its contents
+            if follow && opts.max_symlink_depth.is_some_and(|max| links.len() >= max) {
+                continue;
+            }
+
+            // Followed symlinks must resolve inside the root when confined
+            if is_symlink {
+                if let Some(confined) = &confined_root {
+                    match fs::canonicalize(&path).await {
+                        Ok(target) if target.starts_with(confined) => {}
+                        _ => continue,
+                    }
                 }
+            }
 
-                let file_type = match entry.file_type().await {
-                    Ok(ft) => ft,
-                    Err(e) => {
-                        yield Err(GlobError::Io(e));
+            // Directories no pattern can reach are skipped with their contents
+            if is_dir && !crate::sync::could_reach(&patterns, &path, &root, &opts) {
+                continue;
+            }
+
+            if is_dir {
+                // Stay on the root's filesystem unless crossing is allowed
+                let same_fs = match root_dev {
+                    Some(dev) => device_id(&path).await == Some(dev),
+                    None => true,
+                };
+                let links = if follow {
+                    let target = fs::canonicalize(&path).await.unwrap_or_else(|_| path.clone());
+                    if links.contains(&target) {
+                        yield Err(GlobError::SymlinkCycle);
                         continue;
                     }
+                    let mut chain = Vec::clone(&links);
+                    chain.push(target);
+                    Arc::new(chain)
+                } else {
+                    links
                 };
-
-                let is_dir = file_type.is_dir();
-                let is_symlink = file_type.is_symlink();
-
-                if is_symlink && !opts.follow_symlinks {
+                let child_depth = depth + 1;
+                let below_max = opts.max_depth.is_none_or(|max_depth| child_depth < max_depth);
+                if same_fs {
+                    match opts.walk_order {
+                        TraversalOrder::BreadthFirst => {
+                            pending.push_back((path.clone(), child_depth, links));
+                        }
+                        TraversalOrder::DepthFirst if below_max => {
+                            match Arc::clone(&dir_permits).try_acquire_owned() {
+                                Ok(permit) => match fs::read_dir(&path).await {
+                                    Ok(rd) => open.push((rd, child_depth, links, permit)),
+                                    Err(e) => yield Err(GlobError::Io(e)),
+                                },
+                                // At the limit: read it once the open ones are done
+                                Err(_) => pending.push_back((path.clone(), child_depth, links)),
+                            }
+                        }
+                        TraversalOrder::DepthFirst => {}
+                    }
+                }
+                if !match_dirs {
                     continue;
                 }
+            }
 
-                if is_dir {
-                    if let Some(max_depth) = opts.max_depth {
-                        if depth >= max_depth {
-                            continue;
-                        }
+            // Entries above min_depth are traversed but never returned
+            if opts.min_depth.is_some_and(|min_depth| depth + 1 < min_depth) {
+                continue;
+            }
+
+            // Symlinks can be matched, and returned, by their canonical target
+            let resolved = if is_symlink && opts.resolves_symlinks() {
+                fs::canonicalize(&path)
+                    .await
+                    .ok()
+                    .map(|r| windows::strip_long_path_prefix(&r).into_owned())
+            } else {
+                None
+            };
+
+            // Pattern matching, subject to the non-UTF-8 policy; it needs no
+            // permit, so only entries that could match wait for one
+            {
+                let unprefixed = windows::strip_long_path_prefix(&path);
+                let matched = match &resolved {
+                    Some(r) if match_resolved => r.as_path(),
+                    _ => &*unprefixed,
+                };
+                let target = if relative {
+                    crate::sync::relative_to_root(matched, &match_root)
+                } else {
+                    matched
+                };
+                match path_for_matching(target, non_utf8) {
+                    Ok(Some(s)) if patterns.matches_entry_str(&s, is_dir) => {}
+                    Ok(_) => continue,
+                    Err(e) => {
+                        yield Err(e);
+                        continue;
                     }
-                    stack.push((path.clone(), depth + 1));
+                }
+            }
+
+            // Process candidates asynchronously with bounded concurrency
+            let predicates_clone = predicates.clone();
+            let batch_io_clone = batch_io.clone();
+            let semaphore_clone = semaphore.clone();
+
+            // Acquire semaphore permit, never waiting past the deadline
+            let permit_timeout = match deadline {
+                Some(d) => opts.permit_timeout.min(d.saturating_duration_since(Instant::now())),
+                None => opts.permit_timeout,
+            };
+            let permit = match tokio::time::timeout(
+                permit_timeout,
+                semaphore_clone.acquire_owned()
+            ).await {
+                Ok(Ok(permit)) => permit,
+                // Unreachable: the stream owns the semaphore and never closes it
+                Ok(Err(_)) => {
+                    yield Err(GlobError::Other("concurrency semaphore closed".to_string()));
+                    break 'walk;
+                }
+                Err(_) if deadline.is_some_and(|d| Instant::now() >= d) => {
+                    yield Err(GlobError::Timeout);
+                    break 'walk;
+                }
+                Err(_) => {
+                    yield Err(GlobError::PermitTimeout(path));
                     continue;
                 }
+            };
 
-                // For files, process asynchronously with bounded concurrency
-                let patterns_clone = patterns.clone();
-                let predicates_clone = predicates.clone();
-                let batch_io_clone = batch_io.clone();
-                let path_clone = path.clone();
-                let semaphore_clone = semaphore.clone();
-
-                // Acquire semaphore permit with timeout
-                let permit = match tokio::time::timeout(
-                    opts.timeout.unwrap_or(Duration::from_secs(30)),
-                    semaphore_clone.acquire_owned()
-                ).await {
-                    Ok(Ok(permit)) => permit,
-                    Ok(Err(_)) => continue, // Semaphore closed
-                    Err(_) => continue,     // Timeout
-                };
+            let evaluate = move || {
+                let _permit = permit; // Hold permit for task duration
 
-                // Spawn blocking task for CPU-intensive operations
-                let join_handle = task::spawn_blocking(move || {
-                    let _permit = permit; // Hold permit for task duration
+                let unprefixed = windows::strip_long_path_prefix(&path);
+                let matched = match &resolved {
+                    Some(r) if match_resolved => r.as_path(),
+                    _ => &*unprefixed,
+                };
 
-                    let utf8_path = match Utf8PathBuf::from_path_buf(path_clone.clone()) {
-                        Ok(p) => p,
-                        Err(_) => return Ok(None), // Skip non-UTF8 paths
+                // Predicate filtering
+                let mut metadata = None;
+                if let Some(preds) = &*predicates_clone {
+                    let meta = match batch_io_clone.stat(&RealFileSystem, &path) {
+                        Ok(meta) => meta,
+                        Err(e) => return Err(e),
                     };
-
-                    // Pattern matching
-                    if !patterns_clone.is_match(&utf8_path) {
+                    if !preds.matches_path_only(matched) || !preds.matches_metadata(&meta) {
                         return Ok(None);
                     }
-
-                    // Predicate filtering
-                    if let Some(preds) = &*predicates_clone {
-                        let meta = match batch_io_clone.stat(&path_clone) {
-                            Ok(meta) => meta,
-                            Err(e) => return Err(e),
-                        };
-                        if !preds.matches(&meta) {
-                            return Ok(None);
-                        }
+                    let read = |len| batch_io_clone.read_prefix(&RealFileSystem, &path, len);
+                    if !preds.matches_content(meta.is_file(), read)? {
+                        return Ok(None);
+                    }
+                    if !preds.matches_xattr(|name| batch_io_clone.read_xattr(&RealFileSystem, &path, name))? {
+                        return Ok(None);
+                    }
+                    if !preds.matches_readable(|| batch_io_clone.is_readable(&RealFileSystem, &path))? {
+                        return Ok(None);
                     }
+                    metadata = Some(meta);
+                }
 
-                    Ok(Some(path_clone))
-                });
+                // Matched files are sized for the byte budget
+                let len = if has_budget {
+                    let meta = match metadata {
+                        Some(meta) => meta,
+                        None => batch_io_clone.stat(&RealFileSystem, &path)?,
+                    };
+                    if meta.is_file() { meta.len } else { 0 }
+                } else {
+                    0
+                };
 
-                // Handle task results
-                match join_handle.await {
-                    Ok(Ok(Some(file))) => yield Ok(file),
-                    Ok(Ok(None)) => {}, // No match
-                    Ok(Err(e)) => yield Err(e),
-                    Err(e) => yield Err(GlobError::Other(format!("Task failed: {}", e))),
-                }
+                // Move `path` out unless the long-path prefix is stripped
+                let file = match resolved {
+                    Some(r) if return_resolved => r,
+                    _ if keep_prefix || *unprefixed == *path => path,
+                    _ => unprefixed.into_owned(),
+                };
+                let file = if strip_dot { strip_dot_prefix(file) } else { file };
+                Ok(Some((file, len)))
+            };
+            let outcome = if needs_io {
+                task::spawn_blocking(evaluate)
+                    .await
+                    .unwrap_or_else(|e| Err(GlobError::Other(format!("Task failed: {}", e))))
+            } else {
+                evaluate()
+            };
+
+            // Handle task results
+            // A result that arrives after cancellation is dropped
+            if opts.is_cancelled() {
+                yield Err(GlobError::Cancelled);
+                break 'walk;
+            }
+            match outcome {
+                Ok(Some((file, len))) => match opts.spend_bytes(&mut bytes_spent, len) {
+                    Spend::Keep => yield Ok(file),
+                    Spend::Last => {
+                        yield Ok(file);
+                        break 'walk;
+                    }
+                    Spend::Over => break 'walk,
+                },
+                Ok(None) => {}, // No match
+                Err(e) => yield Err(e),
             }
         }
     }
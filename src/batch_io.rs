@@ -1,31 +1,52 @@
 // batch_io.rs
-use crate::error::GlobError;
+use crate::{
+    error::GlobError,
+    fs::{FileSystem, FsMetadata},
+};
 use lru::LruCache;
 use std::{
-    fs,
     num::NonZeroUsize,
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
-/// Configuration for metadata caching
+/// Default lifetime of cached metadata and content prefixes
 const METADATA_CACHE_TTL: Duration = Duration::from_secs(30);
 
+/// Maximum number of cached content prefixes
+const PREFIX_CACHE_SIZE: usize = 256;
+
 /// A cached metadata entry with expiration timestamp
 #[derive(Debug, Clone)]
 struct CachedMetadata {
-    metadata: fs::Metadata,
+    metadata: FsMetadata,
+    expires_at: Instant,
+}
+
+/// A cached content prefix with expiration timestamp
+#[derive(Debug, Clone)]
+struct CachedPrefix {
+    bytes: Vec<u8>,
     expires_at: Instant,
 }
 
 /// Batch I/O operations with metadata caching
 ///
 /// This struct provides efficient access to filesystem metadata
-/// with LRU caching and configurable symlink following behavior.
+/// with LRU caching and configurable symlink following behavior. Every
+/// lookup goes through the [`FileSystem`] passed to it; cached entries are
+/// keyed by path alone, so an instance should only serve one filesystem.
+///
+/// A caller-owned instance can be shared between globs through
+/// `GlobOptions::batch_io`, so that repeated scans reuse cached metadata
+/// and [`BatchIO::clear_cache`] can invalidate it between runs.
 #[derive(Debug)]
 pub struct BatchIO {
-    metadata_cache: Mutex<LruCache<PathBuf, CachedMetadata>>,
+    /// Keyed by path and whether symlinks were followed
+    metadata_cache: Arc<Mutex<LruCache<(PathBuf, bool), CachedMetadata>>>,
+    prefix_cache: Arc<Mutex<LruCache<(PathBuf, usize), CachedPrefix>>>,
+    ttl: Duration,
     follow_symlinks: bool,
 }
 
@@ -42,7 +63,32 @@ impl BatchIO {
     /// A new BatchIO instance
     pub fn new(cache_size: usize, follow_symlinks: bool) -> Self {
         Self {
-            metadata_cache: Mutex::new(LruCache::new(NonZeroUsize::new(cache_size).unwrap())),
+            metadata_cache: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(cache_size).unwrap(),
+            ))),
+            prefix_cache: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(PREFIX_CACHE_SIZE).unwrap(),
+            ))),
+            ttl: METADATA_CACHE_TTL,
+            follow_symlinks,
+        }
+    }
+
+    /// Sets how long cached metadata and content prefixes stay valid
+    ///
+    /// Defaults to 30 seconds; `Duration::ZERO` disables reuse entirely.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns a handle sharing this instance's caches and TTL, but
+    /// following symlinks as a particular walk requires
+    pub(crate) fn following(&self, follow_symlinks: bool) -> Self {
+        Self {
+            metadata_cache: Arc::clone(&self.metadata_cache),
+            prefix_cache: Arc::clone(&self.prefix_cache),
+            ttl: self.ttl,
             follow_symlinks,
         }
     }
@@ -50,52 +96,47 @@ impl BatchIO {
     /// Retrieves metadata for a path with caching
     ///
     /// This method checks the cache first, and if not found or expired,
-    /// queries the filesystem. Also performs permission checks.
+    /// queries the filesystem. When not following symlinks, a symlink
+    /// yields its own metadata.
     ///
     /// # Arguments
     ///
+    /// * `fs` - Filesystem to query
     /// * `path` - Path to retrieve metadata for
     ///
     /// # Returns
     ///
-    /// `Ok(Metadata)` if successful, `Err(GlobError)` otherwise
+    /// `Ok(FsMetadata)` if successful, `Err(GlobError)` otherwise
     ///
     /// # Errors
     ///
-    /// Returns `GlobError::PermissionDenied` if file is read-only
     /// Returns `GlobError::Io` for I/O errors
-    /// Returns `GlobError::Other` for symlinks when not allowed
-    pub fn stat(&self, path: &Path) -> Result<fs::Metadata, GlobError> {
+    pub fn stat(&self, fs: &dyn FileSystem, path: &Path) -> Result<FsMetadata, GlobError> {
+        let key = (path.to_path_buf(), self.follow_symlinks);
         let mut cache = self.metadata_cache.lock().unwrap();
 
         // Check cache first
-        if let Some(cached) = cache.get(path) {
+        if let Some(cached) = cache.get(&key) {
             if cached.expires_at > Instant::now() {
                 return Ok(cached.metadata.clone());
             }
             // Remove expired entry
-            cache.pop(path);
-        }
-
-        // Check symlink restrictions
-        if !self.follow_symlinks && path.is_symlink() {
-            return Err(GlobError::Other("Symlinks not allowed".into()));
+            cache.pop(&key);
         }
 
-        // Query filesystem
-        let meta = fs::metadata(path).map_err(GlobError::Io)?;
-
-        // Permission check
-        if meta.permissions().readonly() {
-            return Err(GlobError::PermissionDenied);
-        }
+        // Query filesystem; only a symlink's own metadata differs
+        let meta = if self.follow_symlinks {
+            fs.metadata(path)?
+        } else {
+            fs.symlink_metadata(path)?
+        };
 
         // Cache the result
         let cached_meta = CachedMetadata {
             metadata: meta.clone(),
-            expires_at: Instant::now() + METADATA_CACHE_TTL,
+            expires_at: Instant::now() + self.ttl,
         };
-        cache.put(path.to_path_buf(), cached_meta);
+        cache.put(key, cached_meta);
 
         Ok(meta)
     }
@@ -107,21 +148,107 @@ impl BatchIO {
     ///
     /// # Arguments
     ///
+    /// * `fs` - Filesystem to query
     /// * `path` - Path to the symlink
     ///
     /// # Returns
     ///
-    /// `Ok(Metadata)` if successful, `Err(GlobError)` otherwise
-    pub fn stat_symlink(&self, path: &Path) -> Result<fs::Metadata, GlobError> {
-        fs::symlink_metadata(path).map_err(GlobError::Io)
+    /// `Ok(FsMetadata)` if successful, `Err(GlobError)` otherwise
+    pub fn stat_symlink(&self, fs: &dyn FileSystem, path: &Path) -> Result<FsMetadata, GlobError> {
+        Ok(fs.symlink_metadata(path)?)
+    }
+
+    /// Reads up to `len` leading bytes of a file, with caching
+    ///
+    /// Results are cached per path and length in a small LRU, with the
+    /// same expiry as metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `fs` - Filesystem to read from
+    /// * `path` - File to read
+    /// * `len` - Maximum number of bytes to read
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<u8>)` with the leading bytes, shorter than `len` if the file
+    /// is, or `Err(GlobError::Io)` if the file cannot be read
+    pub fn read_prefix(
+        &self,
+        fs: &dyn FileSystem,
+        path: &Path,
+        len: usize,
+    ) -> Result<Vec<u8>, GlobError> {
+        let key = (path.to_path_buf(), len);
+        {
+            let mut cache = self.prefix_cache.lock().unwrap();
+            if let Some(cached) = cache.get(&key) {
+                if cached.expires_at > Instant::now() {
+                    return Ok(cached.bytes.clone());
+                }
+                cache.pop(&key);
+            }
+        }
+
+        // Read without holding the lock so concurrent reads don't serialize
+        let bytes = fs.read_prefix(path, len)?;
+
+        self.prefix_cache.lock().unwrap().put(
+            key,
+            CachedPrefix {
+                bytes: bytes.clone(),
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+        Ok(bytes)
     }
 
-    /// Clears the metadata cache
+    /// Reads an extended attribute, following symlinks as configured
+    ///
+    /// Attributes are not cached. Filesystems without extended attribute
+    /// support report `GlobError::Io` with `Unsupported`.
+    ///
+    /// # Arguments
+    ///
+    /// * `fs` - Filesystem to read from
+    /// * `path` - Entry to read from
+    /// * `name` - Attribute name, such as `user.tag`
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(value))`, `Ok(None)` if the attribute isn't set, or
+    /// `Err(GlobError::Io)` if the entry can't be read
+    pub fn read_xattr(
+        &self,
+        fs: &dyn FileSystem,
+        path: &Path,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, GlobError> {
+        if self.follow_symlinks {
+            Ok(fs.read_xattr(path, name)?)
+        } else {
+            Ok(fs.read_symlink_xattr(path, name)?)
+        }
+    }
+
+    /// Checks whether the current user can read `path`
+    ///
+    /// Symlinks are followed; the result is not cached.
+    ///
+    /// # Errors
+    ///
+    /// `Err(GlobError::Io)` if `fs` can't tell
+    pub fn is_readable(&self, fs: &dyn FileSystem, path: &Path) -> Result<bool, GlobError> {
+        Ok(fs.is_readable(path)?)
+    }
+
+    /// Clears the metadata and content prefix caches
     ///
     /// Useful when filesystem changes are expected and cached data
-    /// might become stale.
+    /// might become stale. Every handle sharing these caches, including
+    /// globs running with this instance, sees the cleared state.
     pub fn clear_cache(&self) {
-        let mut cache = self.metadata_cache.lock().unwrap();
-        cache.clear();
+        self.metadata_cache.lock().unwrap().clear();
+        self.prefix_cache.lock().unwrap().clear();
     }
 }
@@ -0,0 +1,100 @@
+// entry.rs
+use crate::{fs::FsMetadata, predicates::FileType};
+use std::path::PathBuf;
+
+/// A matched filesystem entry with traversal context
+///
+/// This struct carries the information gathered while walking the tree,
+/// so consumers don't need to re-stat paths to learn about them.
+#[derive(Clone, Debug)]
+pub struct GlobEntry {
+    /// Path of the matched entry
+    pub path: PathBuf,
+
+    /// Depth relative to the traversal root (root children are at depth 1)
+    pub depth: usize,
+
+    /// Type of the entry as seen during traversal
+    pub file_type: FileType,
+
+    /// Metadata, populated only when predicates already required a stat
+    pub metadata: Option<FsMetadata>,
+}
+
+impl GlobEntry {
+    /// Consumes the entry and returns its path
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+}
+
+/// Serializes as `{"path", "depth", "type", "size", "mtime"}`
+///
+/// The path is converted lossily to UTF-8, `type` is `"file"`, `"dir"` or
+/// `"symlink"`, and `mtime` is in whole seconds since the Unix epoch.
+/// `size` and `mtime` are `null` when the entry carries no metadata.
+#[cfg(feature = "serde")]
+impl serde::Serialize for GlobEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mtime = self
+            .metadata
+            .as_ref()
+            .and_then(|m| m.modified)
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        let file_type = match self.file_type {
+            FileType::File => "file",
+            FileType::Dir => "dir",
+            FileType::Symlink => "symlink",
+        };
+        let mut s = serializer.serialize_struct("GlobEntry", 5)?;
+        s.serialize_field("path", &self.path.to_string_lossy())?;
+        s.serialize_field("depth", &self.depth)?;
+        s.serialize_field("type", file_type)?;
+        s.serialize_field("size", &self.metadata.as_ref().map(|m| m.len))?;
+        s.serialize_field("mtime", &mtime)?;
+        s.end()
+    }
+}
+
+/// Writes entries as newline-delimited JSON, one object per line
+///
+/// Entries without metadata are stat'ed first, so `size` and `mtime` are
+/// filled in whenever the entry still exists.
+///
+/// # Arguments
+///
+/// * `entries` - Entries to write
+/// * `writer` - Destination, written to unbuffered
+///
+/// # Returns
+///
+/// `Ok(usize)` with the number of entries written
+///
+/// # Errors
+///
+/// Returns `GlobError::Io` if writing fails
+#[cfg(feature = "serde")]
+pub fn write_ndjson<I, W>(entries: I, mut writer: W) -> Result<usize, crate::GlobError>
+where
+    I: IntoIterator<Item = GlobEntry>,
+    W: std::io::Write,
+{
+    let mut count = 0;
+    for mut entry in entries {
+        if entry.metadata.is_none() {
+            entry.metadata = match entry.file_type {
+                FileType::Symlink => std::fs::symlink_metadata(&entry.path),
+                _ => std::fs::metadata(&entry.path),
+            }
+            .ok()
+            .map(|m| FsMetadata::from(&m));
+        }
+        serde_json::to_writer(&mut writer, &entry).map_err(std::io::Error::from)?;
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    Ok(count)
+}
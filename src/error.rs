@@ -1,5 +1,5 @@
 // error.rs
-use std::io;
+use std::{io, path::PathBuf};
 use thiserror::Error;
 use walkdir;
 
@@ -22,6 +22,9 @@ pub enum GlobError {
     InvalidPattern(String),
 
     /// Walkdir traversal error
+    ///
+    /// No longer produced: traversal reports `Io` and `SymlinkCycle`
+    /// errors itself.
     #[error("Walkdir error: {0}")]
     Walkdir(#[from] walkdir::Error),
 
@@ -53,7 +56,72 @@ pub enum GlobError {
     #[error("Operation timed out")]
     Timeout,
 
+    /// Operation was cancelled through `GlobOptions::cancel`
+    #[error("Operation cancelled")]
+    Cancelled,
+
+    /// No concurrency permit became free within `GlobOptions::permit_timeout`
+    ///
+    /// The entry at this path matched the patterns but its predicates were
+    /// not evaluated.
+    #[error("Timed out waiting for a concurrency permit for {}", .0.display())]
+    PermitTimeout(PathBuf),
+
+    /// Path is not valid UTF-8 and `OnNonUtf8::Error` was requested
+    #[error("Path is not valid UTF-8: {}", .0.display())]
+    NonUtf8Path(PathBuf),
+
+    /// A root passed to the facade doesn't exist or isn't a directory
+    #[error("Root directory not found: {}", .0.display())]
+    RootNotFound(PathBuf),
+
+    /// A root referenced an environment variable that isn't set
+    #[error("Environment variable `{0}` in root is not set")]
+    UndefinedEnvVar(String),
+
     /// Permission denied for file access
+    ///
+    /// No longer produced: denied reads are reported as `Io` errors of
+    /// kind `PermissionDenied`, and the `readable` predicate skips
+    /// unreadable entries without an error.
     #[error("Permission denied")]
     PermissionDenied,
+
+    /// A specific input pattern failed to compile
+    #[error("Invalid pattern #{index} `{pattern}`: {message}")]
+    InvalidPatternAt {
+        /// Position of the pattern in the input list
+        index: usize,
+        /// The pattern as supplied, before brace expansion
+        pattern: String,
+        /// Description of the underlying error
+        message: String,
+    },
+
+    /// A pattern loaded from a patterns file failed to compile
+    #[error("Invalid pattern `{pattern}` at {}:{line}: {message}", .file.display())]
+    InvalidPatternInFile {
+        /// The patterns file
+        file: PathBuf,
+        /// 1-based line number of the pattern
+        line: usize,
+        /// The pattern as written, without a leading `!`
+        pattern: String,
+        /// Description of the underlying error
+        message: String,
+    },
+}
+
+impl GlobError {
+    /// Whether the error ends a glob whatever `GlobOptions::on_error` says
+    ///
+    /// These are the errors the synchronous facades always return: a
+    /// timeout, a cancellation, and a root that couldn't be resolved.
+    #[cfg(feature = "async")]
+    pub(crate) fn ends_glob(&self) -> bool {
+        matches!(
+            self,
+            Self::Timeout | Self::Cancelled | Self::RootNotFound(_) | Self::UndefinedEnvVar(_)
+        )
+    }
 }
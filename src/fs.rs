@@ -0,0 +1,507 @@
+// fs.rs
+use crate::error::GlobError;
+use crate::options::OnUndefinedEnv;
+use crate::predicates::FileType;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Filesystem metadata in a form that can be produced without a real file
+///
+/// [`std::fs::Metadata`] cannot be constructed by hand, so traversal over a
+/// [`FileSystem`] works with this struct instead. It carries exactly what
+/// [`crate::Predicates`] inspects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FsMetadata {
+    /// Type of the entry
+    pub file_type: FileType,
+
+    /// Size in bytes
+    pub len: u64,
+
+    /// Last modification time, if available
+    pub modified: Option<SystemTime>,
+
+    /// Creation time, if available
+    pub created: Option<SystemTime>,
+
+    /// Owner user id
+    #[cfg(unix)]
+    pub uid: u32,
+
+    /// Owner group id
+    #[cfg(unix)]
+    pub gid: u32,
+
+    /// Permission and file type bits as reported by `st_mode`
+    #[cfg(unix)]
+    pub mode: u32,
+
+    /// Inode number
+    #[cfg(unix)]
+    pub ino: u64,
+
+    /// Number of hard links
+    #[cfg(unix)]
+    pub nlink: u64,
+
+    /// Id of the device containing the entry
+    #[cfg(unix)]
+    pub dev: u64,
+}
+
+impl FsMetadata {
+    /// Checks whether the entry is a regular file
+    pub fn is_file(&self) -> bool {
+        self.file_type == FileType::File
+    }
+
+    /// Checks whether the entry is a directory
+    pub fn is_dir(&self) -> bool {
+        self.file_type == FileType::Dir
+    }
+}
+
+impl From<&std::fs::Metadata> for FsMetadata {
+    fn from(meta: &std::fs::Metadata) -> Self {
+        #[cfg(unix)]
+        use std::os::unix::fs::MetadataExt;
+
+        let ft = meta.file_type();
+        let file_type = if ft.is_symlink() {
+            FileType::Symlink
+        } else if ft.is_dir() {
+            FileType::Dir
+        } else {
+            FileType::File
+        };
+
+        Self {
+            file_type,
+            len: meta.len(),
+            modified: meta.modified().ok(),
+            created: meta.created().ok(),
+            #[cfg(unix)]
+            uid: meta.uid(),
+            #[cfg(unix)]
+            gid: meta.gid(),
+            #[cfg(unix)]
+            mode: meta.mode(),
+            #[cfg(unix)]
+            ino: meta.ino(),
+            #[cfg(unix)]
+            nlink: meta.nlink(),
+            #[cfg(unix)]
+            dev: meta.dev(),
+        }
+    }
+}
+
+/// Abstraction over the filesystem operations used by traversal
+///
+/// The default implementation is [`RealFileSystem`], which every
+/// synchronous walk uses. Supplying another implementation to
+/// [`crate::PatternHunt::sync_with_fs`] makes it possible to drive the
+/// same traversal over an in-memory tree, for example to simulate
+/// permission errors deterministically in tests.
+pub trait FileSystem: Send + Sync {
+    /// Lists the paths of the entries directly inside `path`
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Lists the entries directly inside `path` with their own types
+    ///
+    /// Symlinks are reported as [`FileType::Symlink`], not followed. The
+    /// default implementation calls [`FileSystem::symlink_metadata`] on
+    /// every path from [`FileSystem::read_dir`].
+    fn read_dir_with_types(&self, path: &Path) -> io::Result<Vec<(PathBuf, FileType)>> {
+        self.read_dir(path)?
+            .into_iter()
+            .map(|p| {
+                let file_type = self.symlink_metadata(&p)?.file_type;
+                Ok((p, file_type))
+            })
+            .collect()
+    }
+
+    /// Returns metadata for `path`, following symlinks
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// Returns metadata for `path` itself, without following symlinks
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata>;
+
+    /// Resolves `path` to an absolute path with all symlinks followed
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf>;
+
+    /// Reads up to `len` leading bytes of the file at `path`
+    ///
+    /// Used by the `content_prefix` predicate. The default implementation
+    /// reports `Unsupported`, so such files never match.
+    fn read_prefix(&self, _path: &Path, _len: usize) -> io::Result<Vec<u8>> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    /// Reads the extended attribute `name` of `path`, following symlinks
+    ///
+    /// Returns `None` when the attribute isn't set. Used by the
+    /// `has_xattr` predicate. The default implementation reports
+    /// `Unsupported`, so such entries never match.
+    fn read_xattr(&self, _path: &Path, _name: &str) -> io::Result<Option<Vec<u8>>> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+
+    /// Reads the extended attribute `name` of `path` itself, without
+    /// following symlinks
+    ///
+    /// Used by the `has_xattr` predicate when the walk doesn't follow
+    /// symlinks. The default implementation calls
+    /// [`FileSystem::read_xattr`], which only differs for symlinks.
+    fn read_symlink_xattr(&self, path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        self.read_xattr(path, name)
+    }
+
+    /// Checks whether the current user can read `path`, following symlinks
+    ///
+    /// Used by the `readable` predicate. The default implementation
+    /// returns an `Unsupported` error, handled per `GlobOptions::on_error`.
+    fn is_readable(&self, _path: &Path) -> io::Result<bool> {
+        Err(io::Error::from(io::ErrorKind::Unsupported))
+    }
+}
+
+/// [`FileSystem`] implementation backed by `std::fs`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect()
+    }
+
+    fn read_dir_with_types(&self, path: &Path) -> io::Result<Vec<(PathBuf, FileType)>> {
+        std::fs::read_dir(path)?
+            .map(|entry| {
+                let entry = entry?;
+                // The type comes from the listing itself on most platforms
+                let ft = entry.file_type()?;
+                let file_type = if ft.is_symlink() {
+                    FileType::Symlink
+                } else if ft.is_dir() {
+                    FileType::Dir
+                } else {
+                    FileType::File
+                };
+                Ok((entry.path(), file_type))
+            })
+            .collect()
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        std::fs::metadata(path).map(|m| FsMetadata::from(&m))
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        std::fs::symlink_metadata(path).map(|m| FsMetadata::from(&m))
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::canonicalize(path)
+    }
+
+    fn read_prefix(&self, path: &Path, len: usize) -> io::Result<Vec<u8>> {
+        read_prefix(path, len)
+    }
+
+    fn read_xattr(&self, path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        read_xattr(path, name, true)
+    }
+
+    fn read_symlink_xattr(&self, path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        read_xattr(path, name, false)
+    }
+
+    fn is_readable(&self, path: &Path) -> io::Result<bool> {
+        Ok(is_readable(path))
+    }
+}
+
+/// Reads up to `len` leading bytes of the file at `path`
+///
+/// Shorter files yield all their bytes; nothing past `len` is read.
+pub(crate) fn read_prefix(path: &Path, len: usize) -> io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut bytes = Vec::with_capacity(len);
+    std::fs::File::open(path)?
+        .take(len as u64)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Checks whether the current user can read `path`, following symlinks
+///
+/// Asks the kernel with `access(2)`, which honours ACLs and ownership
+/// without opening the entry.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub(crate) fn is_readable(path: &Path) -> bool {
+    rustix::fs::access(path, rustix::fs::Access::READ_OK).is_ok()
+}
+
+/// Elsewhere the entry is opened: listed if a directory, otherwise read
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub(crate) fn is_readable(path: &Path) -> bool {
+    if path.is_dir() {
+        std::fs::read_dir(path).is_ok()
+    } else {
+        std::fs::File::open(path).is_ok()
+    }
+}
+
+/// Reads the extended attribute `name` of `path`
+///
+/// A symlink's own attributes are read unless `follow` is set.
+///
+/// # Returns
+///
+/// `Ok(Some(value))`, `Ok(None)` if the attribute isn't set, or
+/// `Err` if the path can't be read
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub(crate) fn read_xattr(path: &Path, name: &str, follow: bool) -> io::Result<Option<Vec<u8>>> {
+    use rustix::{
+        fs::{getxattr, lgetxattr},
+        io::Errno,
+    };
+    #[cfg(target_vendor = "apple")]
+    const NO_ATTR: Errno = Errno::NOATTR;
+    #[cfg(not(target_vendor = "apple"))]
+    const NO_ATTR: Errno = Errno::NODATA;
+
+    let read = |buf: &mut [u8]| {
+        if follow {
+            getxattr(path, name, buf)
+        } else {
+            lgetxattr(path, name, buf)
+        }
+    };
+    loop {
+        // An empty buffer asks for the size; the value may grow in between
+        let size = match read(&mut []) {
+            Ok(size) => size,
+            Err(e) if e == NO_ATTR => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut value = vec![0; size];
+        match read(&mut value) {
+            Ok(len) => {
+                value.truncate(len);
+                return Ok(Some(value));
+            }
+            Err(e) if e == Errno::RANGE => continue,
+            Err(e) if e == NO_ATTR => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Extended attributes are only read on Linux, Android and Apple platforms
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub(crate) fn read_xattr(_path: &Path, _name: &str, _follow: bool) -> io::Result<Option<Vec<u8>>> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// Expands a leading `~` to the current user's home directory
+///
+/// See [`expand_tilde_with`]; the home directory is taken from the
+/// environment.
+pub(crate) fn expand_tilde(s: &str) -> std::borrow::Cow<'_, str> {
+    expand_tilde_with(s, home_dir().as_deref())
+}
+
+/// Expands a leading `~` to `home`
+///
+/// Only `~` alone or followed by a separator is expanded. Other users'
+/// home directories (`~user`) are not looked up, so such strings are
+/// returned unchanged, as are strings that don't start with `~` and all
+/// strings when `home` is `None` or isn't valid UTF-8.
+pub(crate) fn expand_tilde_with<'a>(s: &'a str, home: Option<&Path>) -> std::borrow::Cow<'a, str> {
+    let Some(tail) = s.strip_prefix('~') else {
+        return s.into();
+    };
+    if tail
+        .chars()
+        .next()
+        .is_some_and(|c| !std::path::is_separator(c))
+    {
+        return s.into();
+    }
+    match home.and_then(Path::to_str) {
+        Some(home) => format!("{}{}", home.trim_end_matches(['/', '\\']), tail).into(),
+        None => s.into(),
+    }
+}
+
+/// Returns the current user's home directory from the environment
+///
+/// `HOME` on Unix, `USERPROFILE` on Windows; empty values count as unset.
+fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(var)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Substitutes environment variables in a root string
+///
+/// See [`expand_env_with`]; variables are read from the process
+/// environment, and ones that aren't valid UTF-8 count as unset.
+pub(crate) fn expand_env(
+    s: &str,
+    on_undefined: OnUndefinedEnv,
+) -> Result<std::borrow::Cow<'_, str>, GlobError> {
+    expand_env_with(s, on_undefined, |name| std::env::var(name).ok())
+}
+
+/// Substitutes variables looked up with `var` in a root string
+///
+/// Unix roots use `$VAR` and `${VAR}`, Windows roots `%VAR%`. A `$` or `%`
+/// that doesn't start a variable reference is kept as written.
+///
+/// # Arguments
+///
+/// * `s` - Root string to expand
+/// * `on_undefined` - What to do with variables `var` returns `None` for
+/// * `var` - Looks up a variable's value by name
+///
+/// # Returns
+///
+/// The expanded string, borrowed if it references no variables
+///
+/// # Errors
+///
+/// Returns `GlobError::UndefinedEnvVar` for an unset variable under
+/// `OnUndefinedEnv::Error`
+pub(crate) fn expand_env_with<V>(
+    s: &str,
+    on_undefined: OnUndefinedEnv,
+    var: V,
+) -> Result<std::borrow::Cow<'_, str>, GlobError>
+where
+    V: Fn(&str) -> Option<String>,
+{
+    let lookup = |name: &str| match var(name) {
+        Some(value) => Ok(value),
+        None if on_undefined == OnUndefinedEnv::Empty => Ok(String::new()),
+        None => Err(GlobError::UndefinedEnvVar(name.to_string())),
+    };
+    if cfg!(windows) {
+        expand_percent_vars(s, lookup)
+    } else {
+        expand_dollar_vars(s, lookup)
+    }
+}
+
+/// Expands `$VAR` and `${VAR}` references
+fn expand_dollar_vars<F>(s: &str, lookup: F) -> Result<std::borrow::Cow<'_, str>, GlobError>
+where
+    F: Fn(&str) -> Result<String, GlobError>,
+{
+    if !s.contains('$') {
+        return Ok(s.into());
+    }
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let braced = after
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'))
+            .filter(|(name, _)| !name.is_empty() && name.chars().all(is_name));
+        let (name, tail) = match braced {
+            Some((name, tail)) => (name, tail),
+            None if after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                after.split_at(after.find(|c| !is_name(c)).unwrap_or(after.len()))
+            }
+            None => {
+                out.push('$');
+                rest = after;
+                continue;
+            }
+        };
+        out.push_str(&lookup(name)?);
+        rest = tail;
+    }
+    out.push_str(rest);
+    Ok(out.into())
+}
+
+/// Expands `%VAR%` references
+fn expand_percent_vars<F>(s: &str, lookup: F) -> Result<std::borrow::Cow<'_, str>, GlobError>
+where
+    F: Fn(&str) -> Result<String, GlobError>,
+{
+    if !s.contains('%') {
+        return Ok(s.into());
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        match after.split_once('%') {
+            Some((name, tail)) if !name.is_empty() && !name.contains(['/', '\\']) => {
+                out.push_str(&lookup(name)?);
+                rest = tail;
+            }
+            _ => {
+                out.push('%');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tilde_with_home() {
+        let home = Some(Path::new("/home/me"));
+        assert_eq!(expand_tilde_with("~", home), "/home/me");
+        assert_eq!(expand_tilde_with("~/src/*.rs", home), "/home/me/src/*.rs");
+        assert_eq!(
+            expand_tilde_with("~/x", Some(Path::new("/home/me/"))),
+            "/home/me/x"
+        );
+        assert_eq!(expand_tilde_with("src/~/x", home), "src/~/x");
+        assert_eq!(expand_tilde_with("~other/x", home), "~other/x");
+        assert_eq!(expand_tilde_with("~/x", None), "~/x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_env_with_lookup() {
+        let var = |name: &str| (name == "BASE").then(|| "/srv".to_string());
+        let expand = |s, policy| expand_env_with(s, policy, var).map(|s| s.into_owned());
+
+        for root in ["$BASE/logs", "${BASE}/logs"] {
+            assert_eq!(expand(root, OnUndefinedEnv::Error).unwrap(), "/srv/logs");
+        }
+        assert_eq!(expand("a$/b$1", OnUndefinedEnv::Error).unwrap(), "a$/b$1");
+        assert!(matches!(
+            expand("$UNSET/logs", OnUndefinedEnv::Error),
+            Err(GlobError::UndefinedEnvVar(ref name)) if name == "UNSET"
+        ));
+        assert_eq!(
+            expand("${UNSET}logs", OnUndefinedEnv::Empty).unwrap(),
+            "logs"
+        );
+    }
+}
@@ -4,19 +4,35 @@
 #[cfg(feature = "async")]
 pub mod async_glob;
 pub mod batch_io;
+pub mod entry;
 pub mod error;
+pub mod fs;
 pub mod options;
 pub mod patterns;
 pub mod predicates;
+pub mod stats;
 pub mod sync;
+mod walker;
 pub mod windows;
 
+pub use crate::entry::GlobEntry;
 pub use crate::error::GlobError;
-pub use crate::options::{GlobOptions, GlobOptionsBuilder};
+pub use crate::fs::{FileSystem, FsMetadata, RealFileSystem};
+pub use crate::options::{
+    ErrorPolicy, GlobOptions, GlobOptionsBuilder, MatchMode, OnNonUtf8, OnUndefinedEnv,
+    PathTraversalPolicy, RegexLimits, TimeoutScope, TraversalOrder,
+};
 pub use crate::patterns::Patterns;
-pub use crate::predicates::Predicates;
+pub use crate::predicates::{Predicates, PredicatesBuilder};
+pub use crate::stats::GlobStats;
 
-use std::path::PathBuf;
+use crate::sync::first_canonical;
+use std::{
+    collections::HashSet,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 /// Main facade for the PatternHunt library
 ///
@@ -28,7 +44,16 @@ impl PatternHunt {
     /// Performs synchronous glob pattern matching
     ///
     /// This method searches for files matching the specified patterns
-    /// in the given root directories, with configurable options.
+    /// in the given root directories, with configurable options. Relative
+    /// roots are resolved against `opts.root_dir` as described in
+    /// [`GlobOptions::for_root`], and results are concatenated in root order.
+    /// With `opts.dedupe_by_canonical`, a file already returned under
+    /// another path, from any root, is skipped.
+    ///
+    /// Every root is checked before the walk starts: one that doesn't exist
+    /// or isn't a directory fails with `GlobError::RootNotFound`, or is
+    /// skipped under `opts.skip_missing_roots`. Streams yield that error
+    /// as their only item.
     ///
     /// # Arguments
     ///
@@ -58,14 +83,642 @@ impl PatternHunt {
         opts: GlobOptions,
     ) -> Result<Vec<PathBuf>, GlobError> {
         let pats = Patterns::compile_many(patterns, &opts)?;
+        Self::sync_compiled(&pats, roots, opts)
+    }
+
+    /// Performs synchronous glob matching with patterns and roots from any iterable
+    ///
+    /// Works like [`PatternHunt::sync`], but accepts owned or borrowed
+    /// strings, so patterns built at runtime in a `Vec<String>` need no
+    /// intermediate `Vec<&str>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Pattern strings to match
+    /// * `roots` - Root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use patternhunt::{PatternHunt, GlobOptions};
+    ///
+    /// let patterns: Vec<String> = ["txt", "md"].iter().map(|e| format!("*.{}", e)).collect();
+    /// let results = PatternHunt::sync_with(patterns, ["."], GlobOptions::default()).unwrap();
+    /// ```
+    pub fn sync_with<P, R>(
+        patterns: P,
+        roots: R,
+        opts: GlobOptions,
+    ) -> Result<Vec<PathBuf>, GlobError>
+    where
+        P: IntoIterator,
+        P::Item: AsRef<str>,
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+    {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let roots: Vec<R::Item> = roots.into_iter().collect();
+        let roots: Vec<&str> = roots.iter().map(AsRef::as_ref).collect();
+        Self::sync_compiled(&pats, &roots, opts)
+    }
+
+    /// Performs synchronous glob matching with patterns read from a file
+    ///
+    /// The file lists one pattern per line, with `#` comments and `!`
+    /// exclusions; see [`Patterns::compile_from_file`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern_file` - Patterns file to read
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+    pub fn sync_from_file(
+        pattern_file: &Path,
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<Vec<PathBuf>, GlobError> {
+        let pats = Patterns::compile_from_file(pattern_file, &opts)?;
+        Self::sync_compiled(&pats, roots, opts)
+    }
+
+    /// Performs synchronous glob matching, keeping files modified since a time
+    ///
+    /// Works like [`PatternHunt::sync`] with an `mtime_after` predicate
+    /// merged into `opts.predicates`, so a re-scan only stats entries whose
+    /// path matches and returns those modified at or after `since`. An
+    /// existing, later `mtime_after` still applies.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `since` - Earliest modification time to return
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with new or modified paths, or `Err(GlobError)`
+    /// on failure
+    pub fn sync_since(
+        patterns: &[&str],
+        roots: &[&str],
+        mut opts: GlobOptions,
+        since: SystemTime,
+    ) -> Result<Vec<PathBuf>, GlobError> {
+        let mut preds = opts.predicates.take().unwrap_or_default();
+        preds.mtime_after = Some(preds.mtime_after.map_or(since, |t| t.max(since)));
+        opts.predicates = Some(preds);
+        Self::sync(patterns, roots, opts)
+    }
+
+    /// Re-scans for changes since a previous run
+    ///
+    /// Returns the paths [`PatternHunt::sync_since`] finds, plus the paths
+    /// of `previous` (the result of an earlier scan) that no longer exist.
+    /// Files moved into the tree keep their old modification time, so they
+    /// are only reported if it is at or after `since`.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `since` - Time of the previous scan
+    /// * `previous` - Paths returned by the previous scan
+    ///
+    /// # Returns
+    ///
+    /// `Ok((changed, removed))`, or `Err(GlobError)` on failure
+    pub fn sync_changes(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        since: SystemTime,
+        previous: &[PathBuf],
+    ) -> Result<(Vec<PathBuf>, Vec<PathBuf>), GlobError> {
+        let changed = Self::sync_since(patterns, roots, opts, since)?;
+        let removed = previous
+            .iter()
+            .filter(|p| std::fs::symlink_metadata(p).is_err())
+            .cloned()
+            .collect();
+        Ok((changed, removed))
+    }
+
+    /// Performs synchronous glob matching with already compiled patterns
+    ///
+    /// Works like [`PatternHunt::sync`], but skips compilation so one
+    /// [`Patterns`] can be reused across calls. The compile-time options
+    /// (case sensitivity, match mode, ...) are those the patterns were
+    /// built with; `opts` only governs traversal.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Compiled patterns to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+    pub fn sync_compiled(
+        patterns: &Patterns,
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<Vec<PathBuf>, GlobError> {
         let preds = opts.predicates.clone();
         let mut results = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
 
         // Process each root directory
-        for r in roots {
-            let _root = std::path::Path::new(r);
-            let mut v = crate::sync::glob_sync(pats.clone(), opts.clone(), preds.clone())?;
-            results.append(&mut v);
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let v = crate::sync::glob_sync(patterns.clone(), opts.clone(), preds.clone())?;
+            results.extend(
+                v.into_iter()
+                    .filter(|p| first_canonical(&mut seen, &RealFileSystem, p)),
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Performs synchronous glob matching, keeping results grouped by root
+    ///
+    /// Works like [`PatternHunt::sync`], but each root's matches are kept
+    /// apart, tagged with the root as resolved against `opts.root_dir`.
+    /// Roots skipped under `opts.skip_missing_roots` have no group; a root
+    /// without matches has an empty one.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<(PathBuf, Vec<PathBuf>)>)` with each root and its matches,
+    /// in root order, or `Err(GlobError)` on failure
+    pub fn sync_grouped(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<Vec<(PathBuf, Vec<PathBuf>)>, GlobError> {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut groups = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let root = opts.walk_root();
+            let v = crate::sync::glob_sync(pats.clone(), opts, preds.clone())?;
+            let v = v
+                .into_iter()
+                .filter(|p| first_canonical(&mut seen, &RealFileSystem, p));
+            groups.push((root, v.collect()));
+        }
+
+        Ok(groups)
+    }
+
+    /// Performs synchronous glob pattern matching, returning rich entries
+    ///
+    /// Works like [`PatternHunt::sync`], but each result carries the depth
+    /// at which it was found, its file type and, when predicates forced a
+    /// stat, its metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<GlobEntry>)` with matching entries, or `Err(GlobError)` on failure
+    pub fn sync_entries(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<Vec<GlobEntry>, GlobError> {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut results = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let v = crate::sync::glob_sync_entries(pats.clone(), opts.clone(), preds.clone())?;
+            results.extend(
+                v.into_iter()
+                    .filter(|e| first_canonical(&mut seen, &RealFileSystem, &e.path)),
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Performs synchronous glob pattern matching, writing entries as NDJSON
+    ///
+    /// Runs [`PatternHunt::sync_entries`] and writes each match to `writer`
+    /// as one JSON object per line, in the shape described on
+    /// [`GlobEntry`]'s `Serialize` implementation. Requires the `serde`
+    /// feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `writer` - Destination for the JSON lines
+    ///
+    /// # Returns
+    ///
+    /// `Ok(usize)` with the number of entries written, or `Err(GlobError)`
+    /// on failure
+    #[cfg(feature = "serde")]
+    pub fn sync_ndjson<W: std::io::Write>(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        writer: W,
+    ) -> Result<usize, GlobError> {
+        let entries = Self::sync_entries(patterns, roots, opts)?;
+        crate::entry::write_ndjson(entries, writer)
+    }
+
+    /// Performs synchronous glob pattern matching, writing paths NUL-separated
+    ///
+    /// Runs [`PatternHunt::sync`] and writes each match to `writer`
+    /// followed by a NUL byte, ready for `xargs -0`. On Unix the raw
+    /// `OsStr` bytes are written, so paths that aren't valid UTF-8 come
+    /// through unchanged; elsewhere paths are written lossily as UTF-8.
+    /// Such paths are only matched with `opts.non_utf8` set to
+    /// [`OnNonUtf8::Lossy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `writer` - Destination for the paths
+    ///
+    /// # Returns
+    ///
+    /// `Ok(usize)` with the number of paths written, or `Err(GlobError)`
+    /// on failure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use patternhunt::{PatternHunt, GlobOptions};
+    ///
+    /// let mut out = Vec::new();
+    /// PatternHunt::write_paths_nul(&["*.toml"], &["."], GlobOptions::default(), &mut out)
+    ///     .unwrap();
+    /// ```
+    pub fn write_paths_nul<W: std::io::Write>(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        mut writer: W,
+    ) -> Result<usize, GlobError> {
+        let paths = Self::sync(patterns, roots, opts)?;
+        for path in &paths {
+            #[cfg(unix)]
+            writer.write_all(std::os::unix::ffi::OsStrExt::as_bytes(path.as_os_str()))?;
+            #[cfg(not(unix))]
+            writer.write_all(path.to_string_lossy().as_bytes())?;
+            writer.write_all(b"\0")?;
+        }
+        Ok(paths.len())
+    }
+
+    /// Performs synchronous glob pattern matching, reporting tolerated errors
+    ///
+    /// Works like [`PatternHunt::sync`]. With `opts.on_error` set to
+    /// [`ErrorPolicy::Collect`], per-entry failures such as an unreadable
+    /// directory are returned next to the results instead of ending the walk.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok((paths, errors))`, or `Err(GlobError)` if the walk was aborted
+    pub fn sync_with_errors(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<(Vec<PathBuf>, Vec<GlobError>), GlobError> {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let (v, mut e) = crate::sync::glob_sync_collect(&pats, &opts, preds.as_ref())?;
+            results.extend(
+                v.into_iter()
+                    .filter(|p| first_canonical(&mut seen, &RealFileSystem, p)),
+            );
+            errors.append(&mut e);
+        }
+
+        Ok((results, errors))
+    }
+
+    /// Performs synchronous glob pattern matching and reports statistics
+    ///
+    /// Works like [`PatternHunt::sync`], additionally counting directories
+    /// visited, entries examined, stat calls and matches. The returned
+    /// [`GlobStats`] also carries a snapshot of the global cache metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok((Vec<PathBuf>, GlobStats))` with matching paths and counters,
+    /// or `Err(GlobError)` on failure
+    pub fn sync_with_stats(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<(Vec<PathBuf>, GlobStats), GlobError> {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut stats = GlobStats::default();
+        let mut results = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let _ = crate::sync::glob_sync_visit_with_stats(
+                &pats,
+                &opts,
+                preds.as_ref(),
+                &mut stats,
+                |e| {
+                    if first_canonical(&mut seen, &RealFileSystem, &e.path) {
+                        results.push(e.path);
+                    }
+                    ControlFlow::Continue(())
+                },
+            )?;
+        }
+
+        let (glob_cache, regex_cache) = crate::patterns::cache_metrics();
+        stats.glob_cache = glob_cache;
+        stats.regex_cache = regex_cache;
+        Ok((results, stats))
+    }
+
+    /// Performs synchronous glob pattern matching with progress updates
+    ///
+    /// Works like [`PatternHunt::sync_with_stats`], and `progress` observes
+    /// the running [`GlobStats`] every few hundred entries or 100
+    /// milliseconds, plus once when each root finishes. Cache metrics are
+    /// only filled in the returned stats.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `progress` - Callback receiving the counters so far
+    ///
+    /// # Returns
+    ///
+    /// `Ok((Vec<PathBuf>, GlobStats))` with matching paths and final
+    /// counters, or `Err(GlobError)` on failure
+    pub fn sync_with_progress<P>(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        mut progress: P,
+    ) -> Result<(Vec<PathBuf>, GlobStats), GlobError>
+    where
+        P: FnMut(&GlobStats),
+    {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut stats = GlobStats::default();
+        let mut results = Vec::new();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let _ = crate::sync::glob_sync_visit_with_progress(
+                &pats,
+                &opts,
+                preds.as_ref(),
+                &mut stats,
+                &mut progress,
+                |e| {
+                    if first_canonical(&mut seen, &RealFileSystem, &e.path) {
+                        results.push(e.path);
+                    }
+                    ControlFlow::Continue(())
+                },
+            )?;
+        }
+
+        let (glob_cache, regex_cache) = crate::patterns::cache_metrics();
+        stats.glob_cache = glob_cache;
+        stats.regex_cache = regex_cache;
+        Ok((results, stats))
+    }
+
+    /// Performs synchronous glob pattern matching over a custom filesystem
+    ///
+    /// Works like [`PatternHunt::sync`], but every directory listing and
+    /// metadata lookup goes through `fs`. Pass [`RealFileSystem`] for the
+    /// host filesystem, or an in-memory implementation in tests.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `fs` - Filesystem to traverse
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+    pub fn sync_with_fs(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        fs: &dyn FileSystem,
+    ) -> Result<Vec<PathBuf>, GlobError> {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut results = Vec::new();
+
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, fs)? {
+            let v = crate::sync::glob_sync_fs(&pats, &opts, preds.as_ref(), fs)?;
+            results.extend(v.into_iter().filter(|p| first_canonical(&mut seen, fs, p)));
+        }
+
+        Ok(results)
+    }
+
+    /// Walks synchronously and invokes a callback for every match
+    ///
+    /// Unlike [`PatternHunt::sync`], matches are not collected; `f` is
+    /// called with each matching path as it is found. Returning
+    /// `ControlFlow::Break(())` from the callback stops the traversal.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    /// * `f` - Callback invoked with each matching path
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` when the walk finished or was stopped by the callback,
+    /// or `Err(GlobError)` on failure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use patternhunt::{PatternHunt, GlobOptions};
+    /// use std::ops::ControlFlow;
+    ///
+    /// let mut first = None;
+    /// PatternHunt::for_each(&["**/*.rs"], &["."], GlobOptions::default(), |p| {
+    ///     first = Some(p.to_path_buf());
+    ///     ControlFlow::Break(())
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn for_each<F>(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+        mut f: F,
+    ) -> Result<(), GlobError>
+    where
+        F: FnMut(&Path) -> ControlFlow<()>,
+    {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let preds = opts.predicates.clone();
+        let mut seen = opts.dedupe_by_canonical.then(HashSet::new);
+
+        for opts in opts.for_roots(roots, &RealFileSystem)? {
+            let flow = crate::sync::glob_sync_visit(&pats, &opts, preds.as_ref(), |e| {
+                if first_canonical(&mut seen, &RealFileSystem, &e.path) {
+                    f(&e.path)
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })?;
+            if flow.is_break() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts matching paths without collecting them
+    ///
+    /// Runs the same traversal and predicate filtering as
+    /// [`PatternHunt::sync`], built on [`PatternHunt::for_each`], so no
+    /// result `Vec` is allocated.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(usize)` with the number of matches, or `Err(GlobError)` on failure
+    pub fn count(patterns: &[&str], roots: &[&str], opts: GlobOptions) -> Result<usize, GlobError> {
+        let mut count = 0;
+        Self::for_each(patterns, roots, opts, |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        })?;
+        Ok(count)
+    }
+
+    /// Filters an in-memory list of paths, without touching the filesystem
+    ///
+    /// The patterns are compiled and matched exactly as in [`PatternHunt::sync`],
+    /// but against the supplied paths (for example a tar index or a git tree
+    /// listing). Only predicates that need no metadata, such as `path_glob`,
+    /// are applied; size, time, type and ownership predicates are ignored.
+    /// With `patterns_relative_to_root`, the `root_dir` prefix is stripped
+    /// from each path before matching.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `paths` - Paths to filter
+    /// * `opts` - Configuration options for matching
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with the matching paths in input order,
+    /// or `Err(GlobError)` on failure
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use patternhunt::{PatternHunt, GlobOptions};
+    /// use std::path::PathBuf;
+    ///
+    /// let paths = vec![PathBuf::from("src/lib.rs"), PathBuf::from("README.md")];
+    /// let rust = PatternHunt::filter_paths(&["**/*.rs"], paths, GlobOptions::default()).unwrap();
+    /// assert_eq!(rust, vec![PathBuf::from("src/lib.rs")]);
+    /// ```
+    pub fn filter_paths<I>(
+        patterns: &[&str],
+        paths: I,
+        opts: GlobOptions,
+    ) -> Result<Vec<PathBuf>, GlobError>
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let pats = Patterns::compile_many(patterns, &opts)?;
+        let mut results = Vec::new();
+
+        let root = opts.root_dir.clone().unwrap_or_default();
+        for path in paths {
+            let target = crate::sync::match_target(&path, &root, &opts);
+            let Some(path_str) = crate::sync::path_for_matching(target, opts.non_utf8)? else {
+                continue;
+            };
+            if !pats.matches_str(&path_str) {
+                continue;
+            }
+            if let Some(preds) = &opts.predicates {
+                if !preds.matches_path_only(&path) {
+                    continue;
+                }
+            }
+            results.push(path);
         }
 
         Ok(results)
@@ -85,23 +738,188 @@ impl PatternHunt {
     /// # Returns
     ///
     /// `Ok(impl Stream<Item = Result<PathBuf, GlobError>>)` on success,
-    /// or `Err(GlobError)` if pattern compilation fails
-    ///
-    /// # Note
-    ///
-    /// Currently supports single-root operations. For multiple roots,
-    /// consumers should call this method for each root directory.
+    /// or `Err(GlobError)` if pattern compilation fails. Roots are walked
+    /// one after another, in the order given.
     #[cfg(feature = "async")]
     pub fn stream(
         patterns: &[&str],
-        _roots: &[&str],
+        roots: &[&str],
         opts: GlobOptions,
     ) -> Result<impl futures::Stream<Item = Result<PathBuf, GlobError>>, GlobError> {
         let pats = Patterns::compile_many(patterns, &opts)?;
+        Ok(Self::stream_compiled(&pats, roots, opts))
+    }
+
+    /// Creates a stream of results using already compiled patterns
+    ///
+    /// Works like [`PatternHunt::stream`], but skips compilation so one
+    /// [`Patterns`] can be reused across calls. `opts` only governs
+    /// traversal; the patterns keep the options they were compiled with.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Compiled patterns to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// A stream yielding `Result<PathBuf, GlobError>` values. If a root is
+    /// missing, its `GlobError::RootNotFound` is the only item.
+    #[cfg(feature = "async")]
+    pub fn stream_compiled(
+        patterns: &Patterns,
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> impl futures::Stream<Item = Result<PathBuf, GlobError>> {
+        use futures::StreamExt;
+
         let preds = opts.predicates.clone();
+        let patterns = patterns.clone();
+        // A missing root is the only item, so no root is walked, as in
+        // the sync facades; the collecting facades return it as an error
+        let (per_root, preflight) = match opts.for_roots(roots, &RealFileSystem) {
+            Ok(per_root) => (Some(per_root), None),
+            Err(e) => (None, Some(Err(e))),
+        };
+        let dedupe = opts.dedupe_by_canonical;
+        let mut seen = HashSet::new();
+
+        futures::stream::iter(preflight)
+            .chain(
+                futures::stream::iter(per_root.into_iter().flatten()).flat_map(move |opts| {
+                    crate::async_glob::glob_stream(patterns.clone(), opts, preds.clone())
+                }),
+            )
+            // Canonicalizing blocks, so it runs on tokio's blocking pool
+            .then(move |item| async move {
+                let canonical = match &item {
+                    Ok(path) if dedupe => Some(
+                        tokio::fs::canonicalize(path)
+                            .await
+                            .unwrap_or_else(|_| path.clone()),
+                    ),
+                    _ => None,
+                };
+                (item, canonical)
+            })
+            .filter_map(move |(item, canonical)| {
+                let keep = canonical.is_none_or(|c| seen.insert(c));
+                futures::future::ready(keep.then_some(item))
+            })
+    }
+
+    /// Collects all asynchronous matches into a `Vec`
+    ///
+    /// The async counterpart of [`PatternHunt::sync`]: drives the stream
+    /// from [`PatternHunt::stream`] to completion. Errors yielded by the
+    /// stream are handled per `opts.on_error`: [`ErrorPolicy::Abort`]
+    /// returns the first one, [`ErrorPolicy::Skip`] drops them. Use
+    /// [`PatternHunt::async_collect_with_errors`] to receive collected
+    /// errors. A timeout, a cancellation or a missing root is always
+    /// returned as an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+    #[cfg(feature = "async")]
+    pub async fn async_collect(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<Vec<PathBuf>, GlobError> {
+        Self::async_collect_with_errors(patterns, roots, opts)
+            .await
+            .map(|(results, _)| results)
+    }
+
+    /// Collects all asynchronous matches along with tolerated errors
+    ///
+    /// Works like [`PatternHunt::async_collect`]; under
+    /// [`ErrorPolicy::Collect`] the errors yielded by the stream are
+    /// returned next to the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok((paths, errors))`, or `Err(GlobError)` if collection was aborted
+    #[cfg(feature = "async")]
+    pub async fn async_collect_with_errors(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<(Vec<PathBuf>, Vec<GlobError>), GlobError> {
+        use futures::StreamExt;
+
+        let policy = opts.on_error;
+        let stream = Self::stream(patterns, roots, opts)?;
+        futures::pin_mut!(stream);
+
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(path) => results.push(path),
+                Err(e) if e.ends_glob() => return Err(e),
+                Err(e) => match policy {
+                    ErrorPolicy::Abort => return Err(e),
+                    ErrorPolicy::Skip => {}
+                    ErrorPolicy::Collect => errors.push(e),
+                },
+            }
+        }
+
+        Ok((results, errors))
+    }
+
+    /// Counts asynchronous matches without collecting them
+    ///
+    /// The async counterpart of [`PatternHunt::count`]. Stream errors are
+    /// handled as in [`PatternHunt::async_collect`], except that
+    /// [`ErrorPolicy::Collect`] drops them like [`ErrorPolicy::Skip`].
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Array of pattern strings to match
+    /// * `roots` - Array of root directories to search in
+    /// * `opts` - Configuration options for globbing
+    ///
+    /// # Returns
+    ///
+    /// `Ok(usize)` with the number of matches, or `Err(GlobError)` on failure
+    #[cfg(feature = "async")]
+    pub async fn count_async(
+        patterns: &[&str],
+        roots: &[&str],
+        opts: GlobOptions,
+    ) -> Result<usize, GlobError> {
+        use futures::StreamExt;
+
+        let policy = opts.on_error;
+        let stream = Self::stream(patterns, roots, opts)?;
+        futures::pin_mut!(stream);
+
+        let mut count = 0;
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(_) => count += 1,
+                Err(e) if e.ends_glob() => return Err(e),
+                Err(e) if policy == ErrorPolicy::Abort => return Err(e),
+                Err(_) => {}
+            }
+        }
 
-        // Simple single-root support for facade
-        // Consumer can call for each root if needed
-        Ok(crate::async_glob::glob_stream(pats, opts, preds))
+        Ok(count)
     }
 }
@@ -1,33 +1,678 @@
 // options.rs
-use crate::predicates::Predicates;
-use std::{path::PathBuf, time::Duration};
+use crate::{
+    batch_io::BatchIO,
+    error::GlobError,
+    fs::FileSystem,
+    predicates::{FileType, Predicates},
+};
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Controls which part of a candidate path patterns are matched against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// Match every pattern against the full path string
+    #[default]
+    FullPath,
+    /// Match patterns without a `/` against the file name only
+    Basename,
+    /// Match patterns without a `/` against both the full path and the file name
+    Both,
+}
+
+/// How patterns containing `..` segments are handled at compile time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PathTraversalPolicy {
+    /// Resolve `..` segments, rejecting patterns that would escape the root
+    #[default]
+    Reject,
+    /// Resolve `..` segments, dropping any that would escape the root
+    Normalize,
+    /// Compile patterns exactly as written
+    Allow,
+}
+
+/// What to do with paths that are not valid UTF-8
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OnNonUtf8 {
+    /// Leave the path out of the results
+    #[default]
+    Skip,
+    /// Fail with `GlobError::NonUtf8Path`
+    Error,
+    /// Match against a lossy conversion, returning the original path
+    ///
+    /// Invalid sequences read as U+FFFD, which `?` and `*` match. Metadata,
+    /// content and other predicates still use the original path.
+    Lossy,
+}
+
+/// What root environment expansion does with a variable that isn't set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum OnUndefinedEnv {
+    /// Fail with `GlobError::UndefinedEnvVar` before walking
+    #[default]
+    Error,
+    /// Substitute an empty string, as a shell does
+    Empty,
+}
+
+/// What traversal does when a single entry fails
+///
+/// Covers unreadable directories, broken symlinks, symlink cycles and
+/// failed stats. Applies to synchronous traversal and to
+/// `PatternHunt::async_collect`. Timeouts always end the walk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorPolicy {
+    /// Stop the walk and return the error
+    #[default]
+    Abort,
+    /// Ignore the failing entry and keep walking
+    Skip,
+    /// Keep walking and report the errors alongside the results
+    Collect,
+}
+
+/// Whether `GlobOptions::timeout` covers all roots or each root separately
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TimeoutScope {
+    /// One deadline for the whole glob; later roots get what is left
+    Global,
+    /// Every root gets the full timeout, starting when its walk begins
+    #[default]
+    PerRoot,
+}
+
+/// Order in which asynchronous traversal visits directories
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TraversalOrder {
+    /// Finish each subdirectory before moving on to its siblings
+    ///
+    /// Memory grows with the depth of the tree, one open directory per level.
+    #[default]
+    DepthFirst,
+    /// Visit every directory at one depth before going deeper
+    ///
+    /// Memory grows with the breadth of the tree, as every directory of
+    /// the next level is queued.
+    BreadthFirst,
+}
+
+/// Limits applied to regexes before compilation
+///
+/// These guard against pathological patterns. `max_repetitions` bounds
+/// how deeply unbounded repetitions (`*`, `+`, `{n,}`) may nest, so the
+/// classic `(a+)+` shape is rejected by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RegexLimits {
+    /// Maximum regex length in bytes
+    pub max_len: usize,
+
+    /// Maximum number of groups, capturing or not
+    pub max_groups: usize,
+
+    /// Maximum nesting depth of unbounded repetitions
+    pub max_repetitions: usize,
+}
+
+impl Default for RegexLimits {
+    fn default() -> Self {
+        Self {
+            max_len: 1000,
+            max_groups: 1000,
+            max_repetitions: 1,
+        }
+    }
+}
+
+/// Outcome of charging a match against `max_total_bytes`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Spend {
+    /// Keep the match and continue
+    Keep,
+    /// Keep the match, then stop: the budget is used up
+    Last,
+    /// Drop the match and stop: it would exceed the budget
+    Over,
+}
 
 /// Configuration options for glob operations
 ///
 /// This struct allows fine-grained control over globbing behavior,
 /// including symlink handling, depth limits, and filtering predicates.
+///
+/// Options compare and hash by value, except `cancel` and `batch_io`, which
+/// compare by identity: two option sets are equal only if they share them.
 #[derive(Clone, Debug)]
 pub struct GlobOptions {
     /// Whether to follow symbolic links during traversal
     pub follow_symlinks: bool,
 
     /// Maximum directory depth to traverse (None for unlimited)
+    ///
+    /// Follows WalkDir's convention: the root is depth 0, so `Some(1)`
+    /// yields only entries directly inside the root.
     pub max_depth: Option<usize>,
 
+    /// Minimum depth an entry must be at to be returned (None for no minimum)
+    ///
+    /// Uses the same convention as `max_depth`; shallower directories are
+    /// still traversed, their entries just aren't candidates.
+    pub min_depth: Option<usize>,
+
+    /// Maximum number of symlinks followed along one branch (None for unlimited)
+    ///
+    /// Only meaningful with `follow_symlinks`. Counts symlinks between the
+    /// root and an entry, not directory levels: a symlink that would be
+    /// the `n + 1`th hop is skipped along with everything beneath it.
+    pub max_symlink_depth: Option<usize>,
+
+    /// Whether followed symlinks must resolve to a location inside the root
+    ///
+    /// Only meaningful with `follow_symlinks`: symlink targets are
+    /// canonicalized and skipped (not descended into) when they escape
+    /// the canonical root directory.
+    pub confine_to_root: bool,
+
     /// Whether to use case-sensitive matching
+    ///
+    /// When disabled, regex-backed patterns fold case using Unicode simple
+    /// case folding, while plain globs use globset's ASCII-only folding.
     pub case_sensitive: bool,
 
+    /// Whether case-insensitive matching should fold non-ASCII letters too
+    ///
+    /// Routes every glob through the regex engine so that pairs such as
+    /// `é`/`É` or `σ`/`Σ` compare equal. Only simple (one-to-one) folds are
+    /// applied: `ß` does not match `SS`. Has no effect when
+    /// `case_sensitive` is set.
+    pub unicode_case_fold: bool,
+
     /// Maximum number of concurrent operations for async globbing
     pub max_inflight: usize,
 
-    /// Timeout for individual operations
+    /// How long the async stream waits for a concurrency permit per entry
+    ///
+    /// Only entries matching the patterns wait for a permit, to evaluate
+    /// predicates and size. One whose permit doesn't come in time is
+    /// reported as `GlobError::PermitTimeout` instead of being evaluated.
+    /// The wait never extends past `timeout`.
+    pub permit_timeout: Duration,
+
+    /// Maximum number of directories held open at once; `None` is unbounded
+    ///
+    /// Each open directory holds a file descriptor, so this keeps deep
+    /// walks under low ulimits. A depth-first stream at the limit queues
+    /// further subdirectories and reads them once the open ones are done,
+    /// which changes the order but not the set of results. Synchronous
+    /// walks read each directory in full before descending into it, so
+    /// they never hold more than one open. Values below 1 count as 1.
+    pub max_open_dirs: Option<usize>,
+
+    /// Deadline for the walk; `None` never times out
+    ///
+    /// Depending on `timeout_scope`, it runs from the start of each root's
+    /// walk or is shared by all roots. `permit_timeout` waits never extend
+    /// past it. Once it passes, the walk stops with `GlobError::Timeout`,
+    /// whatever `on_error` says. Matches already yielded by a stream or
+    /// passed to a visitor stay delivered, but `glob_sync` and the other
+    /// collecting calls return only the error, dropping partial results.
     pub timeout: Option<Duration>,
 
+    /// Whether `timeout` is shared by all roots or applies to each one;
+    /// each root gets the full timeout by default
+    pub timeout_scope: TimeoutScope,
+
+    /// Flag that stops traversal early once set to `true`
+    ///
+    /// Set it from another thread, e.g. behind a "Stop" button; the walk
+    /// notices before the next entry and fails with `GlobError::Cancelled`.
+    pub cancel: Option<Arc<AtomicBool>>,
+
+    /// Combined size in bytes of matched files after which the walk stops
+    ///
+    /// Each matched file is stat'ed if predicates haven't already done so;
+    /// directories and symlinks count as zero bytes. The walk stops once
+    /// the budget is reached, and a file that would exceed it is dropped
+    /// unless `max_total_bytes_inclusive` is set. The budget applies to
+    /// each root separately, whatever `timeout_scope` says.
+    pub max_total_bytes: Option<u64>,
+
+    /// Whether the file that crosses `max_total_bytes` is still returned
+    pub max_total_bytes_inclusive: bool,
+
     /// Predicates for filtering files based on metadata
     pub predicates: Option<Predicates>,
 
+    /// Metadata cache shared with other globs, instead of a fresh one per run
+    ///
+    /// Lets a long-lived caller reuse stats across scans and invalidate
+    /// them with `BatchIO::clear_cache`. Compared by identity, like `cancel`.
+    pub batch_io: Option<Arc<BatchIO>>,
+
     /// Root directory to start globbing from
     pub root_dir: Option<PathBuf>,
+
+    /// Whether a leading `~` in roots and patterns means the home directory
+    ///
+    /// As in a shell, `~/projects` becomes the current user's
+    /// `projects` directory, found in `HOME` (`USERPROFILE` on Windows).
+    /// Other users' directories (`~user`) are not looked up. In patterns
+    /// only the leading prefix is expanded, which then has to match an
+    /// absolute candidate path.
+    pub expand_tilde: bool,
+
+    /// Whether environment variables in roots are substituted
+    ///
+    /// Unix roots use `$VAR` and `${VAR}`, Windows roots `%VAR%`.
+    /// Expansion happens before `~` expansion and before roots are checked.
+    pub expand_env: bool,
+
+    /// What `expand_env` does with variables that aren't set
+    pub on_undefined_env: OnUndefinedEnv,
+
+    /// Whether patterns are matched against paths relative to the root
+    ///
+    /// When set, the root prefix is stripped from each candidate before
+    /// matching, so `src/*.rs` finds `<root>/src/lib.rs` under every root.
+    /// Results are still returned with the prefix. The `path_glob`
+    /// predicate keeps seeing the full path.
+    pub patterns_relative_to_root: bool,
+
+    /// Whether a relative root is made absolute before walking
+    ///
+    /// The root, `.` when `root_dir` is unset, is joined onto the current
+    /// directory so results are absolute and don't depend on how the root
+    /// was spelled. Symlinks in the root are not resolved.
+    pub canonicalize_root: bool,
+
+    /// Whether a leading `./` is removed from returned paths
+    ///
+    /// Walking the root `.` otherwise returns paths like `./src/lib.rs`.
+    /// Patterns still see the `./` form; combine with
+    /// `patterns_relative_to_root` to match `src/*.rs` as well.
+    pub strip_dot_prefix: bool,
+
+    /// Whether a match is skipped when its canonical path was already returned
+    ///
+    /// Catches the same real file reached through overlapping roots or
+    /// symlinks, which plain path comparison misses. Costs one
+    /// `canonicalize` (a syscall per path component) per match, and keeps
+    /// every canonical path returned so far in memory.
+    pub dedupe_by_canonical: bool,
+
+    /// Whether results keep the Windows `\\?\` long-path prefix
+    ///
+    /// On Windows, absolute roots are walked through their `\\?\` form so
+    /// paths beyond MAX_PATH stay reachable. The prefix is always stripped
+    /// before matching; by default it is stripped from results too, so they
+    /// start with the root as given. Has no effect on other platforms.
+    pub keep_long_path_prefix: bool,
+
+    /// Whether symlinks are matched by their canonical target
+    ///
+    /// When set, patterns and the `path_glob` predicate see the resolved,
+    /// absolute target of each symlink entry instead of the link's own
+    /// path. Entries reached through a symlinked directory keep their
+    /// path. Broken links fall back to the link path.
+    pub match_resolved_path: bool,
+
+    /// Whether symlinks are returned as their canonical target
+    ///
+    /// Independent of `match_resolved_path`; useful to dedupe results by
+    /// real location.
+    pub return_resolved_path: bool,
+
+    /// Which part of the path slash-less patterns are matched against
+    pub match_mode: MatchMode,
+
+    /// Limits applied to regexes before compilation
+    pub regex_limits: RegexLimits,
+
+    /// Whether numeric braces are regex repetition quantifiers
+    ///
+    /// When set, a brace whose content is only `n`, `n,` or `n,m` (decimal
+    /// digits) repeats the preceding atom: `a{2}` matches `aa` and
+    /// `x{1,3}y` matches `xy` through `xxxy`. Any other brace, such as
+    /// `{txt,md}` or `{1..3}`, is still expanded as alternation. When unset,
+    /// every brace is alternation, so `x{1,3}y` means `x1y` or `x3y`.
+    pub regex_quantifier_braces: bool,
+
+    /// Whether patterns are matched literally, without glob syntax
+    ///
+    /// Every character, including `*`, `?`, `[`, `{` and `\`, stands for
+    /// itself, and `re:` prefixes are not recognized, so a list of exact
+    /// paths such as `file[1].txt` can be matched safely. `..` resolution,
+    /// case sensitivity, the match mode and a trailing `/` still apply.
+    pub literal: bool,
+
+    /// How `..` segments in patterns are handled
+    pub path_traversal: PathTraversalPolicy,
+
+    /// How paths that are not valid UTF-8 are handled
+    pub non_utf8: OnNonUtf8,
+
+    /// How traversal reacts to per-entry errors
+    pub on_error: ErrorPolicy,
+
+    /// Whether `\` in patterns and paths is treated as a `/` separator
+    ///
+    /// Enabled by default on Windows. Disable it to keep `\` as an escape
+    /// character, which is its meaning on other platforms. `re:` and `re~:`
+    /// patterns are never rewritten.
+    pub normalize_separators: bool,
+
+    /// Character that separates path segments, regardless of platform
+    ///
+    /// When set, `*`, `?` and `+` never match the separator; only a whole
+    /// segment `**` spans it. `Some('/')` suits S3 keys, URLs and ZIP
+    /// entries on any host; another character such as `:` is traded with
+    /// `/` in both patterns and candidates, so a literal `/` then matches
+    /// only itself. `re:` regexes see the candidate after that exchange.
+    /// Overrides `normalize_separators` and implies `literal_separator`.
+    pub separator: Option<char>,
+
+    /// Whether `*`, `?` and character classes stop at `/`
+    ///
+    /// By default `src/*.rs` also matches `src/a/b.rs`, as `*` crosses
+    /// separators. When set, only a whole-segment `**` spans directories,
+    /// for globs and extglobs alike.
+    pub literal_separator: bool,
+
+    /// Whether wildcards match a leading `.` in a segment
+    ///
+    /// Set by default. When unset, a `*`, `?`, `+`, `**` or extglob at the
+    /// start of a segment does not match a `.` there, as in bash without
+    /// `dotglob`: `*` no longer matches `.env`, but `.*` does. Unlike
+    /// `skip_hidden`, dot-directories are still walked.
+    pub match_dotfiles: bool,
+
+    /// Whether to skip dotfiles and prune dot-directories
+    pub skip_hidden: bool,
+
+    /// Whether traversal may descend into other filesystems (mount points)
+    pub cross_filesystems: bool,
+
+    /// Whether directories (other than the root) can be returned as matches
+    ///
+    /// Directory-only patterns such as `build/` select nothing without it.
+    /// The root is a candidate only with `include_root` as well.
+    pub match_directories: bool,
+
+    /// Whether the root directory itself is a candidate
+    ///
+    /// Traversal otherwise only yields entries below the root. Applies
+    /// when directories are matched and `min_depth` is unset or 0; the
+    /// root must still match the patterns and predicates. Under
+    /// `patterns_relative_to_root` it is matched as `.`.
+    pub include_root: bool,
+
+    /// Whether roots that don't exist or aren't directories are skipped
+    ///
+    /// By default such a root fails the whole glob with
+    /// `GlobError::RootNotFound` before anything is walked.
+    pub skip_missing_roots: bool,
+
+    /// Order in which the async stream visits directories
+    ///
+    /// Breadth-first surfaces shallow matches first. Synchronous traversal
+    /// is always depth-first.
+    pub walk_order: TraversalOrder,
+
+    /// Worker threads for synchronous walks; `None` or 1 walks on the caller
+    ///
+    /// With more threads and the `parallel` feature, each directory
+    /// directly under the root is walked as its own subtree on a shared
+    /// thread pool, predicates included. Results are the same set as a
+    /// sequential walk, but entries directly under the root come first,
+    /// followed by each subtree in directory order. The visitor sees
+    /// matches as they are found, and once it stops the walk or
+    /// `max_total_bytes` is reached the workers stop too. Progress is
+    /// reported per phase rather than periodically. Without the feature,
+    /// walks always run on the calling thread.
+    pub threads: Option<usize>,
+}
+
+/// Implements `PartialEq`, `Eq` and `Hash` for `GlobOptions` over the listed
+/// fields plus the shared handles `cancel` and `batch_io`, which compare by
+/// identity; destructuring makes an unlisted field a compile error
+macro_rules! options_eq_hash {
+    ($($field:ident),+ $(,)?) => {
+        impl PartialEq for GlobOptions {
+            fn eq(&self, other: &Self) -> bool {
+                let Self { $($field,)+ cancel, batch_io } = self;
+                $(*$field == other.$field &&)+ same_arc(cancel, &other.cancel)
+                    && same_arc(batch_io, &other.batch_io)
+            }
+        }
+
+        impl Eq for GlobOptions {}
+
+        impl Hash for GlobOptions {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                let Self { $($field,)+ cancel, batch_io } = self;
+                $($field.hash(state);)+
+                cancel.as_ref().map(Arc::as_ptr).hash(state);
+                batch_io.as_ref().map(Arc::as_ptr).hash(state);
+            }
+        }
+    };
+}
+
+/// Whether two optional shared handles point at the same value
+fn same_arc<T>(a: &Option<Arc<T>>, b: &Option<Arc<T>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+options_eq_hash!(
+    follow_symlinks,
+    max_depth,
+    min_depth,
+    max_symlink_depth,
+    confine_to_root,
+    case_sensitive,
+    unicode_case_fold,
+    max_inflight,
+    permit_timeout,
+    max_open_dirs,
+    timeout,
+    timeout_scope,
+    max_total_bytes,
+    max_total_bytes_inclusive,
+    predicates,
+    root_dir,
+    expand_tilde,
+    expand_env,
+    on_undefined_env,
+    patterns_relative_to_root,
+    canonicalize_root,
+    strip_dot_prefix,
+    dedupe_by_canonical,
+    keep_long_path_prefix,
+    match_resolved_path,
+    return_resolved_path,
+    match_mode,
+    regex_limits,
+    regex_quantifier_braces,
+    literal,
+    path_traversal,
+    non_utf8,
+    on_error,
+    normalize_separators,
+    separator,
+    literal_separator,
+    match_dotfiles,
+    skip_hidden,
+    cross_filesystems,
+    match_directories,
+    include_root,
+    skip_missing_roots,
+    walk_order,
+    threads,
+);
+
+impl GlobOptions {
+    /// Returns a copy of these options for walking one of several roots
+    ///
+    /// A relative root is resolved against `root_dir` when that is set,
+    /// with `.` standing for `root_dir` itself; otherwise, and for
+    /// absolute roots, the root is used as given. Under `expand_tilde`
+    /// a leading `~` is expanded first.
+    ///
+    /// # Arguments
+    ///
+    /// * `root` - Root directory as passed to the facade methods
+    ///
+    /// # Returns
+    ///
+    /// Options whose `root_dir` is the resolved root
+    pub fn for_root(&self, root: &str) -> GlobOptions {
+        let root = if self.expand_tilde {
+            crate::fs::expand_tilde(root)
+        } else {
+            root.into()
+        };
+        let root = Path::new(root.as_ref());
+        let root_dir = match &self.root_dir {
+            Some(base) if root == Path::new(".") || root.as_os_str().is_empty() => base.clone(),
+            Some(base) => base.join(root),
+            None => root.to_path_buf(),
+        };
+        GlobOptions {
+            root_dir: Some(root_dir),
+            ..self.clone()
+        }
+    }
+
+    /// Resolves every root and checks that each is an existing directory
+    ///
+    /// Runs before any traversal, so a typo in the last root fails fast
+    /// instead of after the others were walked. Symlinks to directories
+    /// are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `roots` - Root directories as passed to the facade methods
+    /// * `fs` - Filesystem the roots are looked up in
+    ///
+    /// # Returns
+    ///
+    /// Options for each root to walk, in order, as from [`GlobOptions::for_root`].
+    /// They are produced as each root starts: under `TimeoutScope::Global`
+    /// their `timeout` is what remains of the one for the whole glob
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::UndefinedEnvVar` if `expand_env` meets an unset
+    /// variable under `OnUndefinedEnv::Error`.
+    /// Returns `GlobError::RootNotFound` for the first root that is
+    /// missing or not a directory, unless `skip_missing_roots` is set
+    pub(crate) fn for_roots(
+        &self,
+        roots: &[&str],
+        fs: &dyn FileSystem,
+    ) -> Result<impl Iterator<Item = GlobOptions> + use<>, GlobError> {
+        let mut per_root = Vec::with_capacity(roots.len());
+        for r in roots {
+            let r = if self.expand_env {
+                crate::fs::expand_env(r, self.on_undefined_env)?
+            } else {
+                (*r).into()
+            };
+            let opts = self.for_root(&r);
+            let root = opts.walk_root();
+            let is_dir = fs
+                .metadata(&root)
+                .is_ok_and(|meta| meta.file_type == FileType::Dir);
+            if is_dir {
+                per_root.push(opts);
+            } else if !self.skip_missing_roots {
+                return Err(GlobError::RootNotFound(root));
+            }
+        }
+
+        let started = Instant::now();
+        let shared = self.timeout_scope == TimeoutScope::Global;
+        Ok(per_root.into_iter().map(move |mut opts| {
+            if shared {
+                opts.timeout = opts.timeout.map(|t| t.saturating_sub(started.elapsed()));
+            }
+            opts
+        }))
+    }
+
+    /// Returns the directory a walk starts from
+    ///
+    /// This is `root_dir`, or `.` when unset, made absolute under
+    /// `canonicalize_root`. If the current directory cannot be read the
+    /// root is used as given.
+    pub(crate) fn walk_root(&self) -> PathBuf {
+        let root = self.root_dir.clone().unwrap_or_else(|| PathBuf::from("."));
+        if self.canonicalize_root && root.is_relative() {
+            std::path::absolute(&root).unwrap_or(root)
+        } else {
+            root
+        }
+    }
+
+    /// Returns whether directories are candidates for matching
+    ///
+    /// This is the case when `match_directories` is set, or when the
+    /// predicates explicitly ask for `FileType::Dir`.
+    pub fn matches_directories(&self) -> bool {
+        self.match_directories
+            || self
+                .predicates
+                .as_ref()
+                .is_some_and(|p| p.file_type == Some(FileType::Dir))
+    }
+
+    /// Whether the `cancel` flag has been set
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    /// Whether wildcards stop at `/`, explicitly or through `separator`
+    pub(crate) fn separator_is_literal(&self) -> bool {
+        self.literal_separator || self.separator.is_some()
+    }
+
+    /// Charges a matched file of `len` bytes against `max_total_bytes`
+    ///
+    /// `spent` is the running total, updated when the file is kept.
+    pub(crate) fn spend_bytes(&self, spent: &mut u64, len: u64) -> Spend {
+        let Some(max) = self.max_total_bytes else {
+            return Spend::Keep;
+        };
+        let total = spent.saturating_add(len);
+        if total > max && !self.max_total_bytes_inclusive {
+            return Spend::Over;
+        }
+        *spent = total;
+        if total >= max {
+            Spend::Last
+        } else {
+            Spend::Keep
+        }
+    }
+
+    /// Whether symlink entries need their canonical target resolved
+    pub(crate) fn resolves_symlinks(&self) -> bool {
+        self.match_resolved_path || self.return_resolved_path
+    }
 }
 
 impl Default for GlobOptions {
@@ -35,11 +680,50 @@ impl Default for GlobOptions {
         Self {
             follow_symlinks: false,
             max_depth: None,
+            min_depth: None,
+            max_symlink_depth: None,
+            confine_to_root: false,
             case_sensitive: cfg!(not(windows)), // Case-insensitive by default on Windows
+            unicode_case_fold: false,
             max_inflight: 64,
+            permit_timeout: Duration::from_secs(30),
+            max_open_dirs: None,
             timeout: None,
+            timeout_scope: TimeoutScope::PerRoot,
+            cancel: None,
+            max_total_bytes: None,
+            max_total_bytes_inclusive: false,
             predicates: None,
+            batch_io: None,
             root_dir: None,
+            expand_tilde: false,
+            expand_env: false,
+            on_undefined_env: OnUndefinedEnv::Error,
+            patterns_relative_to_root: false,
+            canonicalize_root: false,
+            strip_dot_prefix: false,
+            dedupe_by_canonical: false,
+            keep_long_path_prefix: false,
+            match_resolved_path: false,
+            return_resolved_path: false,
+            match_mode: MatchMode::FullPath,
+            regex_limits: RegexLimits::default(),
+            regex_quantifier_braces: false,
+            literal: false,
+            path_traversal: PathTraversalPolicy::Reject,
+            non_utf8: OnNonUtf8::Skip,
+            on_error: ErrorPolicy::Abort,
+            normalize_separators: cfg!(windows),
+            separator: None,
+            literal_separator: false,
+            match_dotfiles: true,
+            skip_hidden: false,
+            cross_filesystems: false,
+            match_directories: false,
+            include_root: false,
+            skip_missing_roots: false,
+            walk_order: TraversalOrder::DepthFirst,
+            threads: None,
         }
     }
 }
@@ -74,36 +758,270 @@ impl GlobOptionsBuilder {
         self
     }
 
+    /// Sets the maximum number of symlinks followed along one branch
+    pub fn max_symlink_depth(mut self, hops: usize) -> Self {
+        self.0.max_symlink_depth = Some(hops);
+        self
+    }
+
+    /// Sets whether followed symlinks must stay inside the root
+    pub fn confine_to_root(mut self, v: bool) -> Self {
+        self.0.confine_to_root = v;
+        self
+    }
+
+    /// Sets the minimum depth an entry must be at to be returned
+    pub fn min_depth(mut self, d: usize) -> Self {
+        self.0.min_depth = Some(d);
+        self
+    }
+
     /// Sets case-sensitive matching behavior
     pub fn case_sensitive(mut self, v: bool) -> Self {
         self.0.case_sensitive = v;
         self
     }
 
+    /// Sets whether case-insensitive matching folds non-ASCII letters
+    pub fn unicode_case_fold(mut self, v: bool) -> Self {
+        self.0.unicode_case_fold = v;
+        self
+    }
+
     /// Sets the maximum number of concurrent operations for async globbing
     pub fn max_inflight(mut self, v: usize) -> Self {
         self.0.max_inflight = v;
         self
     }
 
-    /// Sets the timeout for individual operations
+    /// Sets how long the async stream waits for a concurrency permit per entry
+    pub fn permit_timeout(mut self, t: Duration) -> Self {
+        self.0.permit_timeout = t;
+        self
+    }
+
+    /// Sets the maximum number of directories held open at once
+    pub fn max_open_dirs(mut self, n: usize) -> Self {
+        self.0.max_open_dirs = Some(n);
+        self
+    }
+
+    /// Sets the deadline for the walk, per root or shared as `timeout_scope` says
     pub fn timeout(mut self, t: Duration) -> Self {
         self.0.timeout = Some(t);
         self
     }
 
+    /// Sets a flag that cancels traversal when set to `true`
+    pub fn cancel(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.0.cancel = Some(flag);
+        self
+    }
+
+    /// Sets the combined size of matched files after which the walk stops
+    pub fn max_total_bytes(mut self, bytes: u64) -> Self {
+        self.0.max_total_bytes = Some(bytes);
+        self
+    }
+
+    /// Sets whether the file that crosses the byte budget is still returned
+    pub fn max_total_bytes_inclusive(mut self, v: bool) -> Self {
+        self.0.max_total_bytes_inclusive = v;
+        self
+    }
+
     /// Sets the predicates for file filtering
     pub fn predicates(mut self, p: Predicates) -> Self {
         self.0.predicates = Some(p);
         self
     }
 
+    /// Shares a metadata cache with other globs
+    pub fn batch_io(mut self, io: Arc<BatchIO>) -> Self {
+        self.0.batch_io = Some(io);
+        self
+    }
+
     /// Sets the root directory for globbing
     pub fn root_dir(mut self, dir: PathBuf) -> Self {
         self.0.root_dir = Some(dir);
         self
     }
 
+    /// Sets whether a leading `~` in roots and patterns is a home directory
+    pub fn expand_tilde(mut self, v: bool) -> Self {
+        self.0.expand_tilde = v;
+        self
+    }
+
+    /// Sets whether environment variables in roots are substituted
+    pub fn expand_env(mut self, v: bool) -> Self {
+        self.0.expand_env = v;
+        self
+    }
+
+    /// Sets what environment expansion does with variables that aren't set
+    pub fn on_undefined_env(mut self, policy: OnUndefinedEnv) -> Self {
+        self.0.on_undefined_env = policy;
+        self
+    }
+
+    /// Sets whether patterns are matched against root-relative paths
+    pub fn patterns_relative_to_root(mut self, v: bool) -> Self {
+        self.0.patterns_relative_to_root = v;
+        self
+    }
+
+    /// Sets whether a relative root is made absolute before walking
+    pub fn canonicalize_root(mut self, v: bool) -> Self {
+        self.0.canonicalize_root = v;
+        self
+    }
+
+    /// Sets whether a leading `./` is removed from returned paths
+    pub fn strip_dot_prefix(mut self, v: bool) -> Self {
+        self.0.strip_dot_prefix = v;
+        self
+    }
+
+    /// Sets whether matches are deduplicated by canonical path
+    pub fn dedupe_by_canonical(mut self, v: bool) -> Self {
+        self.0.dedupe_by_canonical = v;
+        self
+    }
+
+    /// Sets whether results keep the Windows long-path prefix
+    pub fn keep_long_path_prefix(mut self, v: bool) -> Self {
+        self.0.keep_long_path_prefix = v;
+        self
+    }
+
+    /// Sets whether symlinks are matched by their canonical target
+    pub fn match_resolved_path(mut self, v: bool) -> Self {
+        self.0.match_resolved_path = v;
+        self
+    }
+
+    /// Sets whether symlinks are returned as their canonical target
+    pub fn return_resolved_path(mut self, v: bool) -> Self {
+        self.0.return_resolved_path = v;
+        self
+    }
+
+    /// Sets which part of the path slash-less patterns are matched against
+    pub fn match_mode(mut self, mode: MatchMode) -> Self {
+        self.0.match_mode = mode;
+        self
+    }
+
+    /// Sets the limits applied to regexes before compilation
+    pub fn regex_limits(mut self, limits: RegexLimits) -> Self {
+        self.0.regex_limits = limits;
+        self
+    }
+
+    /// Sets whether numeric braces are regex repetition quantifiers
+    pub fn regex_quantifier_braces(mut self, v: bool) -> Self {
+        self.0.regex_quantifier_braces = v;
+        self
+    }
+
+    /// Sets whether patterns are matched literally, without glob syntax
+    pub fn literal(mut self, v: bool) -> Self {
+        self.0.literal = v;
+        self
+    }
+
+    /// Sets how `..` segments in patterns are handled
+    pub fn path_traversal(mut self, policy: PathTraversalPolicy) -> Self {
+        self.0.path_traversal = policy;
+        self
+    }
+
+    /// Sets how paths that are not valid UTF-8 are handled
+    pub fn non_utf8(mut self, policy: OnNonUtf8) -> Self {
+        self.0.non_utf8 = policy;
+        self
+    }
+
+    /// Sets how traversal reacts to per-entry errors
+    pub fn on_error(mut self, policy: ErrorPolicy) -> Self {
+        self.0.on_error = policy;
+        self
+    }
+
+    /// Sets whether `\` is treated as a path separator
+    pub fn normalize_separators(mut self, v: bool) -> Self {
+        self.0.normalize_separators = v;
+        self
+    }
+
+    /// Sets the path separator used in matching
+    pub fn separator(mut self, sep: char) -> Self {
+        self.0.separator = Some(sep);
+        self
+    }
+
+    /// Sets whether wildcards stop at path separators
+    pub fn literal_separator(mut self, v: bool) -> Self {
+        self.0.literal_separator = v;
+        self
+    }
+
+    /// Sets whether wildcards match a leading `.` in a segment
+    pub fn match_dotfiles(mut self, v: bool) -> Self {
+        self.0.match_dotfiles = v;
+        self
+    }
+
+    /// Sets whether to skip dotfiles and prune dot-directories
+    pub fn skip_hidden(mut self, v: bool) -> Self {
+        self.0.skip_hidden = v;
+        self
+    }
+
+    /// Sets whether traversal may descend into other filesystems
+    pub fn cross_filesystems(mut self, v: bool) -> Self {
+        self.0.cross_filesystems = v;
+        self
+    }
+
+    /// Sets whether directories can be returned as matches
+    pub fn match_directories(mut self, v: bool) -> Self {
+        self.0.match_directories = v;
+        self
+    }
+
+    /// Sets whether the root directory itself is a candidate
+    pub fn include_root(mut self, v: bool) -> Self {
+        self.0.include_root = v;
+        self
+    }
+
+    /// Sets whether the timeout is shared by all roots or applies to each one
+    pub fn timeout_scope(mut self, scope: TimeoutScope) -> Self {
+        self.0.timeout_scope = scope;
+        self
+    }
+
+    /// Sets whether missing roots are skipped instead of failing the glob
+    pub fn skip_missing_roots(mut self, v: bool) -> Self {
+        self.0.skip_missing_roots = v;
+        self
+    }
+
+    /// Sets the order in which the async stream visits directories
+    pub fn walk_order(mut self, order: TraversalOrder) -> Self {
+        self.0.walk_order = order;
+        self
+    }
+
+    /// Sets the number of worker threads for synchronous walks
+    pub fn threads(mut self, n: usize) -> Self {
+        self.0.threads = Some(n);
+        self
+    }
+
     /// Builds the final GlobOptions instance
     pub fn build(self) -> GlobOptions {
         self.0
@@ -1,5 +1,6 @@
 // patterns/brace.rs
 use crate::error::GlobError;
+use std::rc::Rc;
 
 /// Maximum number of expansions to prevent DoS attacks
 const MAX_EXPANSIONS: usize = 1000;
@@ -9,7 +10,9 @@ const MAX_DEPTH: usize = 10;
 /// Expands brace patterns in a string
 ///
 /// This function supports nested braces and numeric ranges (e.g., {1..3}),
-/// with protection against excessive expansion and deep recursion.
+/// with protection against excessive expansion and deep recursion. Braces
+/// inside `[...]` classes are literal, and those inside `@(...)` or `!(...)`
+/// are left for the pattern compiler, which expands them in the group.
 ///
 /// # Arguments
 ///
@@ -24,103 +27,349 @@ const MAX_DEPTH: usize = 10;
 /// Returns `GlobError::BraceExpansionDepth` if maximum depth exceeded
 /// Returns `GlobError::BraceExpansionCount` if maximum expansions exceeded
 pub fn expand(input: &str) -> Result<Vec<String>, GlobError> {
-    /// Inner recursive expansion function with depth tracking
-    fn expand_inner(input: &str, depth: usize) -> Result<Vec<String>, GlobError> {
-        if depth > MAX_DEPTH {
-            return Err(GlobError::BraceExpansionDepth);
+    expand_with(input, false)
+}
+
+/// Expands brace patterns, optionally leaving repetition quantifiers alone
+///
+/// With `quantifier_braces` set, braces whose whole content is numeric
+/// (`{n}`, `{n,}` or `{n,m}`, see [`is_quantifier`]) are kept verbatim for
+/// the regex engine; every other brace is expanded as by [`expand`].
+///
+/// # Arguments
+///
+/// * `input` - Input string containing brace patterns
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+///
+/// # Returns
+///
+/// `Ok(Vec<String>)` with expanded strings, or `Err(GlobError)` on failure
+pub fn expand_with(input: &str, quantifier_braces: bool) -> Result<Vec<String>, GlobError> {
+    expand_iter_with(input, quantifier_braces).collect()
+}
+
+/// Lazily expands brace patterns in a string
+///
+/// Expansions are produced one at a time in the same order as [`expand`],
+/// so callers can consume them without holding the whole list. Numeric
+/// ranges are not materialized either.
+///
+/// # Arguments
+///
+/// * `input` - Input string containing brace patterns
+///
+/// # Returns
+///
+/// An iterator of expanded strings. A too-deep pattern yields a single
+/// `Err(GlobError::BraceExpansionDepth)`; past the expansion cap the
+/// iterator yields `Err(GlobError::BraceExpansionCount)` and then ends.
+pub fn expand_iter(input: &str) -> impl Iterator<Item = Result<String, GlobError>> {
+    expand_iter_with(input, false)
+}
+
+/// Lazily expands brace patterns, optionally leaving quantifiers alone
+///
+/// The lazy counterpart of [`expand_with`]; see [`expand_iter`].
+///
+/// # Arguments
+///
+/// * `input` - Input string containing brace patterns
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+///
+/// # Returns
+///
+/// An iterator of expanded strings, ending after the first error
+pub fn expand_iter_with(
+    input: &str,
+    quantifier_braces: bool,
+) -> impl Iterator<Item = Result<String, GlobError>> {
+    // Parsing is linear in the input, so depth errors surface up front
+    match parse(input, 0, quantifier_braces) {
+        Ok(pieces) => Expansions {
+            inner: Some(sequence(pieces.into(), 0)),
+            error: None,
+            count: 0,
+        },
+        Err(e) => Expansions {
+            inner: None,
+            error: Some(e),
+            count: 0,
+        },
+    }
+}
+
+/// One part of a parsed brace pattern
+enum Piece {
+    /// Text copied to every expansion
+    Literal(String),
+    /// A brace group; each expansion takes one alternative
+    Braces(Rc<[Alternative]>),
+}
+
+/// One comma-separated alternative of a brace group
+enum Alternative {
+    /// A numeric range such as `1..3`, expanded on demand
+    Range(i64, i64),
+    /// Any other text, possibly with braces of its own
+    Pattern(Rc<[Piece]>),
+}
+
+/// Iterator returned by [`expand_iter_with`], enforcing the expansion cap
+struct Expansions {
+    inner: Option<Box<dyn Iterator<Item = String>>>,
+    error: Option<GlobError>,
+    count: usize,
+}
+
+impl Iterator for Expansions {
+    type Item = Result<String, GlobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        let Some(expansion) = self.inner.as_mut()?.next() else {
+            self.inner = None;
+            return None;
+        };
+        self.count += 1;
+        if self.count > MAX_EXPANSIONS {
+            self.inner = None;
+            return Some(Err(GlobError::BraceExpansionCount));
         }
+        Some(Ok(expansion))
+    }
+}
 
-        /// Finds the matching brace pair in the input string
-        fn find_brace(s: &str) -> Option<(usize, usize)> {
+/// Returns the end of a bracket expression or extglob group starting at `i`
+///
+/// Braces and commas inside `[...]` are class members, and braces inside
+/// `@(...)` or `!(...)` are expanded by the pattern compiler as part of the
+/// group, so expansion steps over both. The returned index is just past the
+/// closing `]` or `)`; `None` means there is no complete span at `i`.
+fn opaque_span(s: &[u8], i: usize) -> Option<usize> {
+    match s[i] {
+        b'[' => class_end(s, i + 1),
+        b'@' | b'!' if s.get(i + 1) == Some(&b'(') => {
             let mut depth = 0usize;
-            let mut start = None;
+            let mut j = i + 1;
+            while j < s.len() {
+                match s[j] {
+                    b'\\' => j += 1,
+                    b'[' => {
+                        if let Some(end) = class_end(s, j + 1) {
+                            j = end;
+                            continue;
+                        }
+                    }
+                    b'(' => depth += 1,
+                    b')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(j + 1);
+                        }
+                    }
+                    _ => {}
+                }
+                j += 1;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Returns the index past the `]` closing a class whose body starts at `i`
+///
+/// Follows the pattern compiler: a leading `!` or `^` negates, a `]` right
+/// after that is a member, and `[:name:]` expressions are taken whole.
+fn class_end(s: &[u8], mut i: usize) -> Option<usize> {
+    if matches!(s.get(i), Some(b'!' | b'^')) {
+        i += 1;
+    }
+    if s.get(i) == Some(&b']') {
+        i += 1;
+    }
+    while i < s.len() {
+        match s[i] {
+            b'\\' => i += 1,
+            b']' => return Some(i + 1),
+            b'[' if s.get(i + 1) == Some(&b':') => {
+                if let Some(len) = s[i + 2..].windows(2).position(|w| w == b":]") {
+                    i += len + 3;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
 
-            for (i, ch) in s.char_indices() {
-                if ch == '{' {
+/// Finds the first matching brace pair in the input string
+///
+/// Backslash-escaped braces are treated as literals, and so are braces
+/// inside character classes and extglob groups (see [`opaque_span`]). As
+/// in bash, a `{` or `}` without a partner is literal too, so `}{a,b}`
+/// and `{x{a,b}` both have the group `{a,b}`. The pattern compiler uses
+/// this to find groups as well, so both agree on what a group is.
+pub(crate) fn find_brace(s: &str) -> Option<(usize, usize)> {
+    let bytes = s.as_bytes();
+    let mut from = 0;
+
+    loop {
+        let mut depth = 0usize;
+        let mut start = None;
+        let mut i = from;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'{' => {
                     if depth == 0 {
                         start = Some(i);
                     }
                     depth += 1;
-                } else if ch == '}' {
-                    if depth == 0 {
-                        return None; // Unbalanced closing brace
-                    }
+                }
+                // A stray closing brace is a literal
+                b'}' if depth == 0 => {}
+                b'}' => {
                     depth -= 1;
                     if depth == 0 {
                         return start.map(|st| (st, i));
                     }
                 }
+                _ => {
+                    if let Some(end) = opaque_span(bytes, i) {
+                        i = end;
+                        continue;
+                    }
+                }
             }
-            None // No complete brace pair found
+            i += 1;
         }
 
-        // Find the first complete brace pair
-        if let Some((st, en)) = find_brace(input) {
-            let before = &input[..st];
-            let inner = &input[st + 1..en];
-            let after = &input[en + 1..];
+        // The outermost `{` is never closed; look for a group after it
+        from = start? + 1;
+    }
+}
 
-            let new_depth = depth + 1;
-            if new_depth > MAX_DEPTH {
-                return Err(GlobError::BraceExpansionDepth);
-            }
+/// Parses brace groups into pieces, with depth tracking
+fn parse(input: &str, depth: usize, quantifier_braces: bool) -> Result<Vec<Piece>, GlobError> {
+    if depth > MAX_DEPTH {
+        return Err(GlobError::BraceExpansionDepth);
+    }
 
-            // Split inner content by commas, handling nested braces
-            let mut items = Vec::new();
-            let mut buf = String::new();
-            let mut inner_depth = 0usize;
-
-            for ch in inner.chars() {
-                if ch == ',' && inner_depth == 0 {
-                    items.push(buf.clone());
-                    buf.clear();
-                } else {
-                    if ch == '{' {
-                        inner_depth += 1;
-                    } else if ch == '}' {
-                        inner_depth = inner_depth.saturating_sub(1);
-                    }
-                    buf.push(ch);
-                }
-            }
+    // Find the first complete brace pair
+    let Some((st, en)) = find_brace(input) else {
+        // No braces found, keep input as a single literal
+        return Ok(vec![Piece::Literal(input.to_string())]);
+    };
+    let inner = &input[st + 1..en];
+    let after = &input[en + 1..];
 
-            if !buf.is_empty() {
-                items.push(buf);
-            }
+    let new_depth = depth + 1;
+    if new_depth > MAX_DEPTH {
+        return Err(GlobError::BraceExpansionDepth);
+    }
 
-            // Handle numeric ranges (e.g., {1..3})
-            let mut expanded_items = Vec::new();
-            for it in items {
-                if let Some((a, b)) = parse_range(&it) {
-                    for v in a..=b {
-                        expanded_items.push(v.to_string());
-                    }
-                } else {
-                    expanded_items.push(it);
-                }
-            }
+    let mut pieces = Vec::new();
+    if quantifier_braces && is_quantifier(inner) {
+        // Keep quantifiers as they are and expand whatever follows
+        pieces.push(Piece::Literal(input[..=en].to_string()));
+    } else {
+        pieces.push(Piece::Literal(input[..st].to_string()));
 
-            // Recursively expand each alternative
-            let mut out = Vec::new();
-            for it in expanded_items {
-                for mid in expand_inner(&it, depth + 1)? {
-                    for suf in expand_inner(after, depth + 1)? {
-                        out.push(format!("{}{}{}", before, mid, suf));
-                        if out.len() > MAX_EXPANSIONS {
-                            return Err(GlobError::BraceExpansionCount);
-                        }
+        // Split inner content by commas, handling nested braces.
+        // Escaped characters are kept verbatim, backslash included,
+        // so the downstream compiler still sees the literal.
+        // Classes and extglob groups are copied whole, commas included.
+        let bytes = inner.as_bytes();
+        let mut items = Vec::new();
+        let mut item_start = 0;
+        let mut inner_depth = 0usize;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b',' if inner_depth == 0 => {
+                    items.push(&inner[item_start..i]);
+                    item_start = i + 1;
+                }
+                b'{' => inner_depth += 1,
+                b'}' => inner_depth = inner_depth.saturating_sub(1),
+                _ => {
+                    if let Some(end) = opaque_span(bytes, i) {
+                        i = end;
+                        continue;
                     }
                 }
             }
-
-            Ok(out)
-        } else {
-            // No braces found, return input as single item
-            Ok(vec![input.to_string()])
+            i += 1;
         }
+
+        // Always keep the last alternative, even when empty (`{a,}`)
+        items.push(&inner[item_start..]);
+
+        // Numeric ranges (e.g., {1..3}) stay unexpanded until iterated
+        let alternatives = items
+            .iter()
+            .map(|it| match parse_range(it) {
+                Some((a, b)) => Ok(Alternative::Range(a, b)),
+                None => Ok(Alternative::Pattern(
+                    parse(it, new_depth, quantifier_braces)?.into(),
+                )),
+            })
+            .collect::<Result<Vec<_>, GlobError>>()?;
+        pieces.push(Piece::Braces(alternatives.into()));
     }
 
-    expand_inner(input, 0)
+    pieces.extend(parse(after, new_depth, quantifier_braces)?);
+    Ok(pieces)
+}
+
+/// Iterates the expansions of `pieces[i..]`
+///
+/// Earlier pieces vary slowest, matching the nesting order of [`expand`].
+fn sequence(pieces: Rc<[Piece]>, i: usize) -> Box<dyn Iterator<Item = String>> {
+    let heads: Box<dyn Iterator<Item = String>> = match pieces.get(i) {
+        None => return Box::new(std::iter::once(String::new())),
+        Some(Piece::Literal(text)) => Box::new(std::iter::once(text.clone())),
+        Some(Piece::Braces(alternatives)) => {
+            let alternatives = alternatives.clone();
+            Box::new(
+                (0..alternatives.len()).flat_map(move |k| match &alternatives[k] {
+                    Alternative::Range(a, b) => Box::new((*a..=*b).map(|v| v.to_string()))
+                        as Box<dyn Iterator<Item = String>>,
+                    Alternative::Pattern(inner) => sequence(inner.clone(), 0),
+                }),
+            )
+        }
+    };
+    Box::new(heads.flat_map(move |head| {
+        sequence(pieces.clone(), i + 1).map(move |tail| format!("{}{}", head, tail))
+    }))
+}
+
+/// Checks whether brace content has the form of a repetition quantifier
+///
+/// Only `n`, `n,` and `n,m` with decimal digits qualify, so alternations
+/// such as `txt,md` or ranges such as `1..3` never do.
+///
+/// # Arguments
+///
+/// * `inner` - Text between the braces
+///
+/// # Returns
+///
+/// `true` if `inner` is a quantifier body, `false` otherwise
+pub fn is_quantifier(inner: &str) -> bool {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match inner.split_once(',') {
+        None => is_number(inner),
+        Some((min, max)) => is_number(min) && (max.is_empty() || is_number(max)),
+    }
 }
 
 /// Parses a numeric range string (e.g., "1..3")
@@ -172,4 +421,39 @@ mod tests {
         let result = expand(deep);
         assert!(matches!(result, Err(GlobError::BraceExpansionDepth)));
     }
+
+    #[test]
+    fn test_expand_iter_is_lazy() {
+        let first: Vec<String> = expand_iter("n{1..1000000000}")
+            .take(3)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(first, vec!["n1", "n2", "n3"]);
+    }
+
+    #[test]
+    fn test_expand_iter_stops_past_cap() {
+        let mut iter = expand_iter("{1..2000}");
+        for _ in 0..MAX_EXPANSIONS {
+            assert!(iter.next().unwrap().is_ok());
+        }
+        assert!(matches!(
+            iter.next(),
+            Some(Err(GlobError::BraceExpansionCount))
+        ));
+        assert!(iter.next().is_none());
+        assert!(matches!(
+            expand("{1..2000}"),
+            Err(GlobError::BraceExpansionCount)
+        ));
+    }
+
+    #[test]
+    fn test_expand_iter_matches_expand_order() {
+        let lazy: Vec<String> = expand_iter("{a,b{1,2}}x{y,z}")
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lazy, expand("{a,b{1,2}}x{y,z}").unwrap());
+        assert_eq!(lazy, vec!["axy", "axz", "b1xy", "b1xz", "b2xy", "b2xz"]);
+    }
 }
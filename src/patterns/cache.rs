@@ -1,19 +1,24 @@
 // patterns/cache.rs
 use crate::error::GlobError;
+use crate::options::{GlobOptions, RegexLimits};
+use crate::patterns::{micromatch, Patterns};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use lru::LruCache;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use regex_syntax::ast::{self, Ast, RepetitionKind, RepetitionRange};
 use std::{
     num::NonZeroUsize,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
     time::{Duration, Instant},
 };
 
 // Limit cache size to prevent uncontrolled memory growth
 const MAX_CACHE_SIZE: usize = 1000;
 const DEFAULT_TTL: Duration = Duration::from_secs(300);
-const MAX_REGEX_COMPLEXITY: usize = 1000;
 
 /// A cache entry with value and expiration time
 #[derive(Clone, Debug)]
@@ -23,7 +28,7 @@ struct CacheEntry<T> {
 }
 
 /// Metrics for cache performance monitoring
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct CacheMetrics {
     pub hits: u64,
     pub misses: u64,
@@ -46,27 +51,37 @@ impl CacheMetrics {
 struct GlobCache {
     cache: Mutex<LruCache<String, CacheEntry<GlobSet>>>,
     metrics: Mutex<CacheMetrics>,
-    ttl: Duration,
+    ttl_nanos: AtomicU64,
 }
 
 /// Cache for compiled Regex patterns with LRU eviction and TTL
 struct RegexCache {
     cache: Mutex<LruCache<String, CacheEntry<Regex>>>,
     metrics: Mutex<CacheMetrics>,
-    ttl: Duration,
+    ttl_nanos: AtomicU64,
+}
+
+/// Converts a requested capacity to a non-zero LRU capacity
+fn capacity_to_non_zero(capacity: usize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Converts a duration to nanoseconds, saturating at `u64::MAX`
+fn duration_to_nanos(d: Duration) -> u64 {
+    u64::try_from(d.as_nanos()).unwrap_or(u64::MAX)
 }
 
 impl GlobCache {
-    fn new(ttl: Duration) -> Self {
+    fn new(capacity: usize, ttl: Duration) -> Self {
         Self {
-            cache: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_CACHE_SIZE).unwrap())),
+            cache: Mutex::new(LruCache::new(capacity_to_non_zero(capacity))),
             metrics: Mutex::new(CacheMetrics {
                 hits: 0,
                 misses: 0,
                 evictions: 0,
                 size: 0,
             }),
-            ttl,
+            ttl_nanos: AtomicU64::new(duration_to_nanos(ttl)),
         }
     }
 
@@ -98,13 +113,43 @@ impl GlobCache {
 
         let entry = CacheEntry {
             value,
-            expires_at: Instant::now() + self.ttl,
+            expires_at: Instant::now() + self.ttl(),
         };
 
-        cache.put(key, entry);
+        // Count LRU evictions, but not replacement of the same key
+        if let Some((evicted, _)) = cache.push(key.clone(), entry) {
+            if evicted != key {
+                metrics.evictions += 1;
+            }
+        }
         metrics.size = cache.len();
     }
 
+    /// Checks whether a live entry exists without touching LRU order or metrics
+    fn contains(&self, key: &str) -> bool {
+        let cache = self.cache.lock().unwrap();
+        cache
+            .peek(key)
+            .is_some_and(|entry| entry.expires_at > Instant::now())
+    }
+
+    /// Returns the keys currently held, most recently used first
+    fn keys(&self) -> Vec<String> {
+        let cache = self.cache.lock().unwrap();
+        cache.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    /// Returns the TTL applied to new entries
+    fn ttl(&self) -> Duration {
+        Duration::from_nanos(self.ttl_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Sets the TTL applied to new entries
+    fn set_ttl(&self, ttl: Duration) {
+        self.ttl_nanos
+            .store(duration_to_nanos(ttl), Ordering::Relaxed);
+    }
+
     /// Returns current cache metrics
     fn metrics(&self) -> CacheMetrics {
         self.metrics.lock().unwrap().clone()
@@ -122,16 +167,16 @@ impl GlobCache {
 }
 
 impl RegexCache {
-    fn new(ttl: Duration) -> Self {
+    fn new(capacity: usize, ttl: Duration) -> Self {
         Self {
-            cache: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_CACHE_SIZE).unwrap())),
+            cache: Mutex::new(LruCache::new(capacity_to_non_zero(capacity))),
             metrics: Mutex::new(CacheMetrics {
                 hits: 0,
                 misses: 0,
                 evictions: 0,
                 size: 0,
             }),
-            ttl,
+            ttl_nanos: AtomicU64::new(duration_to_nanos(ttl)),
         }
     }
 
@@ -163,13 +208,43 @@ impl RegexCache {
 
         let entry = CacheEntry {
             value,
-            expires_at: Instant::now() + self.ttl,
+            expires_at: Instant::now() + self.ttl(),
         };
 
-        cache.put(key, entry);
+        // Count LRU evictions, but not replacement of the same key
+        if let Some((evicted, _)) = cache.push(key.clone(), entry) {
+            if evicted != key {
+                metrics.evictions += 1;
+            }
+        }
         metrics.size = cache.len();
     }
 
+    /// Checks whether a live entry exists without touching LRU order or metrics
+    fn contains(&self, key: &str) -> bool {
+        let cache = self.cache.lock().unwrap();
+        cache
+            .peek(key)
+            .is_some_and(|entry| entry.expires_at > Instant::now())
+    }
+
+    /// Returns the keys currently held, most recently used first
+    fn keys(&self) -> Vec<String> {
+        let cache = self.cache.lock().unwrap();
+        cache.iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    /// Returns the TTL applied to new entries
+    fn ttl(&self) -> Duration {
+        Duration::from_nanos(self.ttl_nanos.load(Ordering::Relaxed))
+    }
+
+    /// Sets the TTL applied to new entries
+    fn set_ttl(&self, ttl: Duration) {
+        self.ttl_nanos
+            .store(duration_to_nanos(ttl), Ordering::Relaxed);
+    }
+
     /// Returns current cache metrics
     fn metrics(&self) -> CacheMetrics {
         self.metrics.lock().unwrap().clone()
@@ -186,11 +261,246 @@ impl RegexCache {
     }
 }
 
-// Global cache instances
-static GLOB_CACHE: Lazy<GlobCache> = Lazy::new(|| GlobCache::new(DEFAULT_TTL));
-static REGEX_CACHE: Lazy<RegexCache> = Lazy::new(|| RegexCache::new(DEFAULT_TTL));
+/// A self-contained cache of compiled globs and regexes
+///
+/// Each instance owns its own LRU storage, TTL and metrics, so independent
+/// users in the same process don't share eviction pressure. The free
+/// functions in this module operate on a process-global default instance.
+pub struct PatternCache {
+    globs: GlobCache,
+    regexes: RegexCache,
+}
+
+impl Default for PatternCache {
+    fn default() -> Self {
+        Self::new(MAX_CACHE_SIZE, DEFAULT_TTL)
+    }
+}
+
+impl PatternCache {
+    /// Creates a cache holding up to `capacity` entries of each kind
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Maximum number of globs and of regexes to keep
+    /// * `ttl` - Time-to-live for new entries
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            globs: GlobCache::new(capacity, ttl),
+            regexes: RegexCache::new(capacity, ttl),
+        }
+    }
+
+    /// Retrieves a compiled GlobSet from cache or compiles and caches it
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Glob pattern to compile
+    ///
+    /// # Returns
+    ///
+    /// `Ok(GlobSet)` if successful, `Err(GlobError)` otherwise
+    pub fn get_or_compile_glob(&self, pattern: &str) -> Result<GlobSet, GlobError> {
+        if let Some(cached) = self.globs.get(pattern) {
+            return Ok(cached);
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        let g = Glob::new(pattern).map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
+        builder.add(g);
+        let set = builder
+            .build()
+            .map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
+
+        self.globs.put(pattern.to_string(), set.clone());
+        Ok(set)
+    }
+
+    /// Retrieves a compiled Regex from cache or compiles and caches it
+    ///
+    /// # Arguments
+    ///
+    /// * `pat` - Regex pattern to compile
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Regex)` if successful, `Err(GlobError)` otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::RegexTooComplex` for patterns that exceed the
+    /// default [`RegexLimits`]
+    pub fn get_or_compile_regex(&self, pat: &str) -> Result<Regex, GlobError> {
+        self.get_or_compile_regex_limited(pat, &RegexLimits::default())
+    }
+
+    /// Retrieves a compiled Regex from cache or compiles it under custom limits
+    ///
+    /// # Arguments
+    ///
+    /// * `pat` - Regex pattern to compile
+    /// * `limits` - Limits the pattern must satisfy
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::RegexTooComplex` for patterns that exceed `limits`
+    pub fn get_or_compile_regex_limited(
+        &self,
+        pat: &str,
+        limits: &RegexLimits,
+    ) -> Result<Regex, GlobError> {
+        // Complexity checks to prevent ReDoS attacks
+        check_regex_limits(pat, limits)?;
+
+        if let Some(cached) = self.regexes.get(pat) {
+            return Ok(cached);
+        }
+
+        let re = Regex::new(pat).map_err(GlobError::Regex)?;
+        self.regexes.put(pat.to_string(), re.clone());
+        Ok(re)
+    }
+
+    /// Pre-compiles patterns so later compilations hit this cache
+    ///
+    /// Each pattern goes through [`Patterns::compile_many_with_cache`],
+    /// which populates the regex cache for `re:` and extended patterns.
+    /// Patterns that compile to plain globs are also recorded in the
+    /// glob cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first compilation error encountered
+    pub fn warm(&self, patterns: &[&str], opts: &GlobOptions) -> Result<(), GlobError> {
+        for pattern in patterns {
+            let compiled = Patterns::compile_many_with_cache([pattern], opts, self)?;
+            if compiled.regexes.is_empty() && compiled.basename_regexes.is_empty() {
+                self.get_or_compile_glob(pattern.trim())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether a pattern is present in the glob or regex cache
+    ///
+    /// `re:` and `re~:` patterns are looked up by their regex and extended glob
+    /// patterns by their converted regex form, with or without the
+    /// case-insensitive flag.
+    pub fn contains(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+        let has_regex =
+            |re: &str| self.regexes.contains(re) || self.regexes.contains(&format!("(?i){}", re));
+        if let Some(re) = micromatch::explicit_regex(pattern) {
+            return has_regex(&re);
+        }
+        if self.globs.contains(pattern) || has_regex(pattern) {
+            return true;
+        }
+        micromatch::micromatch_to_regex(pattern).is_ok_and(|re| has_regex(&re))
+    }
+
+    /// Returns the keys of the glob cache, most recently used first
+    pub fn glob_keys(&self) -> Vec<String> {
+        self.globs.keys()
+    }
+
+    /// Returns the keys of the regex cache, most recently used first
+    pub fn regex_keys(&self) -> Vec<String> {
+        self.regexes.keys()
+    }
+
+    /// Returns metrics for the glob cache
+    pub fn glob_metrics(&self) -> CacheMetrics {
+        self.globs.metrics()
+    }
+
+    /// Returns metrics for the regex cache
+    pub fn regex_metrics(&self) -> CacheMetrics {
+        self.regexes.metrics()
+    }
+
+    /// Sets the TTL for new entries (does not affect existing entries)
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.globs.set_ttl(ttl);
+        self.regexes.set_ttl(ttl);
+    }
+
+    /// Clears both glob and regex caches
+    pub fn clear(&self) {
+        self.globs.clear();
+        self.regexes.clear();
+    }
+}
+
+/// Shape statistics gathered from a regex AST
+#[derive(Default)]
+struct RegexShape {
+    groups: usize,
+    max_repetition_nesting: usize,
+}
+
+/// Walks the AST, counting groups and tracking unbounded repetition nesting
+fn measure_ast(node: &Ast, nesting: usize, shape: &mut RegexShape) {
+    match node {
+        Ast::Repetition(rep) => {
+            let unbounded = matches!(
+                rep.op.kind,
+                RepetitionKind::ZeroOrMore
+                    | RepetitionKind::OneOrMore
+                    | RepetitionKind::Range(RepetitionRange::AtLeast(_))
+            );
+            let nesting = if unbounded { nesting + 1 } else { nesting };
+            shape.max_repetition_nesting = shape.max_repetition_nesting.max(nesting);
+            measure_ast(&rep.ast, nesting, shape);
+        }
+        Ast::Group(group) => {
+            shape.groups += 1;
+            measure_ast(&group.ast, nesting, shape);
+        }
+        Ast::Alternation(alt) => {
+            for child in &alt.asts {
+                measure_ast(child, nesting, shape);
+            }
+        }
+        Ast::Concat(concat) => {
+            for child in &concat.asts {
+                measure_ast(child, nesting, shape);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rejects regexes that exceed the configured limits
+///
+/// Patterns that fail to parse are let through so that `Regex::new`
+/// reports the syntax error itself.
+fn check_regex_limits(pat: &str, limits: &RegexLimits) -> Result<(), GlobError> {
+    if pat.len() > limits.max_len {
+        return Err(GlobError::RegexTooComplex);
+    }
+
+    let Ok(parsed) = ast::parse::Parser::new().parse(pat) else {
+        return Ok(());
+    };
+
+    let mut shape = RegexShape::default();
+    measure_ast(&parsed, 0, &mut shape);
+    if shape.groups > limits.max_groups || shape.max_repetition_nesting > limits.max_repetitions {
+        return Err(GlobError::RegexTooComplex);
+    }
+    Ok(())
+}
+
+// Global cache instance
+static GLOBAL_CACHE: Lazy<PatternCache> = Lazy::new(PatternCache::default);
+
+/// Returns the process-global cache used by default
+pub fn global() -> &'static PatternCache {
+    &GLOBAL_CACHE
+}
 
-/// Retrieves a compiled GlobSet from cache or compiles and caches it
+/// Retrieves a compiled GlobSet from the global cache or compiles and caches it
 ///
 /// # Arguments
 ///
@@ -200,22 +510,10 @@ static REGEX_CACHE: Lazy<RegexCache> = Lazy::new(|| RegexCache::new(DEFAULT_TTL)
 ///
 /// `Ok(GlobSet)` if successful, `Err(GlobError)` otherwise
 pub fn get_or_compile_glob(pattern: &str) -> Result<GlobSet, GlobError> {
-    if let Some(cached) = GLOB_CACHE.get(pattern) {
-        return Ok(cached);
-    }
-
-    let mut builder = GlobSetBuilder::new();
-    let g = Glob::new(pattern).map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
-    builder.add(g);
-    let set = builder
-        .build()
-        .map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
-
-    GLOB_CACHE.put(pattern.to_string(), set.clone());
-    Ok(set)
+    GLOBAL_CACHE.get_or_compile_glob(pattern)
 }
 
-/// Retrieves a compiled Regex from cache or compiles and caches it
+/// Retrieves a compiled Regex from the global cache or compiles and caches it
 ///
 /// # Arguments
 ///
@@ -229,38 +527,53 @@ pub fn get_or_compile_glob(pattern: &str) -> Result<GlobSet, GlobError> {
 ///
 /// Returns `GlobError::RegexTooComplex` for patterns that exceed complexity limits
 pub fn get_or_compile_regex(pat: &str) -> Result<Regex, GlobError> {
-    // Complexity checks to prevent ReDoS attacks
-    if pat.len() > 1000 || pat.matches('(').count() > MAX_REGEX_COMPLEXITY {
-        return Err(GlobError::RegexTooComplex);
-    }
-
-    if let Some(cached) = REGEX_CACHE.get(pat) {
-        return Ok(cached);
-    }
-
-    let re = Regex::new(pat).map_err(GlobError::Regex)?;
-    REGEX_CACHE.put(pat.to_string(), re.clone());
-    Ok(re)
+    GLOBAL_CACHE.get_or_compile_regex(pat)
 }
 
 /// Clears both glob and regex caches
 pub fn clear_caches() {
-    GLOB_CACHE.clear();
-    REGEX_CACHE.clear();
+    GLOBAL_CACHE.clear();
+}
+
+/// Pre-compiles patterns so later compilations hit the global cache
+///
+/// See [`PatternCache::warm`].
+///
+/// # Errors
+///
+/// Returns the first compilation error encountered
+pub fn warm(patterns: &[&str], opts: &GlobOptions) -> Result<(), GlobError> {
+    GLOBAL_CACHE.warm(patterns, opts)
+}
+
+/// Checks whether a pattern is present in the global glob or regex cache
+///
+/// See [`PatternCache::contains`].
+pub fn contains(pattern: &str) -> bool {
+    GLOBAL_CACHE.contains(pattern)
+}
+
+/// Returns the keys of the glob cache, most recently used first
+pub fn glob_cache_keys() -> Vec<String> {
+    GLOBAL_CACHE.glob_keys()
+}
+
+/// Returns the keys of the regex cache, most recently used first
+pub fn regex_cache_keys() -> Vec<String> {
+    GLOBAL_CACHE.regex_keys()
 }
 
 /// Returns metrics for the glob cache
 pub fn glob_cache_metrics() -> CacheMetrics {
-    GLOB_CACHE.metrics()
+    GLOBAL_CACHE.glob_metrics()
 }
 
 /// Returns metrics for the regex cache
 pub fn regex_cache_metrics() -> CacheMetrics {
-    REGEX_CACHE.metrics()
+    GLOBAL_CACHE.regex_metrics()
 }
 
 /// Sets the TTL for new cache entries (does not affect existing entries)
-pub fn set_ttl(_ttl: Duration) {
-    // For simplicity, we don't change TTL of existing entries
-    // New entries will use the new TTL
+pub fn set_ttl(ttl: Duration) {
+    GLOBAL_CACHE.set_ttl(ttl);
 }
@@ -0,0 +1,175 @@
+// patterns/explain.rs
+use super::{brace, micromatch, Patterns};
+use crate::options::GlobOptions;
+
+/// How a single (brace-expanded) pattern is compiled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatternKind {
+    /// Plain glob handled by globset
+    SimpleGlob,
+    /// Extended glob converted to a regex
+    ExtGlob,
+    /// Explicit `re:` or `re~:` regex
+    Regex,
+    /// Pattern whose braces expand into several patterns, each compiled
+    /// on its own; only reported by [`Patterns::pattern_kinds`]
+    BraceExpanded,
+}
+
+impl PatternKind {
+    /// Encodes the kind for a cache file
+    pub(crate) fn to_line(self) -> &'static str {
+        match self {
+            Self::SimpleGlob => "simple_glob",
+            Self::ExtGlob => "ext_glob",
+            Self::Regex => "regex",
+            Self::BraceExpanded => "brace_expanded",
+        }
+    }
+
+    /// Decodes a kind written by [`PatternKind::to_line`]
+    pub(crate) fn from_line(line: &str) -> Option<Self> {
+        match line {
+            "simple_glob" => Some(Self::SimpleGlob),
+            "ext_glob" => Some(Self::ExtGlob),
+            "regex" => Some(Self::Regex),
+            "brace_expanded" => Some(Self::BraceExpanded),
+            _ => None,
+        }
+    }
+}
+
+/// Diagnostic view of one brace expansion of a pattern
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledPattern {
+    /// The expanded pattern text
+    pub source: String,
+
+    /// How the expansion is compiled
+    pub kind: PatternKind,
+
+    /// Final regex string, for expansions routed to the regex engine
+    pub regex: Option<String>,
+}
+
+/// Diagnostic view of how an input pattern is compiled
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternExplanation {
+    /// The original pattern as supplied
+    pub pattern: String,
+
+    /// Results of brace expansion (the pattern itself if there were no braces)
+    pub expansions: Vec<String>,
+
+    /// Compilation details for each expansion
+    pub compiled: Vec<CompiledPattern>,
+
+    /// Error message if the pattern could not be expanded or converted
+    pub error: Option<String>,
+}
+
+impl Patterns {
+    /// Describes how each pattern would be compiled, without compiling it
+    ///
+    /// This surfaces the intermediate results of brace expansion, the
+    /// glob/extglob/regex routing decision and the regex produced by
+    /// micromatch conversion. It is purely diagnostic and touches no
+    /// filesystem.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Pattern strings to explain
+    /// * `opts` - Glob options that would be used for compilation
+    ///
+    /// # Returns
+    ///
+    /// One explanation per non-empty input pattern
+    pub fn explain(patterns: &[&str], opts: &GlobOptions) -> Vec<PatternExplanation> {
+        patterns
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(|p| explain_one(p, opts))
+            .collect()
+    }
+
+    /// Reports how each input pattern was compiled
+    ///
+    /// Lets tools flag patterns that take the slower regex path. Sets
+    /// combined with [`Patterns::and`], [`Patterns::or`] or
+    /// [`Patterns::except`] list the kinds of their operands in order.
+    ///
+    /// # Returns
+    ///
+    /// One kind per non-empty input pattern, in input order
+    pub fn pattern_kinds(&self) -> Vec<PatternKind> {
+        if self.groups.is_empty() {
+            return self.sources.kinds.clone();
+        }
+        self.groups
+            .iter()
+            .flat_map(Patterns::pattern_kinds)
+            .collect()
+    }
+}
+
+/// Builds the explanation for a single trimmed pattern
+fn explain_one(pattern: &str, opts: &GlobOptions) -> PatternExplanation {
+    let mut explanation = PatternExplanation {
+        pattern: pattern.to_string(),
+        expansions: Vec::new(),
+        compiled: Vec::new(),
+        error: None,
+    };
+
+    let expansions = if pattern.contains('{') && pattern.contains('}') {
+        match brace::expand_with(pattern, opts.regex_quantifier_braces) {
+            Ok(v) => v,
+            Err(e) => {
+                explanation.error = Some(e.to_string());
+                return explanation;
+            }
+        }
+    } else {
+        vec![pattern.to_string()]
+    };
+
+    for expanded in &expansions {
+        let compiled = if let Some(re) = micromatch::explicit_regex(expanded) {
+            CompiledPattern {
+                source: expanded.clone(),
+                kind: PatternKind::Regex,
+                regex: Some(re),
+            }
+        } else if Patterns::needs_regex(expanded, opts) {
+            match micromatch::micromatch_to_regex_with_case(
+                expanded,
+                opts.regex_quantifier_braces,
+                opts.separator_is_literal(),
+                opts.match_dotfiles,
+                opts.case_sensitive,
+            ) {
+                Ok(re) => CompiledPattern {
+                    source: expanded.clone(),
+                    kind: PatternKind::ExtGlob,
+                    regex: Some(re),
+                },
+                Err(e) => {
+                    explanation.error = Some(e.to_string());
+                    break;
+                }
+            }
+        } else {
+            CompiledPattern {
+                source: expanded.clone(),
+                kind: PatternKind::SimpleGlob,
+                regex: None,
+            }
+        };
+        explanation.compiled.push(compiled);
+    }
+
+    explanation.expansions = expansions;
+    explanation
+}
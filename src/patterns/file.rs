@@ -0,0 +1,100 @@
+// patterns/file.rs
+use crate::error::GlobError;
+use crate::options::GlobOptions;
+use crate::patterns::Patterns;
+use std::{fs, path::Path};
+
+/// Patterns read from one file, each with its 1-based line number
+#[derive(Default)]
+struct PatternLines {
+    include: Vec<(usize, String)>,
+    exclude: Vec<(usize, String)>,
+}
+
+/// Splits a patterns file into included and excluded patterns
+///
+/// Lines are trimmed; blank lines and lines starting with `#` are skipped.
+/// A leading `!` moves the rest of the line to the excluded patterns,
+/// except for `!(...)`, which is an extglob. `\#` and `\!` escape a
+/// literal leading character.
+fn parse_lines(text: &str) -> PatternLines {
+    let mut lines = PatternLines::default();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let number = index + 1;
+        match line.strip_prefix('!') {
+            Some(rest) if !rest.starts_with('(') => {
+                lines.exclude.push((number, rest.trim_start().to_string()))
+            }
+            _ => {
+                let line = line
+                    .strip_prefix('\\')
+                    .filter(|rest| rest.starts_with(['#', '!']))
+                    .unwrap_or(line);
+                lines.include.push((number, line.to_string()));
+            }
+        }
+    }
+    lines
+}
+
+/// Compiles patterns, reporting errors against their line in `file`
+fn compile_lines(
+    lines: &[(usize, String)],
+    file: &Path,
+    opts: &GlobOptions,
+) -> Result<Patterns, GlobError> {
+    Patterns::compile_many(lines.iter().map(|(_, p)| p), opts).map_err(|e| match e {
+        GlobError::InvalidPatternAt {
+            index,
+            pattern,
+            message,
+        } => GlobError::InvalidPatternInFile {
+            file: file.to_path_buf(),
+            line: lines[index].0,
+            pattern,
+            message,
+        },
+        other => other,
+    })
+}
+
+impl Patterns {
+    /// Compiles the patterns listed in a file, one per line
+    ///
+    /// Lines are trimmed, and blank lines and `#` comments are skipped.
+    /// A line starting with `!` excludes paths: the result matches paths
+    /// that match some other line and none of the `!` lines, regardless
+    /// of order. `!(...)` is still an extglob, and `\#` or `\!` start a
+    /// pattern with a literal `#` or `!`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Patterns file to read
+    /// * `opts` - Glob options for configuration
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Patterns)` if successful, `Err(GlobError)` otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::Io` if the file cannot be read.
+    /// Returns `GlobError::InvalidPatternInFile` with the line number of
+    /// the first pattern with invalid syntax.
+    /// Returns other `GlobError` variants as [`Patterns::compile_many`] does
+    pub fn compile_from_file(path: &Path, opts: &GlobOptions) -> Result<Self, GlobError> {
+        let text = fs::read_to_string(path)?;
+        let lines = parse_lines(&text);
+
+        let include = compile_lines(&lines.include, path, opts)?;
+        if lines.exclude.is_empty() {
+            return Ok(include);
+        }
+        let exclude = compile_lines(&lines.exclude, path, opts)?;
+        Ok(include.except(exclude))
+    }
+}
@@ -0,0 +1,195 @@
+// patterns/lint.rs
+use super::{brace, micromatch, Patterns};
+use crate::options::GlobOptions;
+
+/// Kind of suspicious construct reported by [`Patterns::lint`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WarningKind {
+    /// `**` that is not a whole path segment (so it acts like `*`), or
+    /// that directly repeats another `**` segment
+    RedundantGlobstar,
+    /// Brace group whose alternatives are all empty, such as `{,}`
+    EmptyAlternation,
+    /// Character class with no members or a reversed range
+    EmptyClass,
+    /// `re~:` regex without a leading `^`, so it matches anywhere in the path
+    UnanchoredRegex,
+}
+
+/// A likely mistake found in a pattern
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternWarning {
+    /// The original pattern as supplied
+    pub pattern: String,
+
+    /// What kind of construct was flagged
+    pub kind: WarningKind,
+
+    /// Human-readable description of the problem
+    pub message: String,
+}
+
+impl Patterns {
+    /// Flags constructs that are valid but probably not what was meant
+    ///
+    /// This is purely diagnostic: it neither compiles the patterns nor
+    /// changes how they match. Patterns under `opts.literal` have no
+    /// syntax and are never flagged.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Pattern strings to check
+    /// * `opts` - Glob options that would be used for compilation
+    ///
+    /// # Returns
+    ///
+    /// The warnings for every non-empty input pattern, in input order
+    pub fn lint(patterns: &[&str], opts: &GlobOptions) -> Vec<PatternWarning> {
+        if opts.literal {
+            return Vec::new();
+        }
+        let mut warnings = Vec::new();
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let mut warn = |kind, message: String| {
+                warnings.push(PatternWarning {
+                    pattern: pattern.to_string(),
+                    kind,
+                    message,
+                })
+            };
+
+            if micromatch::explicit_regex(pattern).is_some() {
+                if let Some(re) = pattern.strip_prefix("re~:") {
+                    if !re.starts_with('^') && !re.starts_with("(?") {
+                        warn(
+                            WarningKind::UnanchoredRegex,
+                            format!(
+                                "`{}` can match anywhere in the path; use `re:` or add `^`",
+                                re
+                            ),
+                        );
+                    }
+                }
+                continue;
+            }
+
+            lint_globstars(pattern, &mut warn);
+            lint_braces(pattern, opts.regex_quantifier_braces, &mut warn);
+            lint_classes(pattern, &mut warn);
+        }
+        warnings
+    }
+}
+
+/// Flags `**` used inside a segment or repeated across segments
+fn lint_globstars(pattern: &str, warn: &mut impl FnMut(WarningKind, String)) {
+    let mut previous = "";
+    for segment in pattern.split('/') {
+        if segment.contains("**") && segment != "**" && !segment.contains("**(") {
+            warn(
+                WarningKind::RedundantGlobstar,
+                format!(
+                    "`**` in `{}` is not a whole segment and acts like `*`; use `**/` to cross directories",
+                    segment
+                ),
+            );
+        } else if segment == "**" && previous == "**" {
+            warn(
+                WarningKind::RedundantGlobstar,
+                "`**/**` is the same as a single `**`".to_string(),
+            );
+        }
+        previous = segment;
+    }
+}
+
+/// Returns the byte offsets of unescaped occurrences of `target`
+fn unescaped(pattern: &str, target: char) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut escaped = false;
+    for (i, ch) in pattern.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == target {
+            found.push(i);
+        }
+    }
+    found
+}
+
+/// Flags brace groups whose alternatives are all empty
+fn lint_braces(pattern: &str, quantifier_braces: bool, warn: &mut impl FnMut(WarningKind, String)) {
+    for start in unescaped(pattern, '{') {
+        // Find the matching close brace and the top-level commas
+        let mut depth = 0usize;
+        let mut escaped = false;
+        let mut body = None;
+        for (i, ch) in pattern[start..].char_indices() {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '{' {
+                depth += 1;
+            } else if ch == '}' {
+                depth -= 1;
+                if depth == 0 {
+                    body = Some(&pattern[start + 1..start + i]);
+                    break;
+                }
+            }
+        }
+        let Some(body) = body else {
+            continue;
+        };
+        if quantifier_braces && brace::is_quantifier(body) {
+            continue;
+        }
+        if body.chars().all(|c| c == ',') {
+            warn(
+                WarningKind::EmptyAlternation,
+                format!("`{{{}}}` only expands to the empty string", body),
+            );
+        }
+    }
+}
+
+/// Flags character classes that cannot match any character
+fn lint_classes(pattern: &str, warn: &mut impl FnMut(WarningKind, String)) {
+    for start in unescaped(pattern, '[') {
+        let rest = &pattern[start + 1..];
+        let negated = rest.starts_with(['!', '^']);
+        let body_start = usize::from(negated);
+        // A `]` right after the opening bracket is a member when the class
+        // closes later, as in `[]]`; otherwise the class is empty
+        let leading = usize::from(rest[body_start..].starts_with(']'));
+        let Some(len) = rest[body_start + leading..].find(']') else {
+            if leading == 1 {
+                warn(
+                    WarningKind::EmptyClass,
+                    format!(
+                        "`{}` is an empty character class and matches nothing",
+                        &pattern[start..=start + 1 + body_start]
+                    ),
+                );
+            }
+            continue;
+        };
+        let body = &rest[body_start..body_start + leading + len];
+
+        let chars: Vec<char> = body.chars().collect();
+        for range in chars.windows(3).filter(|w| w[1] == '-') {
+            if range[0] > range[2] {
+                warn(
+                    WarningKind::EmptyClass,
+                    format!(
+                        "range `{}-{}` is reversed and matches nothing",
+                        range[0], range[2]
+                    ),
+                );
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 // patterns/micromatch.rs
+use super::{brace, negate};
 use crate::error::GlobError;
 
 /// Token types for pattern parsing
@@ -137,7 +138,18 @@ fn tokens_to_string(tokens: &[Token]) -> String {
 }
 
 /// Processes extglob patterns and converts them to regex
-fn process_extglob(tokens: &[Token], operator: &Token) -> Result<String, GlobError> {
+///
+/// `starts_segment` tells whether the extglob begins a path segment, where
+/// it must not match a leading `.` unless `match_dotfiles` is set.
+fn process_extglob(
+    tokens: &[Token],
+    operator: &Token,
+    quantifier_braces: bool,
+    literal_separator: bool,
+    match_dotfiles: bool,
+    starts_segment: bool,
+    case_sensitive: bool,
+) -> Result<String, GlobError> {
     let mut alternatives = Vec::new();
     let mut current = Vec::new();
     let mut depth = 0;
@@ -160,16 +172,30 @@ fn process_extglob(tokens: &[Token], operator: &Token) -> Result<String, GlobErr
     if !current.is_empty() {
         alternatives.push(current);
     }
+    let alternatives = expand_alternatives(alternatives, quantifier_braces)?;
+
+    if *operator == Token::Exclamation {
+        let exclude_dot = !match_dotfiles && starts_segment;
+        return negated_segment(
+            &alternatives,
+            quantifier_braces,
+            exclude_dot,
+            case_sensitive,
+        );
+    }
 
     let mut regex_alternatives = Vec::new();
     for alt in alternatives {
         let alt_str = tokens_to_string(&alt);
-        let regex_alt = micromatch_to_regex(&alt_str)?;
-        let regex_alt = regex_alt
-            .trim_start_matches('^')
-            .trim_end_matches('$')
-            .to_string();
-        regex_alternatives.push(regex_alt);
+        let regex_alt = translate(
+            &alt_str,
+            quantifier_braces,
+            literal_separator,
+            match_dotfiles,
+            starts_segment,
+            case_sensitive,
+        )?;
+        regex_alternatives.push(unanchored(&regex_alt).to_string());
     }
 
     let alternatives_str = regex_alternatives.join("|");
@@ -179,43 +205,206 @@ fn process_extglob(tokens: &[Token], operator: &Token) -> Result<String, GlobErr
         Token::Star => Ok(format!("(?:{})*", alternatives_str)),
         Token::Plus => Ok(format!("(?:{})+", alternatives_str)),
         Token::At => Ok(format!("(?:{})", alternatives_str)),
-        Token::Exclamation => Ok(format!("(?!(?:{})).*", alternatives_str)),
         _ => Err(GlobError::InvalidPattern("Invalid extglob operator".into())),
     }
 }
 
+/// Brace-expands each alternative, splicing the expansions in its place
+///
+/// `@(a|{b,c})` thus has the same three alternatives as `@(a|b|c)`, which
+/// keeps `!(...)` working with braces as long as the expansions are literal.
+fn expand_alternatives(
+    alternatives: Vec<Vec<Token>>,
+    quantifier_braces: bool,
+) -> Result<Vec<Vec<Token>>, GlobError> {
+    let mut expanded = Vec::with_capacity(alternatives.len());
+    for alt in alternatives {
+        if !alt.contains(&Token::OpenBrace) {
+            expanded.push(alt);
+            continue;
+        }
+        for expansion in brace::expand_iter_with(&tokens_to_string(&alt), quantifier_braces) {
+            expanded.push(tokenize(&expansion?));
+        }
+    }
+    Ok(expanded)
+}
+
+/// Strips the anchors [`translate`] adds, for embedding in a larger regex
+fn unanchored(regex: &str) -> &str {
+    let regex = regex.strip_prefix('^').unwrap_or(regex);
+    regex.strip_suffix('$').unwrap_or(regex)
+}
+
+/// Translates `!(a|b)` into a regex matching any single path segment except the alternatives
+///
+/// The regex crate has no look-around, so the complement is spelled out
+/// (see [`negate::complement`]). Each alternative is translated as usual,
+/// with wildcards kept within the segment.
+///
+/// # Arguments
+/// * `alternatives` - Token lists of the `|`-separated alternatives
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+/// * `exclude_dot` - Whether segments starting with `.` are excluded too
+/// * `case_sensitive` - Whether the regex will be matched case-sensitively
+///
+/// # Returns
+/// * `Ok(String)` - Regex fragment confined to `[^/]*`
+/// * `Err(GlobError)` - If an alternative is invalid or too complex to negate
+fn negated_segment(
+    alternatives: &[Vec<Token>],
+    quantifier_braces: bool,
+    exclude_dot: bool,
+    case_sensitive: bool,
+) -> Result<String, GlobError> {
+    let regexes = alternatives
+        .iter()
+        .map(|alt| {
+            let regex = translate(
+                &tokens_to_string(alt),
+                quantifier_braces,
+                true,
+                true,
+                false,
+                case_sensitive,
+            )?;
+            Ok(unanchored(&regex).to_string())
+        })
+        .collect::<Result<Vec<_>, GlobError>>()?;
+    negate::complement(&regexes, exclude_dot, !case_sensitive)
+}
+
+/// POSIX character class names understood inside brackets
+const POSIX_CLASSES: &[&str] = &[
+    "alnum", "alpha", "ascii", "blank", "cntrl", "digit", "graph", "lower", "print", "punct",
+    "space", "upper", "word", "xdigit",
+];
+
+/// Upper bound on the token length of a `[:name:]` expression
+const POSIX_CLASS_MAX_TOKENS: usize = 12;
+
+/// Returns the length in tokens of a `[:name:]` expression at the start of `tokens`
+fn posix_class_len(tokens: &[Token]) -> Option<usize> {
+    if tokens.first() != Some(&Token::OpenBracket) || tokens.get(1) != Some(&Token::Char(':')) {
+        return None;
+    }
+    let name_len = tokens[2..]
+        .iter()
+        .take_while(|t| matches!(t, Token::Char(c) if c.is_ascii_alphabetic()))
+        .count();
+    let close = 2 + name_len;
+    if name_len > 0
+        && tokens.get(close) == Some(&Token::Char(':'))
+        && tokens.get(close + 1) == Some(&Token::CloseBracket)
+    {
+        Some(close + 2)
+    } else {
+        None
+    }
+}
+
+/// Collects the tokens of a bracket expression after its opening `[`
+///
+/// A leading `!` or `^` marks negation. The class ends at the first `]`
+/// that is neither its first member nor the end of a POSIX `[:name:]`
+/// expression; any other `[` is a literal.
+fn collect_character_class<I>(tokens: &mut std::iter::Peekable<I>) -> Result<Vec<Token>, GlobError>
+where
+    I: Iterator<Item = Token> + Clone,
+{
+    let mut out = Vec::new();
+
+    if matches!(tokens.peek(), Some(Token::Exclamation | Token::Caret)) {
+        out.extend(tokens.next());
+    }
+    if tokens.peek() == Some(&Token::CloseBracket) {
+        out.extend(tokens.next());
+    }
+
+    loop {
+        match tokens.next() {
+            None => {
+                return Err(GlobError::InvalidPattern(
+                    "unterminated character class".into(),
+                ))
+            }
+            Some(Token::CloseBracket) => return Ok(out),
+            Some(Token::OpenBracket) => {
+                // Look ahead for a complete `[:name:]` and take it whole
+                let mut ahead = vec![Token::OpenBracket];
+                ahead.extend(tokens.clone().take(POSIX_CLASS_MAX_TOKENS));
+                let len = posix_class_len(&ahead).unwrap_or(1);
+                out.push(Token::OpenBracket);
+                out.extend(tokens.by_ref().take(len - 1));
+            }
+            Some(token) => out.push(token),
+        }
+    }
+}
+
 /// Processes character class patterns and converts them to regex
-fn process_character_class(tokens: &[Token]) -> Result<String, GlobError> {
+///
+/// POSIX classes such as `[:digit:]` are passed through, since the regex
+/// engine supports them natively. Other brackets, and characters the
+/// regex engine would read as class set operators, are escaped.
+fn process_character_class(tokens: &[Token], literal_separator: bool) -> Result<String, GlobError> {
     let mut class = String::new();
     let mut negated = false;
-    let mut first_token = true;
+    let mut i = 0;
 
-    for token in tokens {
-        if first_token {
-            if let Token::Exclamation = token {
-                negated = true;
-                first_token = false;
-                continue;
+    // `[!...]` and `[^...]` are the same negation; `^` elsewhere is literal
+    if matches!(tokens.first(), Some(Token::Exclamation | Token::Caret)) {
+        negated = true;
+        i = 1;
+    }
+
+    while i < tokens.len() {
+        if let Some(len) = posix_class_len(&tokens[i..]) {
+            let name = tokens_to_string(&tokens[i + 2..i + len - 2]);
+            if !POSIX_CLASSES.contains(&name.as_str()) {
+                return Err(GlobError::InvalidPattern(format!(
+                    "unknown POSIX character class [:{}:]",
+                    name
+                )));
             }
-            first_token = false;
+            class.push_str(&format!("[:{}:]", name));
+            i += len;
+            continue;
         }
 
-        match token {
-            Token::Char(c) => class.push(*c),
+        match tokens[i] {
+            Token::Char(c) if matches!(c, '&' | '~' | '\\') => {
+                class.push('\\');
+                class.push(c);
+            }
+            Token::Char(c) => class.push(c),
             Token::Escaped(c) => {
                 class.push('\\');
-                class.push(*c);
+                class.push(c);
             }
+            Token::OpenBracket => class.push_str("\\["),
+            Token::CloseBracket => class.push_str("\\]"),
             Token::Dot => class.push('.'),
             Token::Minus => class.push('-'),
-            _ => class.push_str(&tokens_to_string(&[*token])),
+            Token::Caret => class.push_str("\\^"),
+            token => class.push_str(&tokens_to_string(&[token])),
         }
+        i += 1;
     }
 
     if negated {
+        if literal_separator {
+            return Ok(format!("[^{}/]", class));
+        }
         Ok(format!("[^{}]", class))
     } else {
-        Ok(format!("[{}]", class))
+        // Intersect away `/` only from classes that contain it
+        let positive = format!("[{}]", class);
+        let has_separator = regex::Regex::new(&positive).is_ok_and(|re| re.is_match("/"));
+        if literal_separator && has_separator {
+            return Ok(format!("[{}&&[^/]]", class));
+        }
+        Ok(positive)
     }
 }
 
@@ -232,32 +421,295 @@ fn process_character_class(tokens: &[Token]) -> Result<String, GlobError> {
 ///
 /// `Ok(String)` with regex pattern, or `Err(GlobError)` on failure
 pub fn micromatch_to_regex(pat: &str) -> Result<String, GlobError> {
-    // Handle raw regex patterns (prefixed with "re:")
+    micromatch_to_regex_with(pat, false)
+}
+
+/// Returns the regex for an explicit `re:`, `re~:` or `ext:` pattern
+///
+/// `re:` regexes must match the whole path, so they are wrapped in
+/// `^(?:...)$`; `re~:` regexes are searched for anywhere in the path and
+/// are used verbatim. `ext:rs,toml` matches paths ending in any of the
+/// comma-separated extensions, a leading `.` on each being optional.
+/// Returns `None` for any other pattern, and for `ext:` without extensions.
+///
+/// # Arguments
+///
+/// * `pat` - Pattern to inspect
+///
+/// # Returns
+///
+/// `Some(String)` with the regex to compile, or `None` if `pat` is not a
+/// regex pattern
+pub fn explicit_regex(pat: &str) -> Option<String> {
     if let Some(rest) = pat.strip_prefix("re:") {
-        return Ok(rest.to_string());
+        Some(format!("^(?:{})$", rest))
+    } else if let Some(list) = pat.strip_prefix("ext:") {
+        let extensions: Vec<String> = list
+            .split(',')
+            .map(|ext| ext.trim().trim_start_matches('.'))
+            .filter(|ext| !ext.is_empty())
+            .map(regex::escape)
+            .collect();
+        (!extensions.is_empty()).then(|| format!("^.*\\.(?:{})$", extensions.join("|")))
+    } else {
+        pat.strip_prefix("re~:").map(str::to_string)
+    }
+}
+
+/// Converts micromatch patterns to regex strings, with quantifier braces
+///
+/// Same as [`micromatch_to_regex`], but when `quantifier_braces` is set a
+/// brace whose content is numeric (see [`brace::is_quantifier`]) becomes a
+/// regex repetition of the preceding atom, so `a{2}` matches `aa`.
+///
+/// # Arguments
+///
+/// * `pat` - Pattern to convert
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+///
+/// # Returns
+///
+/// `Ok(String)` with regex pattern, or `Err(GlobError)` on failure
+pub fn micromatch_to_regex_with(pat: &str, quantifier_braces: bool) -> Result<String, GlobError> {
+    micromatch_to_regex_with_separator(pat, quantifier_braces, false)
+}
+
+/// Regex for the zero or more leading directories matched by `**/`
+const ANY_DIRS: &str = "(?:.*/)?";
+
+/// Regex for the directories matched by `**/` when dotfiles are not matched
+///
+/// Spelled without nested repetition so it passes the default `RegexLimits`.
+const VISIBLE_DIRS: &str = "(?:[^./](?:[^/]|/[^./])*/)?";
+
+/// Regex for a trailing `**` when dotfiles are not matched
+const VISIBLE_TREE: &str = "(?:[^./](?:[^/]|/[^./])*)?";
+
+/// Regex for a first character that starts neither a dotfile nor a new segment
+const VISIBLE_CHAR: &str = "[^./]";
+
+/// Whether the regex built so far ends at the start of a path segment
+fn at_segment_start(output: &str) -> bool {
+    output.ends_with('/') || output.ends_with(ANY_DIRS) || output.ends_with(VISIBLE_DIRS)
+}
+
+/// Regex for one character of a run matched by `*` or `+`
+///
+/// Without `literal_separator` the run crosses `/`, but never into a
+/// segment starting with `.` unless `match_dotfiles` is set.
+fn any_char(literal_separator: bool, match_dotfiles: bool) -> &'static str {
+    if literal_separator {
+        "[^/]"
+    } else if match_dotfiles {
+        "."
+    } else {
+        "(?:[^/]|/[^./])"
+    }
+}
+
+/// Regex for a `*` at the start of a segment that must not match a leading `.`
+///
+/// When the pattern continues with a literal `.`, the run must be
+/// non-empty, or that `.` would start the segment.
+fn visible_run(literal_separator: bool, non_empty: bool) -> String {
+    let run = format!("{}{}*", VISIBLE_CHAR, any_char(literal_separator, false));
+    if non_empty {
+        run
+    } else {
+        format!("(?:{})?", run)
+    }
+}
+
+/// Whether the next token is a literal `.`
+fn next_is_dot(token: Option<&Token>) -> bool {
+    matches!(
+        token,
+        Some(Token::Dot | Token::Char('.') | Token::Escaped('.'))
+    )
+}
+
+/// Converts micromatch patterns to regex strings, optionally keeping `*` within a segment
+///
+/// Same as [`micromatch_to_regex_with`], but when `literal_separator` is
+/// set `*`, `?` and `+` never match `/`, as with globset's option of the
+/// same name, and character classes exclude `/`. Only a whole-segment
+/// `**` still spans directories.
+///
+/// # Arguments
+///
+/// * `pat` - Pattern to convert
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+/// * `literal_separator` - Whether wildcards stop at `/`
+///
+/// # Returns
+///
+/// `Ok(String)` with regex pattern, or `Err(GlobError)` on failure
+pub fn micromatch_to_regex_with_separator(
+    pat: &str,
+    quantifier_braces: bool,
+    literal_separator: bool,
+) -> Result<String, GlobError> {
+    micromatch_to_regex_with_dotfiles(pat, quantifier_braces, literal_separator, true)
+}
+
+/// Converts micromatch patterns to regex strings, optionally hiding dotfiles from wildcards
+///
+/// Same as [`micromatch_to_regex_with_separator`], but unless
+/// `match_dotfiles` is set a `*`, `?`, `+`, `**` or extglob at the start
+/// of a segment does not match a leading `.`, as in bash without `dotglob`.
+/// A literal `.` in the pattern still matches one.
+///
+/// # Arguments
+///
+/// * `pat` - Pattern to convert
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+/// * `literal_separator` - Whether wildcards stop at `/`
+/// * `match_dotfiles` - Whether wildcards match a leading `.`
+///
+/// # Returns
+///
+/// `Ok(String)` with regex pattern, or `Err(GlobError)` on failure
+pub fn micromatch_to_regex_with_dotfiles(
+    pat: &str,
+    quantifier_braces: bool,
+    literal_separator: bool,
+    match_dotfiles: bool,
+) -> Result<String, GlobError> {
+    micromatch_to_regex_with_case(
+        pat,
+        quantifier_braces,
+        literal_separator,
+        match_dotfiles,
+        true,
+    )
+}
+
+/// Converts micromatch patterns to regex strings, for case-sensitive or -insensitive matching
+///
+/// Same as [`micromatch_to_regex_with_dotfiles`], but when `case_sensitive`
+/// is unset the result is meant to be compiled with `(?i)`. Most of the
+/// regex reads the same either way; only `!(...)`, which spells out every
+/// string its alternatives don't match, needs to know.
+///
+/// # Arguments
+///
+/// * `pat` - Pattern to convert
+/// * `quantifier_braces` - Whether numeric braces are repetition quantifiers
+/// * `literal_separator` - Whether wildcards stop at `/`
+/// * `match_dotfiles` - Whether wildcards match a leading `.`
+/// * `case_sensitive` - Whether the regex will be matched case-sensitively
+///
+/// # Returns
+///
+/// `Ok(String)` with regex pattern, or `Err(GlobError)` on failure
+pub fn micromatch_to_regex_with_case(
+    pat: &str,
+    quantifier_braces: bool,
+    literal_separator: bool,
+    match_dotfiles: bool,
+    case_sensitive: bool,
+) -> Result<String, GlobError> {
+    translate(
+        pat,
+        quantifier_braces,
+        literal_separator,
+        match_dotfiles,
+        true,
+        case_sensitive,
+    )
+}
+
+/// Converts a pattern, or a piece of one that begins a segment when `starts_segment` is set
+fn translate(
+    pat: &str,
+    quantifier_braces: bool,
+    literal_separator: bool,
+    match_dotfiles: bool,
+    starts_segment: bool,
+    case_sensitive: bool,
+) -> Result<String, GlobError> {
+    // Handle raw regex patterns (prefixed with "re:" or "re~:")
+    if let Some(re) = explicit_regex(pat) {
+        return Ok(re);
     }
 
     let tokens = tokenize(pat);
     let mut output = String::new();
     let mut tokens_iter = tokens.into_iter().peekable();
+    let star = format!("{}*", any_char(literal_separator, match_dotfiles));
+    // A top-level `|` splits the pattern into alternatives that must all
+    // sit inside the anchors, each starting where the pattern would
+    let mut paren_depth = 0usize;
+    let mut alternative_start = 0;
 
     while let Some(token) = tokens_iter.next() {
+        let at_segment_start = if output.len() == alternative_start {
+            starts_segment
+        } else {
+            at_segment_start(&output)
+        };
+        // Wildcards here must not match a leading `.`
+        let hide_dot = !match_dotfiles && at_segment_start;
         match token {
+            Token::Question if hide_dot => output.push_str(VISIBLE_CHAR),
+            Token::Question if literal_separator => output.push_str("[^/]"),
             Token::Question => output.push('.'),
-            Token::Star => output.push_str(".*"),
-            Token::Plus => output.push_str(".+"),
+            Token::Star if tokens_iter.peek() == Some(&Token::Star) => {
+                tokens_iter.next();
+                // A whole-segment `**` spans zero or more directories, as in globset
+                if at_segment_start && tokens_iter.peek() == Some(&Token::Char('/')) {
+                    tokens_iter.next();
+                    output.push_str(if hide_dot { VISIBLE_DIRS } else { ANY_DIRS });
+                } else if hide_dot && tokens_iter.peek().is_none() {
+                    output.push_str(VISIBLE_TREE);
+                } else if at_segment_start && tokens_iter.peek().is_none() {
+                    // A trailing `**` matches everything below
+                    output.push_str(".*");
+                } else if hide_dot {
+                    let before_dot = next_is_dot(tokens_iter.peek());
+                    output.push_str(&visible_run(literal_separator, before_dot));
+                } else {
+                    output.push_str(&star);
+                }
+            }
+            Token::Star if hide_dot => {
+                let before_dot = next_is_dot(tokens_iter.peek());
+                output.push_str(&visible_run(literal_separator, before_dot));
+            }
+            Token::Star => output.push_str(&star),
+            Token::Plus if hide_dot => output.push_str(&visible_run(literal_separator, true)),
+            Token::Plus => {
+                output.push_str(any_char(literal_separator, match_dotfiles));
+                output.push('+');
+            }
             Token::At if tokens_iter.peek() == Some(&Token::OpenParen) => {
                 tokens_iter.next();
                 let inner =
                     collect_until_balanced(&mut tokens_iter, Token::OpenParen, Token::CloseParen)?;
-                let processed = process_extglob(&inner, &Token::At)?;
+                let processed = process_extglob(
+                    &inner,
+                    &Token::At,
+                    quantifier_braces,
+                    literal_separator,
+                    match_dotfiles,
+                    at_segment_start,
+                    case_sensitive,
+                )?;
                 output.push_str(&processed);
             }
             Token::Exclamation if tokens_iter.peek() == Some(&Token::OpenParen) => {
                 tokens_iter.next();
                 let inner =
                     collect_until_balanced(&mut tokens_iter, Token::OpenParen, Token::CloseParen)?;
-                let processed = process_extglob(&inner, &Token::Exclamation)?;
+                let processed = process_extglob(
+                    &inner,
+                    &Token::Exclamation,
+                    quantifier_braces,
+                    literal_separator,
+                    match_dotfiles,
+                    at_segment_start,
+                    case_sensitive,
+                )?;
                 output.push_str(&processed);
             }
             Token::OpenParen
@@ -281,41 +733,85 @@ pub fn micromatch_to_regex(pat: &str) -> Result<String, GlobError> {
                 tokens_iter.next();
                 let inner =
                     collect_until_balanced(&mut tokens_iter, Token::OpenParen, Token::CloseParen)?;
-                let processed = process_extglob(&inner, &operator)?;
+                let processed = process_extglob(
+                    &inner,
+                    &operator,
+                    quantifier_braces,
+                    literal_separator,
+                    match_dotfiles,
+                    at_segment_start,
+                    case_sensitive,
+                )?;
                 output.push_str(&processed);
             }
             Token::OpenBracket => {
-                let inner = collect_until_balanced(
-                    &mut tokens_iter,
-                    Token::OpenBracket,
-                    Token::CloseBracket,
-                )?;
-                let processed = process_character_class(&inner)?;
+                let inner = collect_character_class(&mut tokens_iter)?;
+                let processed = process_character_class(&inner, literal_separator)?;
                 output.push_str(&processed);
             }
             Token::OpenBrace => {
-                let inner =
-                    collect_until_balanced(&mut tokens_iter, Token::OpenBrace, Token::CloseBrace)?;
-                let inner_str = tokens_to_string(&inner);
-                let alternatives: Vec<&str> = inner_str.split(',').collect();
-                let regex_alternatives: Vec<String> = alternatives
-                    .iter()
-                    .map(|alt| micromatch_to_regex(alt))
-                    .collect::<Result<Vec<_>, _>>()?;
+                // Brace expansion decides where the group ends, so an
+                // unmatched `{` is literal here just as it is there
+                let rest: Vec<Token> = tokens_iter.collect();
+                let rest = format!("{{{}", tokens_to_string(&rest));
+                let Some((0, end)) = brace::find_brace(&rest) else {
+                    output.push_str("\\{");
+                    tokens_iter = tokenize(&rest[1..]).into_iter().peekable();
+                    continue;
+                };
+                tokens_iter = tokenize(&rest[end + 1..]).into_iter().peekable();
+                let inner_str = &rest[1..end];
+                if quantifier_braces && brace::is_quantifier(inner_str) {
+                    output.push('{');
+                    output.push_str(inner_str);
+                    output.push('}');
+                    continue;
+                }
+                // Expanding the whole group handles nesting and ranges
+                let group = &rest[..=end];
+                let mut regex_alternatives = Vec::new();
+                for alt in brace::expand_iter_with(group, quantifier_braces) {
+                    let regex_alt = translate(
+                        &alt?,
+                        quantifier_braces,
+                        literal_separator,
+                        match_dotfiles,
+                        at_segment_start,
+                        case_sensitive,
+                    )?;
+                    regex_alternatives.push(unanchored(&regex_alt).to_string());
+                }
                 output.push_str("(?:");
                 output.push_str(&regex_alternatives.join("|"));
                 output.push(')');
             }
+            Token::OpenParen => {
+                paren_depth += 1;
+                output.push('(');
+            }
+            Token::CloseParen => {
+                paren_depth = paren_depth.saturating_sub(1);
+                output.push(')');
+            }
+            Token::Pipe if paren_depth == 0 => {
+                output.push('|');
+                alternative_start = output.len();
+            }
             Token::Escaped(c) => output.push_str(&regex_escape_char(c)),
             Token::Char(c) => output.push_str(&regex_escape_char(c)),
             Token::Dot => output.push_str("\\."),
             Token::Caret => output.push_str("\\^"),
             Token::Dollar => output.push_str("\\$"),
+            Token::CloseBrace => output.push_str("\\}"),
             _ => output.push_str(&tokens_to_string(&[token])),
         }
     }
 
-    Ok(format!("^{}$", output))
+    if alternative_start > 0 {
+        Ok(format!("^(?:{})$", output))
+    } else {
+        Ok(format!("^{}$", output))
+    }
 }
 
 #[cfg(test)]
@@ -340,11 +836,91 @@ mod tests {
         assert_eq!(micromatch_to_regex("?(a|b)").unwrap(), "^.(a|b)$");
     }
 
+    #[test]
+    fn test_top_level_alternation() {
+        assert_eq!(micromatch_to_regex("a|b").unwrap(), "^(?:a|b)$");
+        assert_eq!(micromatch_to_regex("(a|b)c").unwrap(), "^(a|b)c$");
+    }
+
+    #[test]
+    fn test_hidden_dotfiles() {
+        let convert = |p| micromatch_to_regex_with_dotfiles(p, false, true, false).unwrap();
+        assert_eq!(convert("*"), "^(?:[^./][^/]*)?$");
+        assert_eq!(convert("*.rs"), "^[^./][^/]*\\.rs$");
+        assert_eq!(convert("a/?"), "^a/[^./]$");
+        assert_eq!(convert("a*"), "^a[^/]*$");
+        assert_eq!(convert("**/b"), "^(?:[^./](?:[^/]|/[^./])*/)?b$");
+        assert_eq!(convert(".*"), "^\\.[^/]*$");
+    }
+
+    #[test]
+    fn test_literal_separator() {
+        let convert = |p| micromatch_to_regex_with_separator(p, false, true).unwrap();
+        assert_eq!(convert("*.txt"), "^[^/]*\\.txt$");
+        assert_eq!(convert("file?.txt"), "^file[^/]\\.txt$");
+        assert_eq!(convert("a/**/b*"), "^a/(?:.*/)?b[^/]*$");
+        assert_eq!(convert("a/**"), "^a/.*$");
+        assert_eq!(convert("a**b"), "^a[^/]*b$");
+        assert_eq!(convert("!(x)"), "^(?:|[^/x]|[^/]*[^/][^/])$");
+        assert_eq!(convert("[!a]"), "^[^a/]$");
+        assert_eq!(convert("[+-0]"), "^[+-0&&[^/]]$");
+        assert_eq!(convert("[0-9]"), "^[0-9]$");
+    }
+
+    #[test]
+    fn test_globstar_segments() {
+        assert_eq!(micromatch_to_regex("a/**/b").unwrap(), "^a/(?:.*/)?b$");
+        assert_eq!(micromatch_to_regex("**/b").unwrap(), "^(?:.*/)?b$");
+        assert_eq!(
+            micromatch_to_regex("**/**/b").unwrap(),
+            "^(?:.*/)?(?:.*/)?b$"
+        );
+        assert_eq!(micromatch_to_regex("a/**").unwrap(), "^a/.*$");
+    }
+
+    #[test]
+    fn test_quantifier_braces() {
+        assert_eq!(micromatch_to_regex_with("a{2}", true).unwrap(), "^a{2}$");
+        assert_eq!(
+            micromatch_to_regex_with("[0-9]{1,3}x", true).unwrap(),
+            "^[0-9]{1,3}x$"
+        );
+        assert_eq!(micromatch_to_regex_with("a{2}", false).unwrap(), "^a(?:2)$");
+    }
+
+    #[test]
+    fn test_posix_classes() {
+        assert_eq!(micromatch_to_regex("[[:digit:]]").unwrap(), "^[[:digit:]]$");
+        assert_eq!(
+            micromatch_to_regex("[![:space:]]").unwrap(),
+            "^[^[:space:]]$"
+        );
+        assert_eq!(
+            micromatch_to_regex("[a-z[:digit:]]").unwrap(),
+            "^[a-z[:digit:]]$"
+        );
+        assert_eq!(micromatch_to_regex("[[]").unwrap(), "^[\\[]$");
+        assert!(micromatch_to_regex("[[:nope:]]").is_err());
+    }
+
+    #[test]
+    fn test_negated_classes() {
+        assert_eq!(micromatch_to_regex("[!a]").unwrap(), "^[^a]$");
+        assert_eq!(micromatch_to_regex("[^a]").unwrap(), "^[^a]$");
+        assert_eq!(micromatch_to_regex("[a^b]").unwrap(), "^[a\\^b]$");
+        assert_eq!(micromatch_to_regex("[!]]").unwrap(), "^[^\\]]$");
+    }
+
     #[test]
     fn test_brace_expansion() {
         assert_eq!(
             micromatch_to_regex("file.{txt,md}").unwrap(),
-            "^file\\.(?:^txt$|^md$)$"
+            "^file\\.(?:txt|md)$"
         );
+        assert_eq!(micromatch_to_regex("@(a|{b,c})").unwrap(), "^(?:a|b|c)$");
+        assert_eq!(micromatch_to_regex("x{1..3}").unwrap(), "^x(?:1|2|3)$");
+        assert_eq!(micromatch_to_regex("[{}]").unwrap(), "^[{}]$");
+        assert_eq!(micromatch_to_regex("{a").unwrap(), "^\\{a$");
+        assert_eq!(micromatch_to_regex("}{a,b}").unwrap(), "^\\}(?:a|b)$");
     }
 }
@@ -1,20 +1,117 @@
 // patterns/mod.rs
 pub mod brace;
 pub mod cache;
+pub mod explain;
+mod file;
+pub mod lint;
 pub mod micromatch;
+mod negate;
+mod normalize;
+mod persist;
+mod prefilter;
+pub mod trace;
 
 use crate::error::GlobError;
-use crate::options::GlobOptions;
-use globset::GlobSet;
+use crate::options::{GlobOptions, MatchMode, PathTraversalPolicy};
+use globset::{GlobSet, GlobSetBuilder};
+use regex::RegexSet;
+
+pub use explain::{CompiledPattern, PatternExplanation, PatternKind};
+pub use lint::{PatternWarning, WarningKind};
+pub use trace::{MatchTrace, TracedPattern};
+
+/// How the sub-groups of a combined [`Patterns`] are evaluated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Combine {
+    /// A path matches if any group matches
+    #[default]
+    Any,
+    /// A path matches only if every group matches
+    All,
+    /// A path matches if the first group matches and no other group does
+    Except,
+}
 
 /// Compiled patterns for efficient matching against paths
 ///
 /// This struct combines both glob patterns and regex patterns
-/// for flexible and efficient path matching.
+/// for flexible and efficient path matching. Patterns without a `/`
+/// are additionally compiled into a basename group when the
+/// [`MatchMode`] asks for file-name matching.
+///
+/// Sets built with [`Patterns::and`], [`Patterns::or`] or [`Patterns::except`] hold their
+/// operands in `groups` and match according to `combine`; their own
+/// glob and regex sets are empty.
+///
+/// Patterns ending in `/`, such as `build/`, match directories only, as in
+/// gitignore. They are compiled without the slash into `dir_only` and are
+/// consulted only for candidates known to be directories.
 #[derive(Clone)]
 pub struct Patterns {
     pub set: GlobSet,
     pub regexes: Vec<regex::Regex>,
+    pub regex_set: RegexSet,
+    pub basename_set: GlobSet,
+    pub basename_regexes: Vec<regex::Regex>,
+    pub basename_regex_set: RegexSet,
+    pub match_mode: MatchMode,
+    pub normalize_separators: bool,
+    /// Custom separator exchanged with `/` before matching, if any
+    pub separator: Option<char>,
+    pub combine: Combine,
+    pub groups: Vec<Patterns>,
+    pub dir_only: Option<Box<Patterns>>,
+    /// Whether any full-path or basename regex was compiled
+    pub has_regex: bool,
+    pub(crate) sources: persist::PatternSources,
+}
+
+/// Accumulates globs and regexes for one matching target
+struct GroupBuilder {
+    globs: GlobSetBuilder,
+    glob_sources: Vec<String>,
+    regexes: Vec<regex::Regex>,
+    /// Whether each regex was given explicitly rather than converted
+    explicit: Vec<bool>,
+    /// Literal segments of each full-path pattern, for pruning traversal
+    literals: Vec<prefilter::LiteralSegments>,
+}
+
+impl GroupBuilder {
+    fn new() -> Self {
+        Self {
+            globs: GlobSetBuilder::new(),
+            glob_sources: Vec::new(),
+            regexes: Vec::new(),
+            explicit: Vec::new(),
+            literals: Vec::new(),
+        }
+    }
+
+    /// Adds a regex, `explicit` if it came from a `re:` or `re~:` pattern
+    fn push_regex(&mut self, re: regex::Regex, explicit: bool) {
+        self.regexes.push(re);
+        self.explicit.push(explicit);
+    }
+
+    /// Builds the globset plus a combined RegexSet over the regexes
+    ///
+    /// The RegexSet answers "does anything match" in a single pass; the
+    /// individual regexes are kept for per-pattern identity, and the glob
+    /// strings so the group can be persisted.
+    fn build(self) -> Result<(GlobSet, Vec<regex::Regex>, RegexSet, Vec<String>), GlobError> {
+        let set = self
+            .globs
+            .build()
+            .map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
+        let regex_set =
+            RegexSet::new(self.regexes.iter().map(|re| re.as_str())).map_err(GlobError::Regex)?;
+        Ok((set, self.regexes, regex_set, self.glob_sources))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.glob_sources.is_empty() && self.regexes.is_empty()
+    }
 }
 
 impl Patterns {
@@ -34,100 +131,493 @@ impl Patterns {
     ///
     /// # Errors
     ///
-    /// Returns `GlobError::PathTraversal` for patterns attempting path traversal
+    /// Returns `GlobError::PathTraversal` for patterns whose `..` segments
+    /// escape the root, unless `opts.path_traversal` says otherwise.
+    /// Returns `GlobError::InvalidPatternAt` with the index and text of
+    /// the first pattern with invalid syntax.
     /// Returns other `GlobError` variants for invalid patterns
     pub fn compile_many<I, S>(patterns: I, opts: &GlobOptions) -> Result<Self, GlobError>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let mut builder = globset::GlobSetBuilder::new();
-        let mut regexes = Vec::new();
+        Self::compile_many_with_cache(patterns, opts, cache::global())
+    }
 
-        for pattern in patterns {
+    /// Compiles a single pattern into a Patterns instance
+    ///
+    /// This is a thin wrapper over [`Patterns::compile_many`], handy for
+    /// validating user input and testing strings with [`Patterns::matches_str`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Pattern string to compile
+    /// * `opts` - Glob options for configuration
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Patterns)` if successful, `Err(GlobError)` otherwise
+    pub fn compile_one(pattern: &str, opts: &GlobOptions) -> Result<Self, GlobError> {
+        Self::compile_many([pattern], opts)
+    }
+
+    /// Compiles multiple patterns using a caller-owned cache
+    ///
+    /// Behaves like [`Patterns::compile_many`], but compiled regexes are
+    /// looked up in and stored into `cache` instead of the global cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Iterator of pattern strings
+    /// * `opts` - Glob options for configuration
+    /// * `cache` - Cache to use for compiled regexes
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Patterns)` if successful, `Err(GlobError)` otherwise
+    pub fn compile_many_with_cache<I, S>(
+        patterns: I,
+        opts: &GlobOptions,
+        cache: &cache::PatternCache,
+    ) -> Result<Self, GlobError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut full = GroupBuilder::new();
+        let mut basename = GroupBuilder::new();
+        let mut dir_full = GroupBuilder::new();
+        let mut dir_basename = GroupBuilder::new();
+
+        let mut inputs = Vec::new();
+        let mut kinds = Vec::new();
+
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            inputs.push(pattern.as_ref().to_string());
             let pattern_str = pattern.as_ref().trim();
             if pattern_str.is_empty() {
                 continue;
             }
 
-            // Protect against path traversal in patterns
-            if pattern_str.contains("**/..") || pattern_str.contains("/../") {
-                return Err(GlobError::PathTraversal);
-            }
+            // Use `/` throughout unless backslashes are escapes; regexes keep theirs
+            let is_regex = !opts.literal && micromatch::explicit_regex(pattern_str).is_some();
+            let pattern_str = if opts.expand_tilde && !is_regex {
+                crate::fs::expand_tilde(pattern_str)
+            } else {
+                pattern_str.into()
+            };
+            let pattern_str = if opts.normalize_separators && opts.separator.is_none() && !is_regex
+            {
+                match crate::windows::normalize_separators(&pattern_str) {
+                    std::borrow::Cow::Owned(normalized) => normalized.into(),
+                    std::borrow::Cow::Borrowed(_) => pattern_str,
+                }
+            } else {
+                pattern_str
+            };
+            // A custom separator trades places with `/`, which globs split on
+            let pattern_str = match opts.separator {
+                Some(sep) if !is_regex => exchange_separator(&pattern_str, sep).into_owned().into(),
+                _ => pattern_str,
+            };
+            // Drive letters are case-insensitive; candidates are uppercased too
+            let pattern_str = match crate::windows::normalize_drive_letter(&pattern_str) {
+                std::borrow::Cow::Owned(normalized) if !is_regex => normalized.into(),
+                _ => pattern_str,
+            };
+
+            // A trailing `/` restricts a glob to directories; match without it
+            let (pattern_str, full, basename) = match pattern_str.strip_suffix('/') {
+                Some(dir) if !dir.is_empty() && !is_regex => {
+                    (dir, &mut dir_full, &mut dir_basename)
+                }
+                _ => (pattern_str.as_ref(), &mut full, &mut basename),
+            };
 
             // Process each pattern individually
-            Self::process_pattern(pattern_str, &mut builder, &mut regexes, opts)?;
+            let kind = Self::process_pattern(pattern_str, full, basename, opts, cache).map_err(
+                |e| match e {
+                    // Syntax errors name the offending input pattern
+                    GlobError::InvalidPattern(message) => GlobError::InvalidPatternAt {
+                        index,
+                        pattern: pattern.as_ref().to_string(),
+                        message,
+                    },
+                    GlobError::Regex(e) => GlobError::InvalidPatternAt {
+                        index,
+                        pattern: pattern.as_ref().to_string(),
+                        message: e.to_string(),
+                    },
+                    other => other,
+                },
+            )?;
+            kinds.push(kind);
         }
 
-        let set = builder
-            .build()
-            .map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
+        let mut compiled = Self::from_groups(full, basename, opts)?;
+        compiled.sources.key = persist::cache_key(&inputs, opts);
+        compiled.sources.kinds = kinds;
+        if !dir_full.is_empty() || !dir_basename.is_empty() {
+            let dir_only = Self::from_groups(dir_full, dir_basename, opts)?;
+            compiled.dir_only = Some(Box::new(dir_only));
+        }
+        Ok(compiled)
+    }
+
+    /// Builds a flat pattern set from its full-path and basename groups
+    fn from_groups(
+        mut full: GroupBuilder,
+        basename: GroupBuilder,
+        opts: &GlobOptions,
+    ) -> Result<Self, GlobError> {
+        let literals = std::mem::take(&mut full.literals);
+        let explicit_regexes = std::mem::take(&mut full.explicit);
+        let (set, regexes, regex_set, globs) = full.build()?;
+        let (basename_set, basename_regexes, basename_regex_set, basename_globs) =
+            basename.build()?;
+        let has_regex = !regexes.is_empty() || !basename_regexes.is_empty();
+
+        Ok(Self {
+            set,
+            regexes,
+            regex_set,
+            basename_set,
+            basename_regexes,
+            basename_regex_set,
+            match_mode: opts.match_mode,
+            normalize_separators: opts.normalize_separators && opts.separator.is_none(),
+            separator: opts.separator,
+            combine: Combine::Any,
+            groups: Vec::new(),
+            dir_only: None,
+            has_regex,
+            sources: persist::PatternSources {
+                key: 0,
+                globs,
+                basename_globs,
+                kinds: Vec::new(),
+                literals,
+                explicit_regexes,
+            },
+        })
+    }
+
+    /// Combines two pattern sets so that a path must match both
+    ///
+    /// Chained calls flatten, so `a.and(b).and(c)` requires all three.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Pattern set that must also match
+    ///
+    /// # Returns
+    ///
+    /// A combined `Patterns` requiring every operand to match
+    pub fn and(self, other: Patterns) -> Patterns {
+        self.combine_with(other, Combine::All)
+    }
 
-        Ok(Self { set, regexes })
+    /// Combines two pattern sets so that a path may match either
+    ///
+    /// Chained calls flatten, so `a.or(b).or(c)` accepts any of the three.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Alternative pattern set
+    ///
+    /// # Returns
+    ///
+    /// A combined `Patterns` accepting a match from any operand
+    pub fn or(self, other: Patterns) -> Patterns {
+        self.combine_with(other, Combine::Any)
+    }
+
+    /// Combines two pattern sets so that paths matching `other` are excluded
+    ///
+    /// Chained calls flatten, so `a.except(b).except(c)` accepts paths
+    /// matching `a` but neither `b` nor `c`.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Pattern set whose matches are rejected
+    ///
+    /// # Returns
+    ///
+    /// A combined `Patterns` accepting matches of `self` not matched by `other`
+    pub fn except(self, other: Patterns) -> Patterns {
+        self.combine_with(other, Combine::Except)
+    }
+
+    /// Builds (or extends) a combined set with the given semantics
+    fn combine_with(mut self, other: Patterns, combine: Combine) -> Patterns {
+        if !self.groups.is_empty() && self.combine == combine {
+            self.groups.push(other);
+            return self;
+        }
+
+        Patterns {
+            set: GlobSet::empty(),
+            regexes: Vec::new(),
+            regex_set: RegexSet::empty(),
+            basename_set: GlobSet::empty(),
+            basename_regexes: Vec::new(),
+            basename_regex_set: RegexSet::empty(),
+            match_mode: self.match_mode,
+            normalize_separators: self.normalize_separators,
+            separator: self.separator,
+            combine,
+            groups: vec![self, other],
+            dir_only: None,
+            has_regex: false,
+            sources: persist::PatternSources::default(),
+        }
     }
 
     /// Processes a single pattern, handling brace expansion and type detection
+    ///
+    /// Explicit `re:`, `re~:` and `ext:` patterns always target the full
+    /// path; an extension is at its end either way.
+    /// Other patterns without a `/` are routed to the basename group
+    /// according to the configured [`MatchMode`]. Under `opts.literal` the
+    /// pattern is matched verbatim: no braces, prefixes or metacharacters.
+    ///
+    /// # Returns
+    ///
+    /// How the pattern was compiled, as reported by [`Patterns::pattern_kinds`]
     fn process_pattern(
         pattern: &str,
-        builder: &mut globset::GlobSetBuilder,
-        regexes: &mut Vec<regex::Regex>,
-        _opts: &GlobOptions,
-    ) -> Result<(), GlobError> {
-        // Check if brace expansion is needed
-        let expanded_patterns = if pattern.contains('{') && pattern.contains('}') {
-            brace::expand(pattern)?
-        } else {
-            vec![pattern.to_string()]
-        };
+        full: &mut GroupBuilder,
+        basename: &mut GroupBuilder,
+        opts: &GlobOptions,
+        cache: &cache::PatternCache,
+    ) -> Result<PatternKind, GlobError> {
+        // Check if brace expansion is needed; expansions are consumed lazily
+        let expanded_patterns: Box<dyn Iterator<Item = Result<String, GlobError>>> =
+            if !opts.literal && pattern.contains('{') && pattern.contains('}') {
+                Box::new(brace::expand_iter_with(
+                    pattern,
+                    opts.regex_quantifier_braces,
+                ))
+            } else {
+                Box::new(std::iter::once(Ok(pattern.to_string())))
+            };
 
+        // Kind of the last expansion, which is the pattern's if it had no braces
+        let mut kind = PatternKind::SimpleGlob;
+        let mut braced = false;
         for expanded in expanded_patterns {
-            // Handle explicit regex patterns (prefixed with "re:")
-            if let Some(regex_pattern) = expanded.strip_prefix("re:") {
-                let re = cache::get_or_compile_regex(regex_pattern)?;
-                regexes.push(re);
+            let expanded = expanded?;
+            braced |= expanded != pattern;
+
+            // Handle explicit regex patterns (prefixed with "re:", "re~:" or "ext:")
+            if let Some(regex_pattern) =
+                micromatch::explicit_regex(&expanded).filter(|_| !opts.literal)
+            {
+                let regex_pattern = Self::with_case_flag(&regex_pattern, opts);
+                let re = cache.get_or_compile_regex_limited(&regex_pattern, &opts.regex_limits)?;
+                full.push_regex(re, true);
+                full.literals.push(prefilter::LiteralSegments::any());
+                kind = PatternKind::Regex;
                 continue;
             }
 
-            // Determine if pattern requires regex conversion
-            if Self::is_complex_pattern(&expanded) {
+            // Protect against path traversal, after braces are expanded
+            let expanded = Self::resolve_parent_segments(&expanded, opts.path_traversal)?;
+
+            // Decide which groups this pattern belongs to
+            let (to_full, to_basename) = if expanded.contains('/') {
+                (true, false)
+            } else {
+                match opts.match_mode {
+                    MatchMode::FullPath => (true, false),
+                    MatchMode::Basename => (false, true),
+                    MatchMode::Both => (true, true),
+                }
+            };
+            if to_full {
+                full.literals
+                    .push(prefilter::LiteralSegments::new(&expanded, opts));
+            }
+
+            // Determine if pattern requires regex conversion; full Unicode
+            // case folding is only available from the regex engine
+            let unicode_fold = opts.unicode_case_fold && !opts.case_sensitive;
+            if opts.literal && !unicode_fold {
+                kind = PatternKind::SimpleGlob;
+                let escaped = Self::escape_literal(&expanded);
+                if to_full {
+                    Self::add_glob_pattern(&escaped, opts, full)?;
+                }
+                if to_basename {
+                    Self::add_glob_pattern(&escaped, opts, basename)?;
+                }
+            } else if unicode_fold || Self::needs_regex(&expanded, opts) {
+                kind = PatternKind::ExtGlob;
                 // Convert complex patterns to regex
-                let regex_pattern = micromatch::micromatch_to_regex(&expanded)?;
-                let re = cache::get_or_compile_regex(&regex_pattern)?;
-                regexes.push(re);
+                let regex_pattern = if opts.literal {
+                    format!("^{}$", regex::escape(&expanded))
+                } else {
+                    micromatch::micromatch_to_regex_with_case(
+                        &expanded,
+                        opts.regex_quantifier_braces,
+                        opts.separator_is_literal(),
+                        opts.match_dotfiles,
+                        opts.case_sensitive,
+                    )?
+                };
+                let regex_pattern = Self::with_case_flag(&regex_pattern, opts);
+                let re = cache.get_or_compile_regex_limited(&regex_pattern, &opts.regex_limits)?;
+                if to_full {
+                    full.push_regex(re.clone(), false);
+                }
+                if to_basename {
+                    basename.push_regex(re, false);
+                }
             } else {
+                kind = PatternKind::SimpleGlob;
                 // Process as regular glob pattern
-                Self::add_glob_pattern(&expanded, builder)?;
+                if to_full {
+                    Self::add_glob_pattern(&expanded, opts, full)?;
+                }
+                if to_basename {
+                    Self::add_glob_pattern(&expanded, opts, basename)?;
+                }
             }
         }
 
-        Ok(())
+        Ok(if braced {
+            PatternKind::BraceExpanded
+        } else {
+            kind
+        })
+    }
+
+    /// Resolves `..` segments in a glob pattern according to `policy`
+    ///
+    /// The pattern is split on `/` and each `..` cancels the preceding
+    /// literal or wildcard segment. A `..` with nothing to cancel, or one
+    /// following `**` (which may match zero segments), escapes the root.
+    /// A `..` embedded in a segment such as `foo..bar` is an ordinary name.
+    ///
+    /// # Returns
+    ///
+    /// The resolved pattern, or `GlobError::PathTraversal` if it escapes
+    /// the root under [`PathTraversalPolicy::Reject`]
+    fn resolve_parent_segments(
+        pattern: &str,
+        policy: PathTraversalPolicy,
+    ) -> Result<String, GlobError> {
+        if policy == PathTraversalPolicy::Allow || !pattern.split('/').any(|seg| seg == "..") {
+            return Ok(pattern.to_string());
+        }
+
+        let mut segments: Vec<&str> = Vec::new();
+        for seg in pattern.split('/') {
+            if seg != ".." {
+                segments.push(seg);
+                continue;
+            }
+            match segments.last() {
+                Some(&prev) if !matches!(prev, "" | "." | "**") => {
+                    segments.pop();
+                }
+                _ if policy == PathTraversalPolicy::Reject => {
+                    return Err(GlobError::PathTraversal);
+                }
+                // Normalize: a `..` at the root stays at the root
+                _ => {}
+            }
+        }
+
+        Ok(segments.join("/"))
+    }
+
+    /// Escapes every glob metacharacter so the pattern matches itself
+    ///
+    /// Builds on `globset::escape`, which leaves `\` alone; it is doubled
+    /// where globset reads it as an escape (everywhere but Windows).
+    fn escape_literal(pattern: &str) -> String {
+        let escaped = globset::escape(pattern);
+        if cfg!(windows) {
+            escaped
+        } else {
+            escaped.replace('\\', "\\\\")
+        }
     }
 
     /// Checks if a pattern contains advanced glob features requiring regex
+    ///
+    /// Backslash-escaped metacharacters are literals and don't count.
     fn is_complex_pattern(pattern: &str) -> bool {
-        // Check for extended glob features that require regex conversion
-        pattern.contains('@')
-            || pattern.contains('!')
-            || pattern.contains('+')
-            || pattern.contains('?')
-            || pattern.contains('(')
-            || pattern.contains(')')
-            || pattern.contains('[')
-            || pattern.contains(']')
-            || pattern.contains('{')
-            || pattern.contains('}')
-            || pattern.contains('|')
-    }
-
-    /// Adds a glob pattern to the globset builder
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                // Extended glob features that require regex conversion
+                '@' | '!' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Checks if a glob must be translated to a regex under `opts`
+    ///
+    /// Besides complex patterns, globset cannot keep a segment-leading
+    /// wildcard off dotfiles, so those need the regex path when
+    /// `match_dotfiles` is unset.
+    fn needs_regex(pattern: &str, opts: &GlobOptions) -> bool {
+        Self::is_complex_pattern(pattern)
+            || (!opts.match_dotfiles
+                && pattern
+                    .split('/')
+                    .any(|segment| segment.starts_with(['*', '?'])))
+    }
+
+    /// Prefixes a regex with the case-insensitive flag when requested
+    ///
+    /// The `regex` crate applies Unicode simple case folding under `(?i)`.
+    fn with_case_flag<'a>(regex: &'a str, opts: &GlobOptions) -> std::borrow::Cow<'a, str> {
+        if opts.case_sensitive {
+            regex.into()
+        } else {
+            format!("(?i){}", regex).into()
+        }
+    }
+
+    /// Compiles one glob with the options' case and separator settings
+    ///
+    /// Case-insensitive globs use globset's ASCII case folding. Under
+    /// `literal_separator`, `*` and `?` stop at `/`; with an explicit
+    /// separator `\` is also always an escape.
+    pub(crate) fn build_glob(
+        pattern: &str,
+        opts: &GlobOptions,
+    ) -> Result<globset::Glob, GlobError> {
+        let mut builder = globset::GlobBuilder::new(pattern);
+        builder
+            .case_insensitive(!opts.case_sensitive)
+            .literal_separator(opts.separator_is_literal());
+        if let Some(sep) = opts.separator {
+            builder.backslash_escape(sep != '\\');
+        }
+        builder
+            .build()
+            .map_err(|e| GlobError::InvalidPattern(e.to_string()))
+    }
+
+    /// Adds a glob pattern to a group's globset builder
+    ///
+    /// The glob is compiled by [`Patterns::build_glob`].
     fn add_glob_pattern(
         pattern: &str,
-        builder: &mut globset::GlobSetBuilder,
+        opts: &GlobOptions,
+        group: &mut GroupBuilder,
     ) -> Result<(), GlobError> {
-        let glob =
-            globset::Glob::new(pattern).map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
-
-        builder.add(glob);
+        group.globs.add(Self::build_glob(pattern, opts)?);
+        group.glob_sources.push(pattern.to_string());
         Ok(())
     }
 
@@ -141,16 +631,90 @@ impl Patterns {
     ///
     /// `true` if the path matches any pattern, `false` otherwise
     pub fn is_match(&self, path: &camino::Utf8PathBuf) -> bool {
-        let path_str = path.as_str();
+        self.matches_str(path.as_str())
+    }
+
+    /// Checks if a plain string matches any of the compiled patterns
+    ///
+    /// No filesystem access is involved, so this works for arbitrary
+    /// keys such as object-store names. The full string is tried first;
+    /// the basename group is consulted only when it holds patterns.
+    /// Backslashes are read as separators when `normalize_separators` is set.
+    /// A string ending in `/` names a directory, so `build/` is matched
+    /// as the directory `build`.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - String to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the string matches any pattern, `false` otherwise
+    pub fn matches_str(&self, s: &str) -> bool {
+        self.matches_entry_str(s, false)
+    }
+
+    /// Checks if a string matches, knowing whether it names a directory
+    ///
+    /// Directory-only patterns (those written with a trailing `/`) can
+    /// match only when `is_dir` is set or `s` ends in `/`.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - String to check
+    /// * `is_dir` - Whether the string is the path of a directory
+    ///
+    /// # Returns
+    ///
+    /// `true` if the string matches any applicable pattern, `false` otherwise
+    pub fn matches_entry_str(&self, s: &str, is_dir: bool) -> bool {
+        if !self.groups.is_empty() {
+            return match self.combine {
+                Combine::Any => self.groups.iter().any(|g| g.matches_entry_str(s, is_dir)),
+                Combine::All => self.groups.iter().all(|g| g.matches_entry_str(s, is_dir)),
+                Combine::Except => {
+                    let (first, rest) = self.groups.split_first().unwrap();
+                    first.matches_entry_str(s, is_dir)
+                        && !rest.iter().any(|g| g.matches_entry_str(s, is_dir))
+                }
+            };
+        }
+
+        let (s, raw) = self.candidate_forms(s);
+        let (s, raw, is_dir) = match s.strip_suffix('/') {
+            Some(dir) if !dir.is_empty() => (dir, raw.as_deref().map(strip_slash), true),
+            _ => (s.as_ref(), raw.as_deref(), is_dir),
+        };
+
+        if is_dir && self.dir_only.as_ref().is_some_and(|d| d.matches_str(s)) {
+            return true;
+        }
+
+        // Rule out paths missing a segment every full-path pattern requires
+        let by_name = !self.basename_set.is_empty() || !self.basename_regexes.is_empty();
+        if !by_name && !self.sources.literals.iter().any(|l| l.may_match(s)) {
+            return false;
+        }
 
         // First check globset (usually faster)
-        if !self.set.is_empty() && self.set.is_match(path_str) {
+        if !self.set.is_empty() && self.set.is_match(s) {
+            return true;
+        }
+
+        // Then check all regexes in a single pass, unless there are none
+        if self.has_regex && self.matches_regexes(s, raw) {
             return true;
         }
 
-        // Then check regexes
-        for re in &self.regexes {
-            if re.is_match(path_str) {
+        // Finally check slash-less patterns against the file name
+        if self.basename_set.is_empty() && self.basename_regexes.is_empty() {
+            return false;
+        }
+        if let Some(name) = camino::Utf8Path::new(s).file_name() {
+            if !self.basename_set.is_empty() && self.basename_set.is_match(name) {
+                return true;
+            }
+            if self.has_regex && self.basename_regex_set.is_match(name) {
                 return true;
             }
         }
@@ -158,10 +722,60 @@ impl Patterns {
         false
     }
 
-    /// Quickly checks if a path could potentially match any pattern
+    /// Checks the full-path regexes against a candidate
+    ///
+    /// `raw` is the candidate before its drive letter was uppercased, if
+    /// that changed it; explicit regexes are matched against it, since
+    /// their drive letters are left as written too.
+    fn matches_regexes(&self, s: &str, raw: Option<&str>) -> bool {
+        let Some(raw) = raw.filter(|_| self.sources.explicit_regexes.contains(&true)) else {
+            return self.regex_set.is_match(s);
+        };
+        self.regexes
+            .iter()
+            .zip(&self.sources.explicit_regexes)
+            .any(|(re, &explicit)| re.is_match(if explicit { raw } else { s }))
+    }
+
+    /// Rewrites a candidate into the form the patterns were compiled for
     ///
-    /// This is a preliminary check before exact matching that can
-    /// help avoid unnecessary work for obviously non-matching paths.
+    /// A custom separator is exchanged with `/`; otherwise backslashes
+    /// become `/` when `normalize_separators` is set. On Windows a leading
+    /// drive letter is uppercased, as it is in patterns other than
+    /// explicit regexes.
+    pub(crate) fn candidate<'a>(&self, s: &'a str) -> std::borrow::Cow<'a, str> {
+        self.candidate_forms(s).0
+    }
+
+    /// Rewrites a candidate as [`Patterns::candidate`] does, also returning
+    /// the form before its drive letter was uppercased if that changed it
+    ///
+    /// Explicit regexes are matched against that second form.
+    pub(crate) fn candidate_forms<'a>(
+        &self,
+        s: &'a str,
+    ) -> (std::borrow::Cow<'a, str>, Option<std::borrow::Cow<'a, str>>) {
+        let s = match self.separator {
+            Some(sep) => exchange_separator(s, sep),
+            None if self.normalize_separators => crate::windows::normalize_separators(s),
+            None => s.into(),
+        };
+        match crate::windows::normalize_drive_letter(&s) {
+            std::borrow::Cow::Owned(normalized) => (normalized.into(), Some(s)),
+            std::borrow::Cow::Borrowed(_) => (s, None),
+        }
+    }
+
+    /// Quickly checks if a path, or anything below it, could match any pattern
+    ///
+    /// Each full-path pattern's leading segments are recorded at compile
+    /// time, so `src/**/mod.rs` rules out every directory outside `src`,
+    /// and `*/src/**` (with `literal_separator`) every directory whose
+    /// second segment isn't `src`. Traversal uses this to skip such
+    /// directories unread. Literal segments after a `**`, like `src` in
+    /// `**/src/**/mod.rs`, can be reached from any directory; they only
+    /// reject entries, in [`Patterns::matches_entry_str`]. Slash-less
+    /// patterns matched against the file name can match anywhere.
     ///
     /// # Arguments
     ///
@@ -171,7 +785,28 @@ impl Patterns {
     ///
     /// `true` if the path might match, `false` if it definitely won't
     pub fn could_match(&self, path: &camino::Utf8PathBuf) -> bool {
-        self.set.is_match(path.as_str()) || !self.regexes.is_empty()
+        self.could_match_str(path.as_str())
+    }
+
+    /// String form of [`Patterns::could_match`]
+    pub(crate) fn could_match_str(&self, s: &str) -> bool {
+        if !self.groups.is_empty() {
+            return match self.combine {
+                Combine::Any => self.groups.iter().any(|g| g.could_match_str(s)),
+                Combine::All => self.groups.iter().all(|g| g.could_match_str(s)),
+                // Exclusions cannot rule out descendants of a path
+                Combine::Except => self.groups[0].could_match_str(s),
+            };
+        }
+
+        if self.dir_only.as_ref().is_some_and(|d| d.could_match_str(s)) {
+            return true;
+        }
+        if !self.basename_set.is_empty() || !self.basename_regexes.is_empty() {
+            return true;
+        }
+        let s = self.candidate(s);
+        self.sources.literals.iter().any(|l| l.admits(&s))
     }
 }
 
@@ -179,3 +814,27 @@ impl Patterns {
 pub fn cache_metrics() -> (cache::CacheMetrics, cache::CacheMetrics) {
     (cache::glob_cache_metrics(), cache::regex_cache_metrics())
 }
+
+/// Drops the trailing `/` of a candidate already known to have one
+fn strip_slash(s: &str) -> &str {
+    &s[..s.len() - 1]
+}
+
+/// Exchanges `sep` and `/` throughout `s`
+///
+/// Patterns and candidates are both rewritten this way, so `sep` acts as
+/// the path separator while a literal `/` still only matches itself.
+/// Borrows the input when there is nothing to exchange.
+fn exchange_separator(s: &str, sep: char) -> std::borrow::Cow<'_, str> {
+    if sep == '/' || !s.contains([sep, '/']) {
+        return s.into();
+    }
+    s.chars()
+        .map(|c| match c {
+            '/' => sep,
+            c if c == sep => '/',
+            c => c,
+        })
+        .collect::<String>()
+        .into()
+}
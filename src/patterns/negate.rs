@@ -0,0 +1,595 @@
+// patterns/negate.rs
+use crate::error::GlobError;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Inclusive ranges of characters, by code point
+type Ranges = Vec<(u32, u32)>;
+
+/// Upper bound on the automaton states one `!(...)` may need
+const MAX_STATES: usize = 256;
+
+/// Longest prefix plus suffix that may decide a negated glob's strings
+const MAX_WINDOW: usize = 8;
+
+/// A regex that matches nothing, for a complement that is empty
+const NOTHING: &str = "[^\\x{0}-\\x{10FFFF}]";
+
+/// An NFA state: moves on one of the automaton's range sets, and free moves
+#[derive(Default)]
+struct State {
+    moves: Vec<(usize, usize)>,
+    free: Vec<usize>,
+}
+
+/// Thompson automaton for the alternatives of one `!(...)`
+#[derive(Default)]
+struct Nfa {
+    states: Vec<State>,
+    /// Characters the moves consume
+    sets: Vec<Ranges>,
+}
+
+impl Nfa {
+    /// Adds a state, allowing a few NFA states for each DFA state to come
+    fn add(&mut self) -> Result<usize, GlobError> {
+        if self.states.len() >= MAX_STATES * 4 {
+            return Err(too_complex());
+        }
+        self.states.push(State::default());
+        Ok(self.states.len() - 1)
+    }
+
+    /// Adds a state reached from `from` by one character in `ranges`
+    fn step(&mut self, from: usize, ranges: Ranges) -> Result<usize, GlobError> {
+        let to = self.add()?;
+        self.sets.push(ranges);
+        self.states[from].moves.push((self.sets.len() - 1, to));
+        Ok(to)
+    }
+
+    /// Adds the states for `hir` after `from`, returning the state it ends in
+    fn build(&mut self, hir: &Hir, from: usize) -> Result<usize, GlobError> {
+        match hir.kind() {
+            HirKind::Empty => Ok(from),
+            HirKind::Literal(literal) => {
+                let text = std::str::from_utf8(&literal.0).map_err(|_| unsupported())?;
+                text.chars()
+                    .try_fold(from, |at, c| self.step(at, vec![(c as u32, c as u32)]))
+            }
+            HirKind::Class(Class::Unicode(class)) => {
+                let ranges = class
+                    .ranges()
+                    .iter()
+                    .map(|r| (r.start() as u32, r.end() as u32))
+                    .collect();
+                self.step(from, ranges)
+            }
+            HirKind::Class(Class::Bytes(_)) | HirKind::Look(_) => Err(unsupported()),
+            HirKind::Repetition(rep) => {
+                let mut at = from;
+                for _ in 0..rep.min {
+                    at = self.build(&rep.sub, at)?;
+                }
+                let Some(max) = rep.max else {
+                    let entry = self.add()?;
+                    self.states[at].free.push(entry);
+                    let end = self.build(&rep.sub, entry)?;
+                    self.states[end].free.push(entry);
+                    return Ok(entry);
+                };
+                let exit = self.add()?;
+                for _ in rep.min..max {
+                    self.states[at].free.push(exit);
+                    at = self.build(&rep.sub, at)?;
+                }
+                self.states[at].free.push(exit);
+                Ok(exit)
+            }
+            HirKind::Capture(capture) => self.build(&capture.sub, from),
+            HirKind::Concat(parts) => parts.iter().try_fold(from, |at, part| self.build(part, at)),
+            HirKind::Alternation(branches) => {
+                let join = self.add()?;
+                for branch in branches {
+                    let end = self.build(branch, from)?;
+                    self.states[end].free.push(join);
+                }
+                Ok(join)
+            }
+        }
+    }
+
+    /// Collects the states reachable from `states` by free moves
+    fn closure(&self, states: impl IntoIterator<Item = usize>) -> BTreeSet<usize> {
+        let mut seen = BTreeSet::new();
+        let mut stack: Vec<usize> = states.into_iter().collect();
+        while let Some(state) = stack.pop() {
+            if seen.insert(state) {
+                stack.extend(&self.states[state].free);
+            }
+        }
+        seen
+    }
+}
+
+fn too_complex() -> GlobError {
+    GlobError::InvalidPattern("!(...) alternatives are too complex to negate".into())
+}
+
+fn unsupported() -> GlobError {
+    GlobError::InvalidPattern("!(...) alternatives must match within a path segment".into())
+}
+
+/// Splits the characters other than `/` into blocks that no range set cuts
+///
+/// `.` always gets a block of its own, so a leading dot can be excluded.
+fn blocks(sets: &[Ranges]) -> Vec<Ranges> {
+    let dot = '.' as u32;
+    let slash = '/' as u32;
+    let mut bounds = BTreeSet::from([0, dot, dot + 1, slash, slash + 1, 0x11_0000]);
+    for &(start, end) in sets.iter().flatten() {
+        bounds.insert(start);
+        bounds.insert(end + 1);
+    }
+
+    let bounds: Vec<u32> = bounds.into_iter().collect();
+    let mut by_members: BTreeMap<(bool, Vec<usize>), Ranges> = BTreeMap::new();
+    for pair in bounds.windows(2) {
+        let (start, end) = (pair[0], pair[1] - 1);
+        if start == slash || (0xD800..=0xDFFF).contains(&start) {
+            continue;
+        }
+        let members = (0..sets.len())
+            .filter(|&i| sets[i].iter().any(|&(lo, hi)| lo <= start && start <= hi))
+            .collect();
+        let ranges = by_members.entry((start == dot, members)).or_default();
+        match ranges.last_mut() {
+            Some(last) if last.1 + 1 == start => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    by_members.into_values().collect()
+}
+
+/// Writes a regex matching one character in `ranges`, which exclude `/`
+///
+/// Small sets are listed; large ones name what they leave out instead.
+fn class(ranges: &[(u32, u32)]) -> String {
+    let write = |c: u32| {
+        let c = char::from_u32(c).unwrap_or('\u{FFFD}');
+        match c {
+            c if c.is_ascii_alphanumeric() || c == '/' || c == '_' => c.to_string(),
+            '.' => "\\.".to_string(),
+            c => format!("\\x{{{:X}}}", c as u32),
+        }
+    };
+    let list = |ranges: &[(u32, u32)]| {
+        let mut out = String::new();
+        for &(start, end) in ranges {
+            out.push_str(&write(start));
+            if end > start {
+                out.push('-');
+                out.push_str(&write(end));
+            }
+        }
+        out
+    };
+
+    // Blocks may span the surrogates, which are not characters
+    let mut merged: Ranges = Vec::new();
+    for &(start, end) in ranges {
+        let start = if (0xD800..=0xDFFF).contains(&start) {
+            0xE000
+        } else {
+            start
+        };
+        let end = if (0xD800..=0xDFFF).contains(&end) {
+            0xD7FF
+        } else {
+            end
+        };
+        match merged.last_mut() {
+            Some(last) if last.1 + 1 >= start || (last.1 == 0xD7FF && start == 0xE000) => {
+                last.1 = last.1.max(end)
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    if let [(start, end)] = merged[..] {
+        if start == end {
+            return regex_syntax::escape(&char::from_u32(start).unwrap().to_string());
+        }
+    }
+
+    let mut missing = Vec::new();
+    let mut at = 0;
+    for &(start, end) in &merged {
+        if start > at {
+            missing.push((at, start - 1));
+        }
+        at = end + 1;
+    }
+    if at <= 0x10_FFFF {
+        missing.push((at, 0x10_FFFF));
+    }
+    // `/` is always missing, and so are the surrogates if a range spans them
+    missing.retain(|&(start, end)| !(start >= 0xD800 && end <= 0xDFFF));
+    if missing.len() < merged.len() {
+        format!("[^{}]", list(&missing))
+    } else {
+        format!("[{}]", list(&merged))
+    }
+}
+
+/// Joins regexes as alternatives
+fn union(alternatives: &[String]) -> String {
+    match alternatives {
+        [one] => one.clone(),
+        many => format!("(?:{})", many.join("|")),
+    }
+}
+
+/// Deterministic automaton over blocks of characters other than `/`
+struct Dfa {
+    blocks: Vec<Ranges>,
+    /// Target of each state on each block
+    next: Vec<Vec<usize>>,
+    accepts: Vec<bool>,
+    start: usize,
+}
+
+impl Dfa {
+    /// Runs the subset construction on `nfa`, accepting where it reaches `end`
+    fn new(nfa: &Nfa, start: usize, end: usize) -> Result<Self, GlobError> {
+        let blocks = blocks(&nfa.sets);
+        // Which range sets take each block in whole
+        let takes: Vec<Vec<bool>> = blocks
+            .iter()
+            .map(|block| {
+                let first = block[0].0;
+                let takes = |set: &Ranges| set.iter().any(|&(lo, hi)| lo <= first && first <= hi);
+                nfa.sets.iter().map(takes).collect()
+            })
+            .collect();
+
+        let mut sets: Vec<BTreeSet<usize>> = vec![nfa.closure([start])];
+        let mut ids: HashMap<BTreeSet<usize>, usize> = HashMap::from([(sets[0].clone(), 0)]);
+        let mut next: Vec<Vec<usize>> = Vec::new();
+        while next.len() < sets.len() {
+            let current = sets[next.len()].clone();
+            let mut row = Vec::with_capacity(blocks.len());
+            for takes in &takes {
+                let targets = current.iter().flat_map(|&state| {
+                    nfa.states[state]
+                        .moves
+                        .iter()
+                        .filter(|&&(set, _)| takes[set])
+                        .map(|&(_, to)| to)
+                });
+                let target = nfa.closure(targets);
+                let id = match ids.get(&target) {
+                    Some(&id) => id,
+                    None if sets.len() >= MAX_STATES => return Err(too_complex()),
+                    None => {
+                        ids.insert(target.clone(), sets.len());
+                        sets.push(target);
+                        sets.len() - 1
+                    }
+                };
+                row.push(id);
+            }
+            next.push(row);
+        }
+
+        Ok(Self {
+            accepts: sets.iter().map(|set| set.contains(&end)).collect(),
+            blocks,
+            next,
+            start: 0,
+        })
+    }
+
+    /// Accepts exactly what was rejected, and with `exclude_dot` nothing starting with `.`
+    fn complement(mut self, exclude_dot: bool) -> Self {
+        for accepts in &mut self.accepts {
+            *accepts = !*accepts;
+        }
+        if exclude_dot {
+            let dot = self
+                .blocks
+                .iter()
+                .position(|b| b[0].0 == '.' as u32)
+                .unwrap();
+            let sink = self.next.len();
+            self.next.push(vec![sink; self.blocks.len()]);
+            self.accepts.push(false);
+            // A fresh start state, as the old one may be entered again later
+            let mut row = self.next[self.start].clone();
+            row[dot] = sink;
+            self.next.push(row);
+            self.accepts.push(self.accepts[self.start]);
+            self.start = self.next.len() - 1;
+        }
+        self
+    }
+
+    /// Spells the automaton out as a regex
+    ///
+    /// Most negated globs accept a string by its first `prefix` and last
+    /// `suffix` characters alone, whatever lies between. These are written
+    /// as short strings plus `prefix[^/]*suffix`, with a single level of
+    /// repetition as the default [`RegexLimits`](crate::options::RegexLimits)
+    /// require. Others fall back to state elimination, which nests.
+    fn regex(&self) -> String {
+        let alternatives = (0..=MAX_WINDOW)
+            .flat_map(|total| (0..=total).map(move |prefix| (prefix, total - prefix)))
+            .find_map(|(prefix, suffix)| self.windowed(prefix, suffix))
+            .unwrap_or_else(|| self.eliminated());
+        if alternatives.is_empty() {
+            NOTHING.to_string()
+        } else {
+            union(&alternatives)
+        }
+    }
+
+    /// Writes the language as strings decided by their ends, if it is one
+    fn windowed(&self, prefix: usize, suffix: usize) -> Option<Vec<String>> {
+        let mut after_prefix = BTreeSet::from([self.start]);
+        for _ in 0..prefix {
+            after_prefix = after_prefix
+                .iter()
+                .flat_map(|&state| self.next[state].iter().copied())
+                .collect();
+        }
+        let mut memo = HashMap::new();
+        if !after_prefix
+            .iter()
+            .all(|&state| self.synchronizes(self.reachable(state), suffix, &mut memo))
+        {
+            return None;
+        }
+
+        let accepts = |state: usize| self.accepts[state];
+        let mut alternatives = self.words(
+            self.start,
+            prefix + suffix,
+            true,
+            &accepts,
+            &mut HashMap::new(),
+        );
+        for state in after_prefix {
+            let start = self.words(
+                self.start,
+                prefix,
+                false,
+                &|s| s == state,
+                &mut HashMap::new(),
+            );
+            let end = self.words(state, suffix, false, &accepts, &mut HashMap::new());
+            if !start.is_empty() && !end.is_empty() {
+                alternatives.push(format!("{}[^/]*{}", union(&start), union(&end)));
+            }
+        }
+        Some(alternatives)
+    }
+
+    /// Collects the states reachable from `state`, itself included
+    fn reachable(&self, state: usize) -> BTreeSet<usize> {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![state];
+        while let Some(state) = stack.pop() {
+            if seen.insert(state) {
+                stack.extend(&self.next[state]);
+            }
+        }
+        seen
+    }
+
+    /// Checks whether any `len` blocks lead every state in `states` to the same state
+    fn synchronizes(
+        &self,
+        states: BTreeSet<usize>,
+        len: usize,
+        memo: &mut HashMap<(BTreeSet<usize>, usize), bool>,
+    ) -> bool {
+        if states.len() <= 1 {
+            return true;
+        }
+        if len == 0 {
+            return false;
+        }
+        if let Some(&known) = memo.get(&(states.clone(), len)) {
+            return known;
+        }
+        let merges = (0..self.blocks.len()).all(|block| {
+            let image = states
+                .iter()
+                .map(|&state| self.next[state][block])
+                .collect();
+            self.synchronizes(image, len - 1, memo)
+        });
+        memo.insert((states, len), merges);
+        merges
+    }
+
+    /// Writes the strings of `len` blocks, or with `shorter` of fewer, that
+    /// lead from `state` to a state `target` picks, as alternatives
+    ///
+    /// `memo` holds earlier results for the same `shorter` and `target`.
+    fn words(
+        &self,
+        state: usize,
+        len: usize,
+        shorter: bool,
+        target: &dyn Fn(usize) -> bool,
+        memo: &mut HashMap<(usize, usize), Vec<String>>,
+    ) -> Vec<String> {
+        if let Some(known) = memo.get(&(state, len)) {
+            return known.clone();
+        }
+        let mut alternatives = Vec::new();
+        if len == 0 {
+            if target(state) && !shorter {
+                alternatives.push(String::new());
+            }
+            return alternatives;
+        }
+        if shorter && target(state) {
+            alternatives.push(String::new());
+        }
+        // Blocks whose continuations read the same share one class
+        let mut by_rest: BTreeMap<String, Ranges> = BTreeMap::new();
+        for (block, &next) in self.next[state].iter().enumerate() {
+            let rest = self.words(next, len - 1, shorter, target, memo);
+            if !rest.is_empty() {
+                let rest = union(&rest);
+                by_rest.entry(rest).or_default().extend(&self.blocks[block]);
+            }
+        }
+        for (rest, mut ranges) in by_rest {
+            ranges.sort_unstable();
+            alternatives.push(format!("{}{}", class(&ranges), rest));
+        }
+        memo.insert((state, len), alternatives.clone());
+        alternatives
+    }
+
+    /// Writes the language by eliminating states one at a time
+    fn eliminated(&self) -> Vec<String> {
+        let (first, last) = (self.next.len(), self.next.len() + 1);
+        let mut labels: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+        labels.insert((first, self.start), vec![String::new()]);
+        for (state, row) in self.next.iter().enumerate() {
+            let mut by_target: BTreeMap<usize, Ranges> = BTreeMap::new();
+            for (block, &target) in row.iter().enumerate() {
+                by_target
+                    .entry(target)
+                    .or_default()
+                    .extend(&self.blocks[block]);
+            }
+            for (target, mut ranges) in by_target {
+                ranges.sort_unstable();
+                labels.insert((state, target), vec![class(&ranges)]);
+            }
+            if self.accepts[state] {
+                labels.insert((state, last), vec![String::new()]);
+            }
+        }
+        for state in 0..self.next.len() {
+            let looping = labels
+                .remove(&(state, state))
+                .map(|l| format!("(?:{})*", union(&l)));
+            let into: Vec<(usize, String)> = labels
+                .iter()
+                .filter(|&(&(_, to), _)| to == state)
+                .map(|(&(from, _), l)| (from, union(l)))
+                .collect();
+            let out: Vec<(usize, String)> = labels
+                .iter()
+                .filter(|&(&(from, _), _)| from == state)
+                .map(|(&(_, to), l)| (to, union(l)))
+                .collect();
+            labels.retain(|&(from, to), _| from != state && to != state);
+            for (from, before) in &into {
+                for (to, after) in &out {
+                    let path = format!("{}{}{}", before, looping.as_deref().unwrap_or(""), after);
+                    labels.entry((*from, *to)).or_default().push(path);
+                }
+            }
+        }
+        labels.remove(&(first, last)).unwrap_or_default()
+    }
+}
+
+/// Builds a regex for every segment string that no alternative matches
+///
+/// The alternatives are turned into a deterministic automaton over blocks
+/// of characters, whose accepting states are then flipped (see
+/// [`Dfa::regex`] for how it is written back out). With `exclude_dot`,
+/// strings starting with `.` are left out as well; with `fold_case` the
+/// alternatives ignore case, so that the result reads the same under `(?i)`.
+///
+/// # Arguments
+/// * `alternatives` - Regexes of the `|`-separated alternatives, unanchored
+/// * `exclude_dot` - Whether segments starting with `.` are excluded too
+/// * `fold_case` - Whether the alternatives match case-insensitively
+///
+/// # Returns
+/// * `Ok(String)` - Regex fragment confined to `[^/]*`
+/// * `Err(GlobError)` - If an alternative can't be negated or needs too many states
+pub(crate) fn complement(
+    alternatives: &[String],
+    exclude_dot: bool,
+    fold_case: bool,
+) -> Result<String, GlobError> {
+    let source = format!(
+        "{}(?:{})",
+        if fold_case { "(?i)" } else { "" },
+        alternatives.join("|")
+    );
+    let hir = regex_syntax::ParserBuilder::new()
+        .build()
+        .parse(&source)
+        .map_err(|e| GlobError::InvalidPattern(e.to_string()))?;
+    let mut nfa = Nfa::default();
+    let start = nfa.add()?;
+    let end = nfa.build(&hir, start)?;
+    let dfa = Dfa::new(&nfa, start, end)?.complement(exclude_dot);
+    Ok(dfa.regex())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every string of up to `len` characters from `alphabet`
+    fn strings(alphabet: &[char], len: usize) -> Vec<String> {
+        let mut all = vec![String::new()];
+        let mut last = vec![String::new()];
+        for _ in 0..len {
+            last = last
+                .iter()
+                .flat_map(|s| alphabet.iter().map(move |c| format!("{}{}", s, c)))
+                .collect();
+            all.extend(last.iter().cloned());
+        }
+        all
+    }
+
+    #[test]
+    fn test_complement_matches_what_alternatives_do_not() {
+        let cases: &[&[&str]] = &[
+            &["x"],
+            &["[^/]*\\.rs"],
+            &["[^/]*\\.(?:js|ts)"],
+            &["a[^/]*b", "b"],
+            &["t[^/]", "[ab][^/]*"],
+            &["[^/]*ab[^/]*"],
+            &["[^/]*"],
+        ];
+        let all = strings(&['a', 'b', 't', '.', 'x', 'r', 's', 'A'], 4);
+        for alternatives in cases {
+            let alternatives: Vec<String> = alternatives.iter().map(|a| a.to_string()).collect();
+            let given = regex::Regex::new(&format!("^(?:{})$", alternatives.join("|"))).unwrap();
+            for exclude_dot in [false, true] {
+                let negated = complement(&alternatives, exclude_dot, false).unwrap();
+                let negated = regex::Regex::new(&format!("^(?:{})$", negated)).unwrap();
+                for s in &all {
+                    let hidden = exclude_dot && s.starts_with('.');
+                    let expected = !(given.is_match(s) || hidden);
+                    assert_eq!(negated.is_match(s), expected, "{alternatives:?} on {s:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_complement_folds_case() {
+        let alternatives = ["[^/]*\\.rs".to_string()];
+        let negated = complement(&alternatives, false, true).unwrap();
+        let negated = regex::Regex::new(&format!("(?i)^(?:{})$", negated)).unwrap();
+        assert!(!negated.is_match("main.RS"));
+        assert!(!negated.is_match("main.rs"));
+        assert!(negated.is_match("main.r"));
+    }
+}
@@ -0,0 +1,105 @@
+// patterns/normalize.rs
+use super::{brace, micromatch, Patterns};
+use crate::options::GlobOptions;
+use std::collections::HashSet;
+
+impl Patterns {
+    /// Rewrites a pattern list without redundant entries
+    ///
+    /// Braces are expanded as they would be at compile time, and exact
+    /// duplicates are dropped keeping the first occurrence. Plain globs are
+    /// then dropped when a `**/<segment>` pattern with the same final
+    /// segment subsumes them, e.g. `src/*.rs` and `*.rs` next to `**/*.rs`.
+    /// Subsumption is a heuristic: it only recognises that one shape and
+    /// keeps anything it cannot prove redundant. Unless `match_dotfiles` is
+    /// set, `**` doesn't descend into dot-directories, so a glob under one,
+    /// like `.github/*.yml`, is never subsumed.
+    ///
+    /// A pattern whose braces fail to expand is kept as given, so that
+    /// compiling the result reports the same error.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Pattern strings to normalize
+    /// * `opts` - Glob options that would be used for compilation
+    ///
+    /// # Returns
+    ///
+    /// The remaining patterns, in input order
+    pub fn normalize(patterns: &[&str], opts: &GlobOptions) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut unique = Vec::new();
+        for pattern in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            let is_regex = !opts.literal && micromatch::explicit_regex(pattern).is_some();
+            let pattern = if opts.normalize_separators && opts.separator.is_none() && !is_regex {
+                crate::windows::normalize_separators(pattern).into_owned()
+            } else {
+                pattern.to_string()
+            };
+            let expanded = if !opts.literal && pattern.contains('{') && pattern.contains('}') {
+                brace::expand_with(&pattern, opts.regex_quantifier_braces)
+                    .unwrap_or_else(|_| vec![pattern])
+            } else {
+                vec![pattern]
+            };
+            for pattern in expanded {
+                if seen.insert(pattern.clone()) {
+                    unique.push(pattern);
+                }
+            }
+        }
+
+        // Segments are only `/`-delimited without a custom separator
+        if opts.literal || opts.separator.is_some() {
+            return unique;
+        }
+        let subsuming: HashSet<(&str, bool)> =
+            unique.iter().filter_map(|p| globstar_suffix(p)).collect();
+        unique
+            .iter()
+            .filter(|p| {
+                globstar_suffix(p).is_some()
+                    || !last_segment(p, opts.match_dotfiles)
+                        .is_some_and(|key| subsuming.contains(&key))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Returns the final segment of `**/<segment>` and whether it is directory-only
+fn globstar_suffix(pattern: &str) -> Option<(&str, bool)> {
+    let rest = pattern.strip_prefix("**/")?;
+    let (segment, dir_only) = split_dir_only(rest);
+    (!segment.contains('/') && segment != "**" && is_plain_glob(segment))
+        .then_some((segment, dir_only))
+}
+
+/// Returns the final segment of a plain glob and whether it is directory-only
+///
+/// Without `dotfiles`, globs below a segment starting with `.` have none,
+/// since `**` wouldn't reach them.
+fn last_segment(pattern: &str, dotfiles: bool) -> Option<(&str, bool)> {
+    let (body, dir_only) = split_dir_only(pattern);
+    if !is_plain_glob(body) || body.split('/').any(|s| s == "..") {
+        return None;
+    }
+    let (dirs, segment) = body.rsplit_once('/').unwrap_or(("", body));
+    if !dotfiles && dirs.split('/').any(|s| s.starts_with('.')) {
+        return None;
+    }
+    Some((segment, dir_only))
+}
+
+/// Splits off a trailing `/`, which restricts a glob to directories
+fn split_dir_only(pattern: &str) -> (&str, bool) {
+    match pattern.strip_suffix('/') {
+        Some(body) if !body.is_empty() => (body, true),
+        _ => (pattern, false),
+    }
+}
+
+/// Checks that a glob has no syntax beyond `*` wildcards
+fn is_plain_glob(pattern: &str) -> bool {
+    micromatch::explicit_regex(pattern).is_none() && !Patterns::is_complex_pattern(pattern)
+}
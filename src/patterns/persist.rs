@@ -0,0 +1,293 @@
+// patterns/persist.rs
+use crate::error::GlobError;
+use crate::options::GlobOptions;
+use crate::patterns::{cache, prefilter::LiteralSegments, GroupBuilder, PatternKind, Patterns};
+use std::{fs, io, path::Path};
+
+/// First line of every cache file; bump the version when the format changes
+const CACHE_HEADER: &str = "patternhunt-cache v5";
+
+/// Normalized pattern strings kept alongside compiled sets
+///
+/// These are the globs handed to globset after brace expansion, separator
+/// normalization and `..` resolution, so reloading them skips that work.
+/// Regex sources are recovered from the compiled regexes themselves.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PatternSources {
+    /// Hash of the input patterns and the options that affect compilation
+    pub(crate) key: u64,
+    pub(crate) globs: Vec<String>,
+    pub(crate) basename_globs: Vec<String>,
+    /// How each non-empty input pattern was routed, for `pattern_kinds`
+    pub(crate) kinds: Vec<PatternKind>,
+    /// Literal segments of each full-path glob or regex, for `could_match`
+    /// and the fast rejection in `matches_entry_str`
+    pub(crate) literals: Vec<LiteralSegments>,
+    /// Whether each full-path regex came from a `re:` or `re~:` pattern
+    pub(crate) explicit_regexes: Vec<bool>,
+}
+
+/// Hashes the inputs that determine what `compile_many` produces
+///
+/// Uses FNV-1a so the key is stable across processes and Rust versions,
+/// unlike `std`'s randomly seeded hasher.
+pub(crate) fn cache_key<S: AsRef<str>>(patterns: &[S], opts: &GlobOptions) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= u64::from(*b);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+
+    feed(CACHE_HEADER.as_bytes());
+    for pattern in patterns {
+        feed(pattern.as_ref().as_bytes());
+        feed(&[0]);
+    }
+    let settings = format!(
+        "{}|{}|{:?}|{:?}|{}|{}|{:?}|{}|{:?}|{}|{}",
+        opts.case_sensitive,
+        opts.unicode_case_fold,
+        opts.match_mode,
+        opts.regex_limits,
+        opts.normalize_separators,
+        opts.regex_quantifier_braces,
+        opts.path_traversal,
+        opts.literal,
+        opts.separator,
+        opts.literal_separator,
+        opts.match_dotfiles,
+    );
+    feed(settings.as_bytes());
+    // Expanded patterns depend on the home directory they were expanded to
+    if opts.expand_tilde {
+        feed(b"|~");
+        feed(crate::fs::expand_tilde("~").as_bytes());
+    }
+    hash
+}
+
+fn corrupt(detail: &str) -> GlobError {
+    GlobError::Other(format!("Corrupt pattern cache: {}", detail))
+}
+
+impl Patterns {
+    /// Writes the normalized patterns to a cache file
+    ///
+    /// The file records the globs and regexes exactly as they were handed
+    /// to the matchers, keyed by a hash of the original patterns and
+    /// options. Load it back with [`Patterns::from_cached`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to write; it is replaced if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::Other` for sets built with [`Patterns::and`],
+    /// [`Patterns::or`] or [`Patterns::except`], and `GlobError::Io` if the file cannot be written
+    pub fn save_cache(&self, path: &Path) -> Result<(), GlobError> {
+        if !self.groups.is_empty() {
+            return Err(GlobError::Other(
+                "Combined pattern sets cannot be cached".to_string(),
+            ));
+        }
+
+        let mut out = String::new();
+        out.push_str(CACHE_HEADER);
+        out.push('\n');
+        out.push_str(&format!("key {:016x}\n", self.sources.key));
+        for kind in &self.sources.kinds {
+            out.push_str(&format!("kind {}\n", kind.to_line()));
+        }
+
+        let dir_only = self.dir_only.as_deref().map(|d| ("dir_", d));
+        for (prefix, group) in std::iter::once(("", self)).chain(dir_only) {
+            let literals: Vec<String> = group
+                .sources
+                .literals
+                .iter()
+                .map(LiteralSegments::to_line)
+                .collect();
+            let lines = [
+                ("glob", &group.sources.globs),
+                ("basename_glob", &group.sources.basename_globs),
+            ]
+            .into_iter()
+            .flat_map(|(tag, items)| items.iter().map(move |s| (tag, s.as_str())))
+            .chain(
+                group
+                    .regexes
+                    .iter()
+                    .zip(&group.sources.explicit_regexes)
+                    .map(|(re, &explicit)| {
+                        (
+                            if explicit { "explicit_regex" } else { "regex" },
+                            re.as_str(),
+                        )
+                    }),
+            )
+            .chain(
+                group
+                    .basename_regexes
+                    .iter()
+                    .map(|re| ("basename_regex", re.as_str())),
+            )
+            .chain(literals.iter().map(|l| ("literals", l.as_str())));
+            for (tag, source) in lines {
+                // One entry per line; a pattern spanning lines can't be stored
+                if source.contains('\n') {
+                    return Err(GlobError::Other(format!(
+                        "Pattern `{}` contains a newline and cannot be cached",
+                        source
+                    )));
+                }
+                out.push_str(&format!("{}{} {}\n", prefix, tag, source));
+            }
+        }
+
+        fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Loads patterns previously written by [`Patterns::save_cache`]
+    ///
+    /// The original patterns and options are needed to check that the
+    /// cache is still current: if their hash differs from the one stored
+    /// in the file, or the file does not exist or has an older format,
+    /// `Ok(None)` is returned and the caller should compile afresh.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Cache file to read
+    /// * `patterns` - The patterns the cache was built from
+    /// * `opts` - The options the cache was built with
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(Patterns))` matching exactly like a fresh compile, or
+    /// `Ok(None)` if the cache is missing or stale
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::Io` if the file cannot be read and
+    /// `GlobError::Other` if it is malformed
+    pub fn from_cached<I, S>(
+        path: &Path,
+        patterns: I,
+        opts: &GlobOptions,
+    ) -> Result<Option<Self>, GlobError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut lines = text.lines();
+        if lines.next() != Some(CACHE_HEADER) {
+            return Ok(None);
+        }
+
+        let inputs: Vec<String> = patterns
+            .into_iter()
+            .map(|p| p.as_ref().to_string())
+            .collect();
+        let key = cache_key(&inputs, opts);
+
+        let mut stored_key = None;
+        let mut kinds = Vec::new();
+        let mut groups: [GroupBuilder; 4] = std::array::from_fn(|_| GroupBuilder::new());
+
+        for line in lines {
+            let (tag, value) = line
+                .split_once(' ')
+                .ok_or_else(|| corrupt(&format!("unexpected line `{}`", line)))?;
+            if tag == "key" {
+                let stored = u64::from_str_radix(value, 16).map_err(|_| corrupt("invalid key"))?;
+                // Stop before compiling anything from a stale cache
+                if stored != key {
+                    return Ok(None);
+                }
+                stored_key = Some(stored);
+                continue;
+            }
+            if tag == "kind" {
+                kinds.push(PatternKind::from_line(value).ok_or_else(|| corrupt("invalid kind"))?);
+                continue;
+            }
+
+            // Groups are, in order: full, basename, and their directory-only twins
+            let (offset, tag) = match tag.strip_prefix("dir_") {
+                Some(tag) => (2, tag),
+                None => (0, tag),
+            };
+            match tag {
+                "glob" | "basename_glob" => {
+                    let group = &mut groups[offset + usize::from(tag != "glob")];
+                    group.globs.add(Patterns::build_glob(value, opts)?);
+                    group.glob_sources.push(value.to_string());
+                }
+                "regex" | "explicit_regex" | "basename_regex" => {
+                    let group = &mut groups[offset + usize::from(tag == "basename_regex")];
+                    let re =
+                        cache::global().get_or_compile_regex_limited(value, &opts.regex_limits)?;
+                    group.push_regex(re, tag == "explicit_regex");
+                }
+                "literals" => {
+                    let literals = LiteralSegments::from_line(value, opts)
+                        .ok_or_else(|| corrupt("invalid literals"))?;
+                    groups[offset].literals.push(literals);
+                }
+                _ => return Err(corrupt(&format!("unknown entry `{}`", tag))),
+            }
+        }
+
+        if stored_key.is_none() {
+            return Err(corrupt("missing key"));
+        }
+
+        let [full, basename, dir_full, dir_basename] = groups;
+        let mut patterns = Self::from_groups(full, basename, opts)?;
+        patterns.sources.key = key;
+        patterns.sources.kinds = kinds;
+        if !dir_full.is_empty() || !dir_basename.is_empty() {
+            let dir_only = Self::from_groups(dir_full, dir_basename, opts)?;
+            patterns.dir_only = Some(Box::new(dir_only));
+        }
+        Ok(Some(patterns))
+    }
+
+    /// Loads patterns from a cache file, compiling and saving them on a miss
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Cache file to read and, if stale, rewrite
+    /// * `patterns` - Pattern strings to compile
+    /// * `opts` - Glob options for configuration
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Patterns)` if successful, `Err(GlobError)` otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Patterns::compile_many`] and
+    /// [`Patterns::save_cache`]; a malformed cache file is recompiled
+    pub fn compile_cached<S: AsRef<str>>(
+        path: &Path,
+        patterns: &[S],
+        opts: &GlobOptions,
+    ) -> Result<Self, GlobError> {
+        if let Ok(Some(cached)) = Self::from_cached(path, patterns, opts) {
+            return Ok(cached);
+        }
+        let compiled = Self::compile_many(patterns, opts)?;
+        compiled.save_cache(path)?;
+        Ok(compiled)
+    }
+}
@@ -0,0 +1,274 @@
+// patterns/prefilter.rs
+use crate::options::GlobOptions;
+
+/// Characters that make a pattern segment more than literal text
+const META: &[char] = &[
+    '*', '?', '[', ']', '{', '}', '(', ')', '!', '@', '+', '|', '\\',
+];
+
+/// A leading segment of a pattern, matched against one path segment
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    /// Literal text the path segment must equal
+    Literal(String),
+    /// A `*`/`?` wildcard, which can match any single segment
+    Any,
+}
+
+/// Literal path segments one full-path pattern requires
+///
+/// The leading segments are positional: every path the pattern matches
+/// starts with them, so a directory that departs from them can't contain
+/// a match and traversal skips it. Single-segment wildcards such as `*`
+/// count as positional when wildcards stop at `/`.
+///
+/// Literal segments past the first `**`, like `src` and `mod.rs` in
+/// `**/src/**/mod.rs`, are required rather than positional: a match
+/// contains them in order, but a `**` can lead to them from any
+/// directory, so they never prune one. They reject entries instead,
+/// before any matcher runs. An empty set admits everything.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LiteralSegments {
+    leading: Vec<Segment>,
+    /// Whether the pattern is nothing but the leading segments
+    exact: bool,
+    /// Literal segments a match contains in order after the leading ones
+    required: Vec<String>,
+    /// Whether the last required segment is the pattern's final one
+    required_last: bool,
+    /// Whether segments compare case-insensitively
+    fold_case: bool,
+}
+
+impl LiteralSegments {
+    /// Takes the literal segments of a compiled pattern
+    ///
+    /// `pattern` is in its final form, after brace expansion and
+    /// separator normalization. Leading segments end at `**`, at a
+    /// wildcard that may cross `/`, or at a `.` or `..` segment. Any
+    /// segment with other glob syntax ends both leading and required
+    /// segments, since it might span a `/`. Under `opts.literal` every
+    /// segment is literal.
+    pub(crate) fn new(pattern: &str, opts: &GlobOptions) -> Self {
+        let mut literals = Self {
+            exact: true,
+            fold_case: !opts.case_sensitive,
+            ..Self::default()
+        };
+        let mut positional = true;
+        let mut segments = pattern.split('/').peekable();
+        while let Some(segment) = segments.next() {
+            let is_last = segments.peek().is_none();
+            let literal = opts.literal || !segment.contains(META);
+            // Only `*` and `?` are sure to stay within the segment
+            let single = !segment.contains("**")
+                && segment
+                    .chars()
+                    .all(|c| !META.contains(&c) || c == '*' || c == '?');
+            if segment == "." || segment == ".." {
+                literals.exact = false;
+                break;
+            }
+            if literal && positional {
+                literals.leading.push(Segment::Literal(segment.to_string()));
+            } else if literal {
+                literals.required.push(segment.to_string());
+                literals.required_last = is_last;
+            } else if single && positional && opts.separator_is_literal() {
+                literals.leading.push(Segment::Any);
+            } else if segment == "**" || single {
+                literals.exact = false;
+                positional = false;
+            } else {
+                literals.exact = false;
+                break;
+            }
+        }
+        literals
+    }
+
+    /// Segments for patterns that can match anywhere, such as regexes
+    pub(crate) fn any() -> Self {
+        Self::default()
+    }
+
+    /// Checks whether `dir`, or any path below it, could match the pattern
+    ///
+    /// `dir` is a candidate string with `/` separators. A leading `./` is
+    /// ignored, and non-ASCII segments always agree when folding case,
+    /// since Unicode folding can change their length.
+    pub(crate) fn admits(&self, dir: &str) -> bool {
+        let dir = dir.strip_prefix("./").unwrap_or(dir);
+        for (i, part) in dir.split('/').enumerate() {
+            let Some(segment) = self.leading.get(i) else {
+                // Past the end of a fully literal pattern nothing matches
+                return !self.exact;
+            };
+            if let Segment::Literal(literal) = segment {
+                if !self.same(literal, part) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Checks whether the path `s` itself could match the pattern
+    ///
+    /// Besides the leading segments, the required ones must all appear,
+    /// in order; the pattern's final literal segment must be the path's.
+    pub(crate) fn may_match(&self, s: &str) -> bool {
+        let s = s.strip_prefix("./").unwrap_or(s);
+        let parts: Vec<&str> = s.split('/').collect();
+        if self.exact && parts.len() != self.leading.len() {
+            return false;
+        }
+        let leading_ok = self
+            .leading
+            .iter()
+            .zip(&parts)
+            .all(|(segment, part)| match segment {
+                Segment::Literal(literal) => self.same(literal, part),
+                Segment::Any => true,
+            });
+        if !leading_ok {
+            return false;
+        }
+
+        let mut rest = parts.get(self.leading.len()..).unwrap_or_default();
+        let mut required = self.required.as_slice();
+        if self.required_last {
+            let Some((last, before)) = required.split_last() else {
+                return true;
+            };
+            match rest.split_last() {
+                Some((part, parts)) if self.same(last, part) => rest = parts,
+                _ => return false,
+            }
+            required = before;
+        }
+        // Greedy in-order search for each required segment
+        let mut parts = rest.iter();
+        required
+            .iter()
+            .all(|literal| parts.any(|part| self.same(literal, part)))
+    }
+
+    /// Compares a pattern segment with a path segment
+    fn same(&self, literal: &str, part: &str) -> bool {
+        if !self.fold_case {
+            literal == part
+        } else if literal.is_ascii() && part.is_ascii() {
+            literal.eq_ignore_ascii_case(part)
+        } else {
+            true
+        }
+    }
+
+    /// Encodes the segments for a cache file
+    ///
+    /// `=` or `+` marks an exact set or not, followed by `/`-separated
+    /// segments, each tagged `l` (leading literal), `a` (leading
+    /// wildcard), `r` (required) or `f` (required and final).
+    pub(crate) fn to_line(&self) -> String {
+        let leading = self.leading.iter().map(|segment| match segment {
+            Segment::Literal(literal) => format!("l{}", literal),
+            Segment::Any => "a".to_string(),
+        });
+        let last = self.required.len().saturating_sub(1);
+        let required = self.required.iter().enumerate().map(|(i, literal)| {
+            let tag = if self.required_last && i == last {
+                'f'
+            } else {
+                'r'
+            };
+            format!("{}{}", tag, literal)
+        });
+        format!(
+            "{}{}",
+            if self.exact { '=' } else { '+' },
+            leading.chain(required).collect::<Vec<_>>().join("/")
+        )
+    }
+
+    /// Decodes segments written by [`LiteralSegments::to_line`]
+    pub(crate) fn from_line(line: &str, opts: &GlobOptions) -> Option<Self> {
+        let (exact, rest) = match line.split_at_checked(1)? {
+            ("=", rest) => (true, rest),
+            ("+", rest) => (false, rest),
+            _ => return None,
+        };
+        let mut literals = Self {
+            exact,
+            fold_case: !opts.case_sensitive,
+            ..Self::default()
+        };
+        for segment in rest.split('/').filter(|s| !s.is_empty()) {
+            let (tag, value) = segment.split_at_checked(1)?;
+            match tag {
+                "l" => literals.leading.push(Segment::Literal(value.to_string())),
+                "a" => literals.leading.push(Segment::Any),
+                "r" | "f" => {
+                    literals.required.push(value.to_string());
+                    literals.required_last = tag == "f";
+                }
+                _ => return None,
+            }
+        }
+        Some(literals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_segments_reject_entries() {
+        let opts = GlobOptions::default();
+        let literals = LiteralSegments::new("**/src/**/mod.rs", &opts);
+        assert!(literals.admits("vendor/deep"));
+        assert!(literals.may_match("src/mod.rs"));
+        assert!(literals.may_match("a/src/b/c/mod.rs"));
+        assert!(!literals.may_match("a/lib/mod.rs"));
+        assert!(!literals.may_match("a/mod.rs/src"));
+        assert!(!literals.may_match("src/mod.rs/x"));
+    }
+
+    #[test]
+    fn test_single_segment_wildcards_stay_positional() {
+        let opts = GlobOptions {
+            literal_separator: true,
+            ..GlobOptions::default()
+        };
+        let literals = LiteralSegments::new("*/src/*.rs", &opts);
+        assert!(literals.admits("a/src"));
+        assert!(!literals.admits("a/lib"));
+        assert!(!literals.admits("a/src/b/c"));
+        assert!(literals.may_match("a/src/lib.rs"));
+        assert!(!literals.may_match("a/src/b/lib.rs"));
+
+        // Without `literal_separator` a `*` may span several segments
+        let literals = LiteralSegments::new("*/src/*.rs", &GlobOptions::default());
+        assert!(literals.admits("a/lib"));
+        assert!(literals.may_match("a/b/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_line_roundtrip() {
+        let opts = GlobOptions {
+            literal_separator: true,
+            ..GlobOptions::default()
+        };
+        for pattern in ["src/*/x", "**/src/**/mod.rs", "a/**/b/*.rs", "*.md"] {
+            let literals = LiteralSegments::new(pattern, &opts);
+            assert_eq!(
+                LiteralSegments::from_line(&literals.to_line(), &opts),
+                Some(literals),
+                "{pattern}"
+            );
+        }
+        assert_eq!(LiteralSegments::from_line("", &opts), None);
+        assert_eq!(LiteralSegments::from_line("=x", &opts), None);
+    }
+}
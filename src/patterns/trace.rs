@@ -0,0 +1,230 @@
+// patterns/trace.rs
+use super::{strip_slash, Combine, Patterns};
+use crate::{
+    error::GlobError,
+    fs::FsMetadata,
+    predicates::{FileType, Predicates},
+};
+use globset::GlobSet;
+use std::{fs, path::Path};
+
+/// A compiled glob or regex that matched during a trace
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TracedPattern {
+    /// Position of the pattern within its glob or regex list
+    pub index: usize,
+
+    /// The glob or regex as compiled, after brace expansion
+    pub source: String,
+}
+
+/// Detailed account of how a path was matched, from [`Patterns::trace_match`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchTrace {
+    /// The string patterns were tested against, after separator
+    /// normalization or exchange and with any trailing `/` removed
+    pub candidate: String,
+
+    /// Whether the candidate was treated as a directory
+    pub is_dir: bool,
+
+    /// Full-path globs that matched
+    pub globs: Vec<TracedPattern>,
+
+    /// Full-path regexes that matched
+    pub regexes: Vec<TracedPattern>,
+
+    /// File name the basename patterns were tested against, if any were
+    pub basename: Option<String>,
+
+    /// Basename globs that matched the file name
+    pub basename_globs: Vec<TracedPattern>,
+
+    /// Basename regexes that matched the file name
+    pub basename_regexes: Vec<TracedPattern>,
+
+    /// Trace of the directory-only patterns (`build/`), for directories
+    pub dir_only: Option<Box<MatchTrace>>,
+
+    /// Traces of the component sets of a [`Patterns::and`] or
+    /// [`Patterns::or`] combination
+    pub groups: Vec<MatchTrace>,
+
+    /// Name of the first predicate that rejected the path, such as
+    /// `"min_size"`, when traced with predicates
+    pub failed_predicate: Option<&'static str>,
+
+    /// Whether the path matched overall, predicates included
+    pub matched: bool,
+}
+
+impl Patterns {
+    /// Reports which patterns match a string, and why the result is what it is
+    ///
+    /// This evaluates the same steps as [`Patterns::matches_str`] but
+    /// collects every glob and regex that matched instead of stopping at
+    /// the first. It is meant for debugging; use the `matches_*` methods
+    /// for actual matching.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - String to trace; a trailing `/` marks a directory
+    ///
+    /// # Returns
+    ///
+    /// A [`MatchTrace`] whose `matched` agrees with `matches_str(path)`
+    pub fn trace_match(&self, path: &str) -> MatchTrace {
+        self.trace_entry(path, false)
+    }
+
+    /// Traces a path on disk through the patterns and the predicates
+    ///
+    /// Like [`Patterns::trace_match`], and additionally reports the first
+    /// predicate the entry fails. Predicates are evaluated even when no
+    /// pattern matches, so both reasons are visible at once.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Existing path to trace
+    /// * `predicates` - Predicates to evaluate against the entry
+    ///
+    /// # Returns
+    ///
+    /// `Ok(MatchTrace)` with `failed_predicate` set if a predicate failed
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::Io` if the path's metadata cannot be read
+    pub fn trace_match_with(
+        &self,
+        path: &Path,
+        predicates: &Predicates,
+    ) -> Result<MatchTrace, GlobError> {
+        let meta = if predicates.follow_symlinks {
+            fs::metadata(path)?
+        } else {
+            fs::symlink_metadata(path)?
+        };
+        let meta = FsMetadata::from(&meta);
+
+        let mut trace = self.trace_entry(&path.to_string_lossy(), meta.file_type == FileType::Dir);
+        trace.failed_predicate = predicates
+            .failed_path_check(path)
+            .or_else(|| predicates.failed_metadata_check(&meta));
+        if trace.failed_predicate.is_none() {
+            let read = |len| Ok(crate::fs::read_prefix(path, len)?);
+            if !predicates.matches_content(meta.file_type == FileType::File, read)? {
+                trace.failed_predicate = Some("content_prefix");
+            }
+        }
+        if trace.failed_predicate.is_none() {
+            trace.failed_predicate = predicates.failed_xattr_check(|name| {
+                Ok(crate::fs::read_xattr(
+                    path,
+                    name,
+                    predicates.follow_symlinks,
+                )?)
+            })?;
+        }
+        if trace.failed_predicate.is_none()
+            && !predicates.matches_readable(|| Ok(crate::fs::is_readable(path)))?
+        {
+            trace.failed_predicate = Some("readable");
+        }
+        trace.matched &= trace.failed_predicate.is_none();
+        Ok(trace)
+    }
+
+    /// Traces a candidate string, mirroring [`Patterns::matches_entry_str`]
+    fn trace_entry(&self, s: &str, is_dir: bool) -> MatchTrace {
+        if !self.groups.is_empty() {
+            let groups: Vec<MatchTrace> = self
+                .groups
+                .iter()
+                .map(|g| g.trace_entry(s, is_dir))
+                .collect();
+            let matched = match self.combine {
+                Combine::Any => groups.iter().any(|g| g.matched),
+                Combine::All => groups.iter().all(|g| g.matched),
+                Combine::Except => groups[0].matched && !groups[1..].iter().any(|g| g.matched),
+            };
+            return MatchTrace {
+                candidate: s.to_string(),
+                is_dir,
+                groups,
+                matched,
+                ..MatchTrace::default()
+            };
+        }
+
+        let (s, raw) = self.candidate_forms(s);
+        let (s, raw, is_dir) = match s.strip_suffix('/') {
+            Some(dir) if !dir.is_empty() => (dir, raw.as_deref().map(strip_slash), true),
+            _ => (s.as_ref(), raw.as_deref(), is_dir),
+        };
+
+        let mut trace = MatchTrace {
+            candidate: s.to_string(),
+            is_dir,
+            globs: traced_globs(&self.set, &self.sources.globs, s),
+            regexes: traced_regexes(&self.regexes, s, &self.sources.explicit_regexes, raw),
+            ..MatchTrace::default()
+        };
+        if is_dir {
+            trace.dir_only = self
+                .dir_only
+                .as_ref()
+                .map(|d| Box::new(d.trace_entry(s, false)));
+        }
+        if !self.basename_set.is_empty() || !self.basename_regexes.is_empty() {
+            if let Some(name) = camino::Utf8Path::new(s).file_name() {
+                trace.basename = Some(name.to_string());
+                trace.basename_globs =
+                    traced_globs(&self.basename_set, &self.sources.basename_globs, name);
+                trace.basename_regexes = traced_regexes(&self.basename_regexes, name, &[], None);
+            }
+        }
+
+        trace.matched = trace.dir_only.as_ref().is_some_and(|d| d.matched)
+            || !trace.globs.is_empty()
+            || !trace.regexes.is_empty()
+            || !trace.basename_globs.is_empty()
+            || !trace.basename_regexes.is_empty();
+        trace
+    }
+}
+
+/// Lists the globs of `set` matching `s`, with their sources
+fn traced_globs(set: &GlobSet, sources: &[String], s: &str) -> Vec<TracedPattern> {
+    set.matches(s)
+        .into_iter()
+        .map(|index| TracedPattern {
+            index,
+            source: sources.get(index).cloned().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Lists the regexes matching `s`, with their sources
+///
+/// Regexes flagged in `explicit` are tested against `raw` instead, when
+/// given, as in `Patterns::matches_entry_str`.
+fn traced_regexes(
+    regexes: &[regex::Regex],
+    s: &str,
+    explicit: &[bool],
+    raw: Option<&str>,
+) -> Vec<TracedPattern> {
+    regexes
+        .iter()
+        .enumerate()
+        .filter(|&(index, re)| match raw {
+            Some(raw) if explicit.get(index) == Some(&true) => re.is_match(raw),
+            _ => re.is_match(s),
+        })
+        .map(|(index, re)| TracedPattern {
+            index,
+            source: re.as_str().to_string(),
+        })
+        .collect()
+}
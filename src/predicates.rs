@@ -1,11 +1,16 @@
 // predicates.rs
-use std::{fs::Metadata, time::SystemTime};
+use crate::{error::GlobError, fs::FsMetadata, patterns::cache};
+use std::{
+    fs::Metadata,
+    path::Path,
+    time::{Duration, SystemTime},
+};
 
 /// File type predicates for filtering
 ///
 /// This enum allows filtering files based on their type
 /// during glob pattern matching operations.
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FileType {
     /// Regular files
     File,
@@ -18,8 +23,12 @@ pub enum FileType {
 /// Predicates for filtering files based on metadata
 ///
 /// This struct provides a flexible way to filter files based on
-/// various attributes like size, type, and timestamps.
-#[derive(Clone, Debug)]
+/// various attributes like size, type, and timestamps. Build it with
+/// [`PredicatesBuilder`], or from `Predicates::default()` by setting
+/// fields; it is `#[non_exhaustive]`, so new predicates can be added
+/// without breaking callers, and struct literals aren't accepted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub struct Predicates {
     /// Minimum file size in bytes
     pub min_size: Option<u64>,
@@ -36,6 +45,15 @@ pub struct Predicates {
     /// Last modified before this time
     pub mtime_before: Option<SystemTime>,
 
+    /// Last modified at most this long ago
+    ///
+    /// Age is measured from the time of the check; a modification time in
+    /// the future counts as zero age.
+    pub mtime_within: Option<Duration>,
+
+    /// Last modified at least this long ago
+    pub mtime_older_than: Option<Duration>,
+
     /// Created after this time
     pub ctime_after: Option<SystemTime>,
 
@@ -44,13 +62,160 @@ pub struct Predicates {
 
     /// Whether to follow symlinks for metadata checks
     pub follow_symlinks: bool,
+
+    /// Secondary glob the entry path must also match
+    pub path_glob: Option<String>,
+
+    /// Bytes the file contents must start with, such as a magic number
+    ///
+    /// Only regular files can match. Evaluated by traversal, which reads
+    /// the leading bytes; [`Predicates::matches_path`] does not check it.
+    pub content_prefix: Option<Vec<u8>>,
+
+    /// Required owner user id
+    #[cfg(unix)]
+    pub uid: Option<u32>,
+
+    /// Required owner group id
+    #[cfg(unix)]
+    pub gid: Option<u32>,
+
+    /// Permission bits to inspect; defaults to `mode_expected` when unset
+    #[cfg(unix)]
+    pub mode_mask: Option<u32>,
+
+    /// Value the masked permission bits must equal; defaults to `mode_mask`
+    #[cfg(unix)]
+    pub mode_expected: Option<u32>,
+
+    /// Minimum number of hard links; `Some(2)` selects hardlinked files
+    #[cfg(unix)]
+    pub nlink_min: Option<u64>,
+
+    /// Required inode number
+    #[cfg(unix)]
+    pub inode: Option<u64>,
+
+    /// Extended attribute the entry must have, such as `user.tag`
+    ///
+    /// Evaluated by traversal, like `content_prefix`.
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    pub has_xattr: Option<String>,
+
+    /// Value the `has_xattr` attribute must hold; any value when unset
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    pub xattr_value: Option<Vec<u8>>,
+
+    /// Only match entries the current user can read
+    ///
+    /// Evaluated by traversal with a cheap access check; unreadable
+    /// entries are skipped rather than reported as errors.
+    pub readable: bool,
 }
 
 impl Predicates {
+    /// Checks if a path and its metadata match all predicates
+    ///
+    /// Path-based predicates such as `path_glob` are evaluated first,
+    /// then the metadata predicates via [`Predicates::matches`]. The
+    /// path glob is compiled on first use and kept in the pattern cache;
+    /// an invalid glob never matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the entry being evaluated
+    /// * `meta` - File metadata to evaluate
+    ///
+    /// # Returns
+    ///
+    /// `true` if all predicates match, `false` otherwise
+    pub fn matches_path(&self, path: &Path, meta: &Metadata) -> bool {
+        self.matches_path_only(path) && self.matches(meta)
+    }
+
+    /// Evaluates only the predicates that need no metadata
+    pub(crate) fn matches_path_only(&self, path: &Path) -> bool {
+        if let Some(glob) = &self.path_glob {
+            match cache::get_or_compile_glob(glob) {
+                Ok(set) => {
+                    if !set.is_match(path.to_string_lossy().as_ref()) {
+                        return false;
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Evaluates the `content_prefix` predicate
+    ///
+    /// `read` is called only when the predicate is set and the entry is a
+    /// regular file, with the prefix length; it returns up to that many
+    /// leading bytes. Files shorter than the prefix don't match.
+    pub(crate) fn matches_content<F>(&self, is_file: bool, read: F) -> Result<bool, GlobError>
+    where
+        F: FnOnce(usize) -> Result<Vec<u8>, GlobError>,
+    {
+        let Some(prefix) = &self.content_prefix else {
+            return Ok(true);
+        };
+        if !is_file {
+            return Ok(false);
+        }
+        Ok(read(prefix.len())? == *prefix)
+    }
+
+    /// Evaluates the `has_xattr` and `xattr_value` predicates
+    ///
+    /// `read` is called only when `has_xattr` is set, with the attribute
+    /// name; it returns the value, or `None` if the attribute isn't set.
+    pub(crate) fn matches_xattr<F>(&self, read: F) -> Result<bool, GlobError>
+    where
+        F: FnOnce(&str) -> Result<Option<Vec<u8>>, GlobError>,
+    {
+        Ok(self.failed_xattr_check(read)?.is_none())
+    }
+
+    /// Returns the name of the attribute predicate that rejects an entry
+    ///
+    /// `"has_xattr"` if the attribute isn't set, `"xattr_value"` if it
+    /// holds another value. `read` is as for [`Predicates::matches_xattr`].
+    pub(crate) fn failed_xattr_check<F>(&self, read: F) -> Result<Option<&'static str>, GlobError>
+    where
+        F: FnOnce(&str) -> Result<Option<Vec<u8>>, GlobError>,
+    {
+        #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+        if let Some(name) = &self.has_xattr {
+            return Ok(match read(name)? {
+                None => Some("has_xattr"),
+                Some(value) if self.xattr_value.as_ref().is_some_and(|v| *v != value) => {
+                    Some("xattr_value")
+                }
+                Some(_) => None,
+            });
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+        let _ = read;
+        Ok(None)
+    }
+
+    /// Evaluates the `readable` predicate
+    ///
+    /// `check` is called only when `readable` is set.
+    pub(crate) fn matches_readable<F>(&self, check: F) -> Result<bool, GlobError>
+    where
+        F: FnOnce() -> Result<bool, GlobError>,
+    {
+        Ok(!self.readable || check()?)
+    }
+
     /// Checks if file metadata matches all predicates
     ///
-    /// This method evaluates all configured predicates against
-    /// the file metadata and returns true only if all match.
+    /// This method evaluates all configured metadata predicates and
+    /// returns true only if all match. Path-based predicates are ignored
+    /// here; use [`Predicates::matches_path`] to include them.
     ///
     /// # Arguments
     ///
@@ -60,57 +225,276 @@ impl Predicates {
     ///
     /// `true` if all predicates match, `false` otherwise
     pub fn matches(&self, meta: &Metadata) -> bool {
+        self.matches_metadata(&FsMetadata::from(meta))
+    }
+
+    /// Checks if filesystem-agnostic metadata matches all predicates
+    ///
+    /// This is the check behind [`Predicates::matches`], usable with
+    /// metadata produced by any [`crate::fs::FileSystem`].
+    ///
+    /// # Arguments
+    ///
+    /// * `meta` - Metadata to evaluate
+    ///
+    /// # Returns
+    ///
+    /// `true` if all predicates match, `false` otherwise
+    pub fn matches_metadata(&self, meta: &FsMetadata) -> bool {
+        self.failed_metadata_check(meta).is_none()
+    }
+
+    /// Returns the name of the first path predicate that rejects `path`
+    ///
+    /// The name is that of the predicate field, e.g. `"path_glob"`.
+    pub(crate) fn failed_path_check(&self, path: &Path) -> Option<&'static str> {
+        (!self.matches_path_only(path)).then_some("path_glob")
+    }
+
+    /// Returns the name of the first metadata predicate that rejects `meta`
+    ///
+    /// Predicates are checked in the same order as by
+    /// [`Predicates::matches_metadata`]; the name is that of the predicate
+    /// field, e.g. `"min_size"`.
+    pub(crate) fn failed_metadata_check(&self, meta: &FsMetadata) -> Option<&'static str> {
         // Size predicates
         if let Some(min) = self.min_size {
-            if meta.len() < min {
-                return false;
+            if meta.len < min {
+                return Some("min_size");
             }
         }
 
         if let Some(max) = self.max_size {
-            if meta.len() > max {
-                return false;
+            if meta.len > max {
+                return Some("max_size");
             }
         }
 
         // File type predicate
-        if let Some(ft) = &self.file_type {
-            match ft {
-                FileType::File if !meta.is_file() => return false,
-                FileType::Dir if !meta.is_dir() => return false,
-                FileType::Symlink if !meta.file_type().is_symlink() => return false,
-                _ => {}
-            }
+        if self.file_type.is_some_and(|ft| ft != meta.file_type) {
+            return Some("file_type");
         }
 
         // Modification time predicates
-        if let Ok(mtime) = meta.modified() {
+        if let Some(mtime) = meta.modified {
             if let Some(after) = self.mtime_after {
                 if mtime < after {
-                    return false;
+                    return Some("mtime_after");
                 }
             }
             if let Some(before) = self.mtime_before {
                 if mtime > before {
-                    return false;
+                    return Some("mtime_before");
+                }
+            }
+            if self.mtime_within.is_some() || self.mtime_older_than.is_some() {
+                let age = SystemTime::now()
+                    .duration_since(mtime)
+                    .unwrap_or(Duration::ZERO);
+                if self.mtime_within.is_some_and(|within| age > within) {
+                    return Some("mtime_within");
+                }
+                if self.mtime_older_than.is_some_and(|older| age < older) {
+                    return Some("mtime_older_than");
+                }
+            }
+        }
+
+        // Ownership and permission predicates
+        #[cfg(unix)]
+        {
+            if self.uid.is_some_and(|uid| meta.uid != uid) {
+                return Some("uid");
+            }
+            if self.gid.is_some_and(|gid| meta.gid != gid) {
+                return Some("gid");
+            }
+            if self.nlink_min.is_some_and(|min| meta.nlink < min) {
+                return Some("nlink_min");
+            }
+            if self.inode.is_some_and(|ino| meta.ino != ino) {
+                return Some("inode");
+            }
+
+            // Permission mode predicate: (mode & mask) == expected
+            if let Some(mask) = self.mode_mask.or(self.mode_expected) {
+                let expected = self.mode_expected.unwrap_or(mask);
+                if meta.mode & mask != expected {
+                    return Some("mode");
                 }
             }
         }
 
         // Creation time predicates
-        if let Ok(ctime) = meta.created() {
+        if let Some(ctime) = meta.created {
             if let Some(after) = self.ctime_after {
                 if ctime < after {
-                    return false;
+                    return Some("ctime_after");
                 }
             }
             if let Some(before) = self.ctime_before {
                 if ctime > before {
-                    return false;
+                    return Some("ctime_before");
                 }
             }
         }
 
-        true
+        None
+    }
+}
+
+/// Builder for Predicates for fluent configuration
+///
+/// This is the preferred way to construct [`Predicates`]: unset
+/// predicates stay disabled, and callers keep compiling when new
+/// predicate fields are added.
+pub struct PredicatesBuilder(Predicates);
+
+impl Default for PredicatesBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PredicatesBuilder {
+    /// Creates a new builder with no predicates set
+    pub fn new() -> Self {
+        Self(Predicates::default())
+    }
+
+    /// Sets the minimum file size in bytes
+    pub fn min_size(mut self, bytes: u64) -> Self {
+        self.0.min_size = Some(bytes);
+        self
+    }
+
+    /// Sets the maximum file size in bytes
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.0.max_size = Some(bytes);
+        self
+    }
+
+    /// Sets the required file type
+    pub fn file_type(mut self, ft: FileType) -> Self {
+        self.0.file_type = Some(ft);
+        self
+    }
+
+    /// Requires the file to be modified after this time
+    pub fn mtime_after(mut self, t: SystemTime) -> Self {
+        self.0.mtime_after = Some(t);
+        self
+    }
+
+    /// Requires the file to be modified before this time
+    pub fn mtime_before(mut self, t: SystemTime) -> Self {
+        self.0.mtime_before = Some(t);
+        self
+    }
+
+    /// Requires the file to be modified at most this long ago
+    pub fn mtime_within(mut self, age: Duration) -> Self {
+        self.0.mtime_within = Some(age);
+        self
+    }
+
+    /// Requires the file to be modified at least this long ago
+    pub fn mtime_older_than(mut self, age: Duration) -> Self {
+        self.0.mtime_older_than = Some(age);
+        self
+    }
+
+    /// Requires the file to be created after this time
+    pub fn ctime_after(mut self, t: SystemTime) -> Self {
+        self.0.ctime_after = Some(t);
+        self
+    }
+
+    /// Requires the file to be created before this time
+    pub fn ctime_before(mut self, t: SystemTime) -> Self {
+        self.0.ctime_before = Some(t);
+        self
+    }
+
+    /// Sets whether to follow symlinks for metadata checks
+    pub fn follow_symlinks(mut self, v: bool) -> Self {
+        self.0.follow_symlinks = v;
+        self
+    }
+
+    /// Requires the file to be owned by this user id
+    #[cfg(unix)]
+    pub fn uid(mut self, uid: u32) -> Self {
+        self.0.uid = Some(uid);
+        self
+    }
+
+    /// Requires the file to be owned by this group id
+    #[cfg(unix)]
+    pub fn gid(mut self, gid: u32) -> Self {
+        self.0.gid = Some(gid);
+        self
+    }
+
+    /// Requires the file to have at least this many hard links
+    #[cfg(unix)]
+    pub fn nlink_min(mut self, n: u64) -> Self {
+        self.0.nlink_min = Some(n);
+        self
+    }
+
+    /// Requires the file to have this inode number
+    #[cfg(unix)]
+    pub fn inode(mut self, ino: u64) -> Self {
+        self.0.inode = Some(ino);
+        self
+    }
+
+    /// Requires `(mode & mask) == expected` for the file's permission bits
+    ///
+    /// For example `mode(0o002, 0o002)` selects world-writable files.
+    #[cfg(unix)]
+    pub fn mode(mut self, mask: u32, expected: u32) -> Self {
+        self.0.mode_mask = Some(mask);
+        self.0.mode_expected = Some(expected);
+        self
+    }
+
+    /// Sets a secondary glob the entry path must also match
+    pub fn path_glob(mut self, glob: impl Into<String>) -> Self {
+        self.0.path_glob = Some(glob.into());
+        self
+    }
+
+    /// Requires the file contents to start with these bytes
+    pub fn content_prefix(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.0.content_prefix = Some(bytes.into());
+        self
+    }
+
+    /// Requires the extended attribute `name` to be set
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    pub fn has_xattr(mut self, name: impl Into<String>) -> Self {
+        self.0.has_xattr = Some(name.into());
+        self
+    }
+
+    /// Requires the extended attribute `name` to hold `value`
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    pub fn xattr_value(mut self, name: impl Into<String>, value: impl Into<Vec<u8>>) -> Self {
+        self.0.has_xattr = Some(name.into());
+        self.0.xattr_value = Some(value.into());
+        self
+    }
+
+    /// Only matches entries the current user can read
+    pub fn readable(mut self, v: bool) -> Self {
+        self.0.readable = v;
+        self
+    }
+
+    /// Builds the final Predicates instance
+    pub fn build(self) -> Predicates {
+        self.0
     }
 }
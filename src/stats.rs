@@ -0,0 +1,32 @@
+// stats.rs
+use crate::patterns::cache::CacheMetrics;
+use std::time::Duration;
+
+/// Counters describing how much work a glob operation did
+///
+/// Traversal counters accumulate across all roots of a call. The cache
+/// metrics are a snapshot of the global pattern caches taken when the
+/// operation finished.
+#[derive(Clone, Debug, Default)]
+pub struct GlobStats {
+    /// Directories encountered during the walk, including the root
+    pub dirs_visited: usize,
+
+    /// Entries yielded by the walk, before any filtering
+    pub entries_examined: usize,
+
+    /// Metadata lookups made for predicate evaluation
+    pub stat_calls: usize,
+
+    /// Entries returned as matches
+    pub matches: usize,
+
+    /// Time spent walking so far
+    pub elapsed: Duration,
+
+    /// Global glob cache metrics at the end of the operation
+    pub glob_cache: CacheMetrics,
+
+    /// Global regex cache metrics at the end of the operation
+    pub regex_cache: CacheMetrics,
+}
@@ -1,60 +1,209 @@
 // sync.rs
 use crate::{
-    batch_io::BatchIO, error::GlobError, patterns::Patterns, predicates::Predicates, GlobOptions,
+    batch_io::BatchIO,
+    entry::GlobEntry,
+    error::GlobError,
+    fs::{FileSystem, RealFileSystem},
+    options::{ErrorPolicy, OnNonUtf8, Spend},
+    patterns::Patterns,
+    predicates::{FileType, Predicates},
+    stats::GlobStats,
+    walker::{WalkEntry, Walker},
+    windows, GlobOptions,
 };
-use camino::Utf8PathBuf;
 use std::{
+    borrow::Cow,
     collections::HashSet,
+    ops::ControlFlow,
     path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
 };
-use walkdir::WalkDir;
 
-/// Checks for symlink cycles during directory traversal
+/// Number of entries processed between deadline checks
+const TIMEOUT_CHECK_INTERVAL: usize = 64;
+
+/// Entries examined between progress reports
+const PROGRESS_INTERVAL_ENTRIES: usize = 256;
+
+/// Longest time between progress reports, checked with the deadline
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Checks whether an entry `depth` levels below the root is hidden
+/// (its name starts with `.`)
+///
+/// The traversal root itself is never considered hidden.
+fn is_hidden(dent: &WalkEntry, depth: usize) -> bool {
+    depth > 0
+        && dent
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+}
+
+/// Checks if a path is allowed based on root directory restrictions
 ///
-/// This function maintains a set of visited paths and detects cycles
-/// when following symlinks, which is crucial to prevent infinite loops.
+/// This function ensures that paths outside the specified root directory
+/// are excluded from results, providing basic security against path traversal.
 ///
 /// # Arguments
 ///
-/// * `path` - The current path being visited
-/// * `visited` - Mutable reference to the set of visited paths
+/// * `path` - The path to check
+/// * `root` - Root directory the walk started from
 ///
 /// # Returns
 ///
-/// `true` if a cycle is detected, `false` otherwise
-fn check_for_cycles(path: &Path, visited: &mut HashSet<PathBuf>) -> bool {
-    if visited.contains(path) {
-        return true;
-    }
-    visited.insert(path.to_path_buf());
-    false
+/// `true` if the path is allowed, `false` otherwise
+fn is_path_allowed(path: &Path, root: &Path) -> bool {
+    // The drive letter of a root typed as `c:\` may differ in case
+    windows::normalize_drive_letter_path(path)
+        .starts_with(windows::normalize_drive_letter_path(root))
 }
 
-/// Checks if a path is allowed based on root directory restrictions
+/// Checks whether a symlink entry resolves to a location under `root`
 ///
-/// This function ensures that paths outside the specified root directory
-/// are excluded from results, providing basic security against path traversal.
+/// Non-symlink entries are not canonicalized: their parents were already
+/// checked, so they cannot escape on their own.
 ///
 /// # Arguments
 ///
-/// * `path` - The path to check
-/// * `root_dir` - Optional root directory restriction
+/// * `fs` - Filesystem the walk goes through
+/// * `dent` - The entry to check
+/// * `root` - Canonicalized root directory
+fn is_confined(fs: &dyn FileSystem, dent: &WalkEntry, root: &Path) -> bool {
+    if !dent.is_symlink {
+        return true;
+    }
+    fs.canonicalize(&dent.path)
+        .is_ok_and(|target| target.starts_with(root))
+}
+
+/// Counts the symlinks between the traversal root and an entry, inclusive
+///
+/// Only the `depth` components below the root are examined, so a
+/// symlinked root does not count as a hop.
+fn symlink_hops(fs: &dyn FileSystem, dent: &WalkEntry, depth: usize) -> usize {
+    dent.path
+        .ancestors()
+        .take(depth)
+        .filter(|p| {
+            fs.symlink_metadata(p)
+                .is_ok_and(|m| m.file_type == FileType::Symlink)
+        })
+        .count()
+}
+
+/// Applies the error policy to a per-entry error
 ///
 /// # Returns
 ///
-/// `true` if the path is allowed, `false` otherwise
-fn is_path_allowed(path: &Path, root_dir: &Option<PathBuf>) -> bool {
-    if let Some(root) = root_dir {
-        path.starts_with(root)
+/// `Err` if the walk must stop, `Ok(())` if it should move on to the
+/// next entry; collected errors are pushed onto `errors`
+fn handle_entry_error(
+    policy: ErrorPolicy,
+    err: GlobError,
+    errors: &mut Vec<GlobError>,
+) -> Result<(), GlobError> {
+    match policy {
+        ErrorPolicy::Abort => Err(err),
+        ErrorPolicy::Skip => Ok(()),
+        ErrorPolicy::Collect => {
+            errors.push(err);
+            Ok(())
+        }
+    }
+}
+
+/// Returns the path patterns are matched against
+///
+/// This is `path` itself, or its part below `root` when
+/// `opts.patterns_relative_to_root` is set.
+pub(crate) fn match_target<'a>(path: &'a Path, root: &Path, opts: &GlobOptions) -> &'a Path {
+    if opts.patterns_relative_to_root {
+        relative_to_root(path, root)
     } else {
-        true
+        path
+    }
+}
+
+/// Returns the part of `path` below `root`, with the root itself as `.`
+pub(crate) fn relative_to_root<'a>(path: &'a Path, root: &Path) -> &'a Path {
+    match path.strip_prefix(root) {
+        Ok(rest) if rest.as_os_str().is_empty() => Path::new("."),
+        Ok(rest) => rest,
+        Err(_) => path,
+    }
+}
+
+/// Returns whether a directory could be a match or hold one
+///
+/// Directories that depart from every pattern's leading segments are skipped
+/// with their contents (see [`Patterns::could_match`]). Symlinks matched
+/// by their resolved target can match anywhere, so nothing is pruned then.
+pub(crate) fn could_reach(
+    patterns: &Patterns,
+    dir: &Path,
+    root: &Path,
+    opts: &GlobOptions,
+) -> bool {
+    if opts.match_resolved_path {
+        return true;
+    }
+    let unprefixed = windows::strip_long_path_prefix(dir);
+    match match_target(&unprefixed, root, opts).to_str() {
+        Some(s) => patterns.could_match_str(s),
+        None => true,
+    }
+}
+
+/// Removes a leading `.` component, keeping `.` itself
+pub(crate) fn strip_dot_prefix(path: PathBuf) -> PathBuf {
+    match path.strip_prefix(".") {
+        Ok(rest) if !rest.as_os_str().is_empty() => rest.to_path_buf(),
+        _ => path,
+    }
+}
+
+/// Records a match's canonical path, returning whether it is the first
+///
+/// Always `true` when `seen` is `None`, as it is unless
+/// `dedupe_by_canonical` is set. Paths are canonicalized through `fs`; a
+/// path that can't be canonicalized is recorded as it is.
+pub(crate) fn first_canonical(
+    seen: &mut Option<HashSet<PathBuf>>,
+    fs: &dyn FileSystem,
+    path: &Path,
+) -> bool {
+    match seen {
+        Some(seen) => seen.insert(fs.canonicalize(path).unwrap_or_else(|_| path.to_path_buf())),
+        None => true,
+    }
+}
+
+/// Returns the string a path is matched against under the given policy
+///
+/// # Returns
+///
+/// `Ok(None)` if the path should be skipped, or `Err(GlobError::NonUtf8Path)`
+/// for non-UTF-8 paths under `OnNonUtf8::Error`
+pub(crate) fn path_for_matching(
+    path: &Path,
+    policy: OnNonUtf8,
+) -> Result<Option<Cow<'_, str>>, GlobError> {
+    match path.to_str() {
+        Some(s) => Ok(Some(Cow::Borrowed(s))),
+        None => match policy {
+            OnNonUtf8::Skip => Ok(None),
+            OnNonUtf8::Error => Err(GlobError::NonUtf8Path(path.to_path_buf())),
+            OnNonUtf8::Lossy => Ok(Some(path.to_string_lossy())),
+        },
     }
 }
 
 /// Performs synchronous glob pattern matching
 ///
-/// This function traverses the directory tree synchronously using WalkDir,
-/// matches paths against compiled patterns, and applies predicate filters.
+/// This function traverses the directory tree synchronously on the host
+/// filesystem, matches paths against compiled patterns, and applies
+/// predicate filters.
 ///
 /// # Arguments
 ///
@@ -69,59 +218,786 @@ fn is_path_allowed(path: &Path, root_dir: &Option<PathBuf>) -> bool {
 /// # Errors
 ///
 /// Returns `GlobError` for I/O errors, permission denied, symlink cycles,
-/// and other issues during filesystem traversal.
+/// and other issues during filesystem traversal, unless `opts.on_error`
+/// tolerates them. Returns `GlobError::Timeout` when `opts.timeout`
+/// elapses before the walk ends, and `GlobError::Cancelled` once
+/// `opts.cancel` is set.
 pub fn glob_sync(
     patterns: Patterns,
     opts: GlobOptions,
     predicates: Option<Predicates>,
 ) -> Result<Vec<PathBuf>, GlobError> {
+    let entries = glob_sync_entries(patterns, opts, predicates)?;
+    Ok(entries.into_iter().map(GlobEntry::into_path).collect())
+}
+
+/// Performs synchronous glob pattern matching, returning rich entries
+///
+/// Same traversal as [`glob_sync`], but each match is reported as a
+/// [`GlobEntry`] carrying its depth, file type and (when predicates
+/// required a stat) its metadata.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+///
+/// # Returns
+///
+/// `Ok(Vec<GlobEntry>)` with matching entries, or `Err(GlobError)` on failure
+pub fn glob_sync_entries(
+    patterns: Patterns,
+    opts: GlobOptions,
+    predicates: Option<Predicates>,
+) -> Result<Vec<GlobEntry>, GlobError> {
     let mut results = Vec::new();
-    let root = opts.root_dir.clone().unwrap_or_else(|| PathBuf::from("."));
-    let mut visited_links = HashSet::new();
-    let batch_io = BatchIO::new(1000, opts.follow_symlinks);
+    let _ = glob_sync_visit(&patterns, &opts, predicates.as_ref(), |entry| {
+        results.push(entry);
+        ControlFlow::Continue(())
+    })?;
+    Ok(results)
+}
+
+/// Walks the tree synchronously, invoking a visitor for every match
+///
+/// This is the core of the synchronous traversal: matches are handed to
+/// `visit` as they are found instead of being collected, and the walk
+/// stops as soon as the visitor returns `ControlFlow::Break`.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+/// * `visit` - Callback invoked with each matching entry
+///
+/// # Returns
+///
+/// `Ok(ControlFlow::Break(()))` if the visitor stopped the walk early,
+/// `Ok(ControlFlow::Continue(()))` if the walk ran to completion,
+/// or `Err(GlobError)` on failure
+pub fn glob_sync_visit<F>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+{
+    let mut stats = GlobStats::default();
+    glob_sync_visit_with_stats(patterns, opts, predicates, &mut stats, visit)
+}
 
-    // Use WalkDir for efficient directory traversal
-    for entry in WalkDir::new(&root)
+/// Walks the tree synchronously, counting the work done
+///
+/// Same as [`glob_sync_visit`], but traversal counters are added to
+/// `stats` as the walk proceeds. Cache metrics are left untouched.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+/// * `stats` - Counters to accumulate into
+/// * `visit` - Callback invoked with each matching entry
+///
+/// # Returns
+///
+/// The same as [`glob_sync_visit`]
+pub fn glob_sync_visit_with_stats<F>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    stats: &mut GlobStats,
+    visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+{
+    let mut errors = Vec::new();
+    walk(
+        patterns,
+        opts,
+        predicates,
+        &RealFileSystem,
+        stats,
+        &mut errors,
+        None,
+        visit,
+    )
+}
+
+/// Walks the tree synchronously, reporting progress along the way
+///
+/// Same as [`glob_sync_visit_with_stats`], but `progress` is called with
+/// the running counters every few hundred entries or 100 milliseconds,
+/// whichever comes first, and once more when the walk completes.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+/// * `stats` - Counters to accumulate into
+/// * `progress` - Callback observing the running counters
+/// * `visit` - Callback invoked with each matching entry
+///
+/// # Returns
+///
+/// The same as [`glob_sync_visit`]
+pub fn glob_sync_visit_with_progress<F, P>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    stats: &mut GlobStats,
+    mut progress: P,
+    visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+    P: FnMut(&GlobStats),
+{
+    let mut errors = Vec::new();
+    walk(
+        patterns,
+        opts,
+        predicates,
+        &RealFileSystem,
+        stats,
+        &mut errors,
+        Some(&mut progress),
+        visit,
+    )
+}
+
+/// Performs synchronous glob pattern matching, collecting per-entry errors
+///
+/// Behaves like [`glob_sync`], but errors tolerated under
+/// [`ErrorPolicy::Collect`] are returned next to the results instead of
+/// being dropped. With the other policies the error list is always empty.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+///
+/// # Returns
+///
+/// `Ok((paths, errors))`, or `Err(GlobError)` if the walk was aborted
+pub fn glob_sync_collect(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+) -> Result<(Vec<PathBuf>, Vec<GlobError>), GlobError> {
+    let mut stats = GlobStats::default();
+    let mut errors = Vec::new();
+    let mut results = Vec::new();
+    let _ = walk(
+        patterns,
+        opts,
+        predicates,
+        &RealFileSystem,
+        &mut stats,
+        &mut errors,
+        None,
+        |e| {
+            results.push(e.path);
+            ControlFlow::Continue(())
+        },
+    )?;
+    Ok((results, errors))
+}
+
+/// Traversal shared by the synchronous entry points
+///
+/// Walks on the calling thread, or splits the tree across `opts.threads`
+/// workers when more than one is requested. All I/O goes through `fs`.
+#[allow(clippy::too_many_arguments)]
+fn walk<F>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    fs: &dyn FileSystem,
+    stats: &mut GlobStats,
+    errors: &mut Vec<GlobError>,
+    progress: Option<&mut dyn FnMut(&GlobStats)>,
+    visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+{
+    let root = opts.walk_root();
+    let scope = Scope {
+        root: &root,
+        start: &root,
+        depth: 0,
+        max_depth: opts.max_depth.unwrap_or(usize::MAX),
+        deadline: opts.timeout.and_then(|t| Instant::now().checked_add(t)),
+        stop: None,
+    };
+    let mut bytes_spent = 0;
+    match opts.threads {
+        // A walk no deeper than the root's children has nothing to split
+        #[cfg(feature = "parallel")]
+        Some(threads) if threads > 1 && scope.max_depth > 1 => walk_parallel(
+            patterns, opts, predicates, fs, &scope, threads, stats, errors, progress, visit,
+        ),
+        _ => walk_tree(
+            patterns,
+            opts,
+            predicates,
+            fs,
+            &scope,
+            &mut bytes_spent,
+            stats,
+            errors,
+            progress,
+            visit,
+        ),
+    }
+}
+
+/// The part of the tree one [`Walker`] pass covers
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    /// Root of the whole walk, which matching and confinement refer to
+    root: &'a Path,
+    /// Directory this pass starts from: the root or one of its children
+    start: &'a Path,
+    /// Depth of `start` below the root
+    depth: usize,
+    /// Deepest level below the root this pass descends to
+    max_depth: usize,
+    /// Deadline shared by every pass of the walk
+    deadline: Option<Instant>,
+    /// Set once the walk has stopped early, ending passes still running
+    stop: Option<&'a AtomicBool>,
+}
+
+/// What a subtree worker reports to the thread delivering matches
+#[cfg(feature = "parallel")]
+enum Found {
+    Entry(GlobEntry),
+    Done(Result<(), GlobError>, GlobStats, Vec<GlobError>),
+}
+
+/// Returns the pool of `threads` workers, built on first use
+///
+/// Pools are kept for later walks, one per thread count in use.
+#[cfg(feature = "parallel")]
+fn thread_pool(threads: usize) -> Result<std::sync::Arc<rayon::ThreadPool>, GlobError> {
+    use once_cell::sync::Lazy;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    static POOLS: Lazy<Mutex<HashMap<usize, Arc<rayon::ThreadPool>>>> = Lazy::new(Default::default);
+
+    let mut pools = POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(Arc::clone(pool));
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("patternhunt-walk-{}", i))
+        .build()
+        .map_err(|e| GlobError::Other(format!("Failed to start worker threads: {}", e)))?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Ok(pool)
+}
+
+/// Walks the root's children on the calling thread, then each child
+/// directory as a subtree on a pool of `threads` workers
+///
+/// Subtree matches are handed to `visit` on the calling thread as workers
+/// find them, in directory order: a later subtree's matches wait until the
+/// earlier ones are done. The byte budget is charged again as they are
+/// delivered, since each worker charged it on its own. Once the walk stops
+/// early, workers still running stop at their next entry.
+#[cfg(feature = "parallel")]
+#[allow(clippy::too_many_arguments)]
+fn walk_parallel<F>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    fs: &dyn FileSystem,
+    scope: &Scope,
+    threads: usize,
+    stats: &mut GlobStats,
+    errors: &mut Vec<GlobError>,
+    mut progress: Option<&mut dyn FnMut(&GlobStats)>,
+    mut visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+{
+    use std::{collections::VecDeque, sync::mpsc};
+
+    let started = Instant::now();
+    let elapsed_before = stats.elapsed;
+    let mut bytes_spent = 0;
+    let top = Scope {
+        max_depth: 1,
+        ..*scope
+    };
+    // Nothing at the top level is yielded below min_depth
+    let flow = if opts.min_depth.is_some_and(|d| d > 1) {
+        ControlFlow::Continue(())
+    } else {
+        walk_tree(
+            patterns,
+            opts,
+            predicates,
+            fs,
+            &top,
+            &mut bytes_spent,
+            stats,
+            errors,
+            progress
+                .as_mut()
+                .map(|p| &mut **p as &mut dyn FnMut(&GlobStats)),
+            &mut visit,
+        )?
+    };
+    if flow.is_break() {
+        return Ok(flow);
+    }
+
+    let pool = thread_pool(threads)?;
+    let dirs = subtrees(fs, opts, scope);
+    let stop = &AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    let outcome = pool.in_place_scope(|workers| {
+        for (i, dir) in dirs.iter().enumerate() {
+            let tx = tx.clone();
+            let subtree = Scope {
+                start: dir,
+                depth: 1,
+                stop: Some(stop),
+                ..*scope
+            };
+            workers.spawn(move |_| {
+                // Subtrees queued behind an early stop are never read
+                if stop.load(Ordering::Relaxed) {
+                    let _ = tx.send((i, Found::Done(Ok(()), GlobStats::default(), Vec::new())));
+                    return;
+                }
+                let mut spent = bytes_spent;
+                let mut stats = GlobStats::default();
+                let mut errors = Vec::new();
+                let result = walk_tree(
+                    patterns,
+                    opts,
+                    predicates,
+                    fs,
+                    &subtree,
+                    &mut spent,
+                    &mut stats,
+                    &mut errors,
+                    None,
+                    // Sending fails once the delivering thread is gone
+                    |entry| match tx.send((i, Found::Entry(entry))) {
+                        Ok(()) => ControlFlow::Continue(()),
+                        Err(_) => ControlFlow::Break(()),
+                    },
+                );
+                let _ = tx.send((i, Found::Done(result.map(|_| ()), stats, errors)));
+            });
+        }
+        drop(tx);
+
+        // Matches are counted as they are delivered, not as workers find them
+        let mut buffered: Vec<VecDeque<Found>> = dirs.iter().map(|_| VecDeque::new()).collect();
+        let mut next = 0;
+        let outcome = 'deliver: loop {
+            while let Some(found) = buffered.get_mut(next).and_then(VecDeque::pop_front) {
+                match found {
+                    Found::Entry(entry) => {
+                        let len = entry
+                            .metadata
+                            .as_ref()
+                            .filter(|m| m.is_file())
+                            .map_or(0, |m| m.len);
+                        let spend = opts.spend_bytes(&mut bytes_spent, len);
+                        if spend == Spend::Over {
+                            break 'deliver Ok(ControlFlow::Break(()));
+                        }
+                        stats.matches += 1;
+                        if visit(entry).is_break() || spend == Spend::Last {
+                            break 'deliver Ok(ControlFlow::Break(()));
+                        }
+                    }
+                    Found::Done(result, worker, mut worker_errors) => {
+                        stats.dirs_visited += worker.dirs_visited;
+                        stats.entries_examined += worker.entries_examined;
+                        stats.stat_calls += worker.stat_calls;
+                        errors.append(&mut worker_errors);
+                        if let Err(e) = result {
+                            break 'deliver Err(e);
+                        }
+                        next += 1;
+                    }
+                }
+            }
+            if next == dirs.len() {
+                break Ok(ControlFlow::Continue(()));
+            }
+            match rx.recv() {
+                Ok((i, found)) => buffered[i].push_back(found),
+                // Unreachable: every worker reports Done before hanging up
+                Err(_) => break Ok(ControlFlow::Continue(())),
+            }
+        };
+        // Workers still walking notice at their next entry
+        stop.store(true, Ordering::Relaxed);
+        outcome
+    });
+
+    stats.elapsed = elapsed_before + started.elapsed();
+    if outcome.as_ref().is_ok_and(|flow| flow.is_continue()) {
+        if let Some(report) = progress {
+            report(stats);
+        }
+    }
+    outcome
+}
+
+/// Lists the root's child directories a sequential walk would descend into
+#[cfg(feature = "parallel")]
+fn subtrees(fs: &dyn FileSystem, opts: &GlobOptions, scope: &Scope) -> Vec<PathBuf> {
+    use crate::walker::device_id;
+
+    let min_depth = opts.min_depth.unwrap_or(0);
+    let filter = EntryFilter::new(fs, opts, scope.root, min_depth);
+    let root_dev = (!opts.cross_filesystems)
+        .then(|| device_id(fs, scope.root))
+        .flatten();
+    let start = windows::ensure_long_path_prefix(scope.root);
+    // Entries above min_depth are never filtered, only descended
+    Walker::new(fs, start, |dent| min_depth > 1 || filter.keeps(dent, 1))
         .follow_links(opts.follow_symlinks)
-        .same_file_system(true)
-        .max_depth(opts.max_depth.unwrap_or(usize::MAX))
-    {
-        let dent = entry.map_err(GlobError::Walkdir)?;
-        let p = dent.path();
+        .min_depth(1)
+        .max_depth(1)
+        .filter_map(Result::ok)
+        .filter(WalkEntry::is_dir)
+        .filter(|dent| root_dev.is_none() || device_id(fs, &dent.path) == root_dev)
+        .map(|dent| dent.path)
+        .collect()
+}
+
+/// Decides which entries are yielded and descended into
+struct EntryFilter<'a> {
+    fs: &'a dyn FileSystem,
+    opts: &'a GlobOptions,
+    /// Canonical root, when followed symlinks must stay under it
+    confined_root: Option<PathBuf>,
+    max_hops: Option<usize>,
+    /// Shallowest depth the pass yields, the first the filter sees
+    first_level: usize,
+}
+
+impl<'a> EntryFilter<'a> {
+    fn new(fs: &'a dyn FileSystem, opts: &'a GlobOptions, root: &Path, first_level: usize) -> Self {
+        Self {
+            fs,
+            opts,
+            confined_root: (opts.follow_symlinks && opts.confine_to_root)
+                .then(|| fs.canonicalize(root).unwrap_or_else(|_| root.to_path_buf())),
+            max_hops: opts.max_symlink_depth.filter(|_| opts.follow_symlinks),
+            first_level,
+        }
+    }
+
+    /// Whether an entry at `depth` below the root is kept
+    fn keeps(&self, dent: &WalkEntry, depth: usize) -> bool {
+        !(self.opts.skip_hidden && is_hidden(dent, depth))
+            && self
+                .confined_root
+                .as_ref()
+                .is_none_or(|r| is_confined(self.fs, dent, r))
+            // Only symlinks add hops, but entries skipped by min_depth
+            // never reach this filter, so check the first level seen too
+            && self.max_hops.is_none_or(|max| {
+                !(dent.is_symlink || depth == self.first_level)
+                    || symlink_hops(self.fs, dent, depth) <= max
+            })
+    }
+}
+
+/// Walks one [`Scope`], visiting matches as they are found
+#[allow(clippy::too_many_arguments)]
+fn walk_tree<F>(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    fs: &dyn FileSystem,
+    scope: &Scope,
+    bytes_spent: &mut u64,
+    stats: &mut GlobStats,
+    errors: &mut Vec<GlobError>,
+    mut progress: Option<&mut dyn FnMut(&GlobStats)>,
+    mut visit: F,
+) -> Result<ControlFlow<()>, GlobError>
+where
+    F: FnMut(GlobEntry) -> ControlFlow<()>,
+{
+    let started = Instant::now();
+    let elapsed_before = stats.elapsed;
+    let mut last_report = (stats.entries_examined, started);
+    let root = scope.root;
+    // Predicates may ask to stat through symlinks even when the walk doesn't
+    let stat_follows = opts.follow_symlinks || predicates.is_some_and(|p| p.follow_symlinks);
+    let batch_io = match &opts.batch_io {
+        Some(shared) => shared.following(stat_follows),
+        None => BatchIO::new(1000, stat_follows),
+    };
+    let deadline = scope.deadline;
+    let match_dirs = opts.matches_directories();
+    // A subtree pass leaves its own directory to the pass over the root
+    let min_depth = opts.min_depth.unwrap_or(0);
+    let first_level = if scope.depth == 0 {
+        min_depth
+    } else {
+        min_depth.max(scope.depth + 1)
+    };
+    let filter = EntryFilter::new(fs, opts, root, first_level);
+
+    // Walk long paths through their `\\?\` form on Windows
+    let start = windows::ensure_long_path_prefix(scope.start);
+    let walker = Walker::new(fs, start, |dent| {
+        let depth = scope.depth + dent.depth;
+        filter.keeps(dent, depth)
+            && (depth == 0 || !dent.is_dir() || could_reach(patterns, &dent.path, root, opts))
+    })
+    .follow_links(opts.follow_symlinks)
+    .same_file_system(!opts.cross_filesystems)
+    .min_depth(first_level - scope.depth)
+    .max_depth(scope.max_depth - scope.depth);
+    for (i, entry) in walker.enumerate() {
+        // Enforce the overall timeout, checking the clock only periodically
+        if i % TIMEOUT_CHECK_INTERVAL == 0 && deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(GlobError::Timeout);
+        }
+        if opts.is_cancelled() {
+            return Err(GlobError::Cancelled);
+        }
+        if scope.stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+            stats.elapsed = elapsed_before + started.elapsed();
+            return Ok(ControlFlow::Break(()));
+        }
+
+        let dent = match entry {
+            Ok(dent) => dent,
+            Err(e) => {
+                handle_entry_error(opts.on_error, e, errors)?;
+                continue;
+            }
+        };
+        // I/O goes through `io_path`; everything else sees the unprefixed form
+        let io_path = dent.path.as_path();
+        let depth = scope.depth + dent.depth;
+        let unprefixed = windows::strip_long_path_prefix(io_path);
+        let p = &*unprefixed;
+        stats.entries_examined += 1;
+        if dent.is_dir() {
+            stats.dirs_visited += 1;
+        }
+
+        // Report progress by entry count, or by time at the deadline checks
+        if let Some(report) = progress.as_deref_mut() {
+            let due = stats.entries_examined - last_report.0 >= PROGRESS_INTERVAL_ENTRIES
+                || (i % TIMEOUT_CHECK_INTERVAL == 0
+                    && last_report.1.elapsed() >= PROGRESS_INTERVAL);
+            if due {
+                stats.elapsed = elapsed_before + started.elapsed();
+                report(stats);
+                last_report = (stats.entries_examined, Instant::now());
+            }
+        }
 
         // Check path restrictions
-        if !is_path_allowed(p, &opts.root_dir) {
+        if !is_path_allowed(p, root) {
             continue;
         }
 
-        // Check for symlink cycles if following symlinks
-        if opts.follow_symlinks && check_for_cycles(p, &mut visited_links) {
-            return Err(GlobError::SymlinkCycle);
+        // Directories are candidates only when requested, the root only
+        // with `include_root`
+        if dent.is_dir() && (!match_dirs || (depth == 0 && !opts.include_root)) {
+            continue;
         }
 
-        // Skip directories (we're only interested in files)
-        if p.is_dir() {
+        // Symlinks can be matched, and returned, by their canonical target
+        let resolved = (dent.is_symlink && opts.resolves_symlinks())
+            .then(|| fs.canonicalize(io_path).ok())
+            .flatten()
+            .map(|r| windows::strip_long_path_prefix(&r).into_owned());
+        let matched = match &resolved {
+            Some(r) if opts.match_resolved_path => r.as_path(),
+            _ => p,
+        };
+
+        // Pattern matching, subject to the non-UTF-8 policy
+        let target = match_target(matched, root, opts);
+        let Some(path_str) = path_for_matching(target, opts.non_utf8)? else {
+            continue;
+        };
+        if !patterns.matches_entry_str(&path_str, dent.is_dir()) {
             continue;
         }
 
-        // Convert to UTF-8 path for pattern matching
-        if let Ok(up) = Utf8PathBuf::from_path_buf(p.to_path_buf()) {
-            // Pattern matching
-            if !patterns.is_match(&up) {
+        // Predicate filtering
+        let mut metadata = None;
+        if let Some(pred) = predicates {
+            stats.stat_calls += 1;
+            let meta = match batch_io.stat(fs, io_path) {
+                Ok(meta) => meta,
+                Err(e) => {
+                    handle_entry_error(opts.on_error, e, errors)?;
+                    continue;
+                }
+            };
+            if !pred.matches_path_only(matched) || !pred.matches_metadata(&meta) {
                 continue;
             }
-
-            // Predicate filtering
-            if let Some(pred) = &predicates {
-                let meta = batch_io.stat(p)?;
-                if !pred.matches(&meta) {
+            let read = |len| batch_io.read_prefix(fs, io_path, len);
+            match pred.matches_content(meta.is_file(), read) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    handle_entry_error(opts.on_error, e, errors)?;
+                    continue;
+                }
+            }
+            match pred.matches_xattr(|name| batch_io.read_xattr(fs, io_path, name)) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    handle_entry_error(opts.on_error, e, errors)?;
                     continue;
                 }
             }
+            match pred.matches_readable(|| batch_io.is_readable(fs, io_path)) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    handle_entry_error(opts.on_error, e, errors)?;
+                    continue;
+                }
+            }
+            metadata = Some(meta);
+        }
 
-            results.push(p.to_path_buf());
+        // Charge matched files against the byte budget
+        let mut spend = Spend::Keep;
+        if opts.max_total_bytes.is_some() {
+            if metadata.is_none() {
+                stats.stat_calls += 1;
+                match batch_io.stat(fs, io_path) {
+                    Ok(meta) => metadata = Some(meta),
+                    Err(e) => {
+                        handle_entry_error(opts.on_error, e, errors)?;
+                        continue;
+                    }
+                }
+            }
+            let len = metadata
+                .as_ref()
+                .filter(|m| m.is_file())
+                .map_or(0, |m| m.len);
+            spend = opts.spend_bytes(bytes_spent, len);
+            if spend == Spend::Over {
+                stats.elapsed = elapsed_before + started.elapsed();
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+
+        let path = match resolved {
+            Some(r) if opts.return_resolved_path => r,
+            _ if opts.keep_long_path_prefix => io_path.to_path_buf(),
+            _ => p.to_path_buf(),
+        };
+        let path = if opts.strip_dot_prefix {
+            strip_dot_prefix(path)
+        } else {
+            path
+        };
+        let entry = GlobEntry {
+            path,
+            depth,
+            file_type: entry_file_type(&dent),
+            metadata,
+        };
+        stats.matches += 1;
+        if visit(entry).is_break() || spend == Spend::Last {
+            stats.elapsed = elapsed_before + started.elapsed();
+            return Ok(ControlFlow::Break(()));
         }
     }
 
+    stats.elapsed = elapsed_before + started.elapsed();
+    if let Some(report) = progress {
+        report(stats);
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Performs synchronous glob pattern matching over a [`FileSystem`]
+///
+/// Same traversal as [`glob_sync`], but every directory listing, metadata
+/// lookup and read goes through `fs`, so the walk can be exercised against
+/// an in-memory implementation. Filesystem boundaries are detected through
+/// [`crate::FsMetadata`]'s device id.
+///
+/// # Arguments
+///
+/// * `patterns` - Compiled patterns to match against
+/// * `opts` - Configuration options for globbing
+/// * `predicates` - Optional predicates for filtering files
+/// * `fs` - Filesystem to traverse
+///
+/// # Returns
+///
+/// `Ok(Vec<PathBuf>)` with matching paths, or `Err(GlobError)` on failure
+///
+/// # Errors
+///
+/// The same as [`glob_sync`], with `GlobError::Io` carrying the errors
+/// reported by `fs`
+pub fn glob_sync_fs(
+    patterns: &Patterns,
+    opts: &GlobOptions,
+    predicates: Option<&Predicates>,
+    fs: &dyn FileSystem,
+) -> Result<Vec<PathBuf>, GlobError> {
+    let mut stats = GlobStats::default();
+    let mut errors = Vec::new();
+    let mut results = Vec::new();
+    let _ = walk(
+        patterns,
+        opts,
+        predicates,
+        fs,
+        &mut stats,
+        &mut errors,
+        None,
+        |e| {
+            results.push(e.path);
+            ControlFlow::Continue(())
+        },
+    )?;
     Ok(results)
 }
+
+/// Classifies a walked entry into the crate's file type
+fn entry_file_type(dent: &WalkEntry) -> FileType {
+    if dent.is_symlink {
+        FileType::Symlink
+    } else {
+        dent.file_type
+    }
+}
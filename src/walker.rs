@@ -0,0 +1,264 @@
+// walker.rs
+use crate::{error::GlobError, fs::FileSystem, predicates::FileType};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    vec,
+};
+
+/// An entry found by [`Walker`]
+pub(crate) struct WalkEntry {
+    /// Path of the entry, below the path the walk started from
+    pub path: PathBuf,
+    /// Depth below the start of the walk, which is at depth 0
+    pub depth: usize,
+    /// Type of the entry, or of its target when the symlink was followed
+    pub file_type: FileType,
+    /// Whether the entry itself is a symlink
+    pub is_symlink: bool,
+}
+
+impl WalkEntry {
+    /// Checks whether the entry is, or leads to, a directory
+    pub(crate) fn is_dir(&self) -> bool {
+        self.file_type == FileType::Dir
+    }
+
+    /// Returns the last component of the entry's path
+    pub(crate) fn file_name(&self) -> Option<&OsStr> {
+        self.path.file_name()
+    }
+}
+
+/// A directory on the path from the start to the current entry
+struct OpenDir {
+    path: PathBuf,
+    depth: usize,
+    /// Canonical path, kept when following symlinks to detect cycles
+    canonical: Option<PathBuf>,
+    /// Entries still to visit, `None` until the directory is read
+    children: Option<vec::IntoIter<(PathBuf, FileType)>>,
+}
+
+/// Depth-first traversal whose I/O all goes through a [`FileSystem`]
+///
+/// Entries are yielded before their contents, in the order the filesystem
+/// lists them, and a directory is only read once its entry was yielded.
+/// Entries rejected by the filter are neither yielded nor descended into;
+/// entries shallower than the minimum depth are descended into without
+/// being filtered. The start path is always followed if it is a symlink.
+///
+/// A followed symlink that leads back to a directory on the current path
+/// is reported as `GlobError::SymlinkCycle` instead of being yielded.
+/// Directories reached twice by other routes, such as two links to the
+/// same sibling, are walked each time.
+pub(crate) struct Walker<'a, F> {
+    fs: &'a dyn FileSystem,
+    filter: F,
+    follow_links: bool,
+    min_depth: usize,
+    max_depth: usize,
+    /// Device of the start, when the walk must not cross filesystems
+    start_dev: Option<u64>,
+    /// The start path until its entry has been yielded
+    start: Option<PathBuf>,
+    /// Directories being read, innermost last
+    stack: Vec<OpenDir>,
+}
+
+impl<'a, F> Walker<'a, F>
+where
+    F: FnMut(&WalkEntry) -> bool,
+{
+    /// Creates a walk over `start`, keeping the entries `filter` accepts
+    pub(crate) fn new(fs: &'a dyn FileSystem, start: PathBuf, filter: F) -> Self {
+        Self {
+            fs,
+            filter,
+            follow_links: false,
+            min_depth: 0,
+            max_depth: usize::MAX,
+            start_dev: None,
+            start: Some(start),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Follows symlinks to their targets, descending into linked directories
+    pub(crate) fn follow_links(mut self, yes: bool) -> Self {
+        self.follow_links = yes;
+        self
+    }
+
+    /// Stays on the start's filesystem: directories on another one are
+    /// yielded but not descended into (detected on Unix only)
+    pub(crate) fn same_file_system(mut self, yes: bool) -> Self {
+        self.start_dev = match &self.start {
+            Some(start) if yes => device_id(self.fs, start),
+            _ => None,
+        };
+        self
+    }
+
+    /// Yields nothing shallower than `depth`
+    pub(crate) fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Yields nothing deeper than `depth`
+    pub(crate) fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Builds the entry for the start path, following it if it is a symlink
+    fn start_entry(&self, path: PathBuf) -> Result<WalkEntry, GlobError> {
+        let own = self.fs.symlink_metadata(&path)?.file_type;
+        let file_type = if own == FileType::Symlink {
+            self.fs.metadata(&path)?.file_type
+        } else {
+            own
+        };
+        Ok(WalkEntry {
+            path,
+            depth: 0,
+            file_type,
+            is_symlink: own == FileType::Symlink,
+        })
+    }
+
+    /// Builds the entry for a listed path, following it if requested
+    ///
+    /// # Errors
+    ///
+    /// Returns `GlobError::Io` if a followed symlink can't be resolved, and
+    /// `GlobError::SymlinkCycle` if it leads to a directory being read
+    fn child_entry(
+        &self,
+        path: PathBuf,
+        own: FileType,
+        depth: usize,
+    ) -> Result<WalkEntry, GlobError> {
+        let is_symlink = own == FileType::Symlink;
+        let file_type = if is_symlink && self.follow_links {
+            let target = self.fs.metadata(&path)?.file_type;
+            // Only a link to a directory can lead back into the walk
+            if target == FileType::Dir {
+                let canonical = self.fs.canonicalize(&path)?;
+                if self
+                    .stack
+                    .iter()
+                    .any(|dir| dir.canonical.as_deref() == Some(canonical.as_path()))
+                {
+                    return Err(GlobError::SymlinkCycle);
+                }
+            }
+            target
+        } else {
+            own
+        };
+        Ok(WalkEntry {
+            path,
+            depth,
+            file_type,
+            is_symlink,
+        })
+    }
+
+    /// Applies the filter and depth range to an entry, queueing the
+    /// directories to descend into
+    ///
+    /// # Returns
+    ///
+    /// The entry if it should be yielded
+    fn admit(&mut self, entry: WalkEntry) -> Option<WalkEntry> {
+        if entry.depth >= self.min_depth && !(self.filter)(&entry) {
+            return None;
+        }
+        let descend = entry.is_dir()
+            && entry.depth < self.max_depth
+            && (entry.depth == 0
+                || self.start_dev.is_none()
+                || device_id(self.fs, &entry.path) == self.start_dev);
+        if descend {
+            // A directory that isn't a symlink extends its parent's path
+            let canonical = if !self.follow_links {
+                None
+            } else if entry.is_symlink || entry.depth == 0 {
+                self.fs.canonicalize(&entry.path).ok()
+            } else {
+                self.stack
+                    .last()
+                    .and_then(|parent| parent.canonical.as_ref())
+                    .zip(entry.file_name())
+                    .map(|(parent, name)| parent.join(name))
+            };
+            self.stack.push(OpenDir {
+                path: entry.path.clone(),
+                depth: entry.depth,
+                canonical,
+                children: None,
+            });
+        }
+        (entry.depth >= self.min_depth).then_some(entry)
+    }
+}
+
+impl<F> Iterator for Walker<'_, F>
+where
+    F: FnMut(&WalkEntry) -> bool,
+{
+    type Item = Result<WalkEntry, GlobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(start) = self.start.take() {
+            match self.start_entry(start) {
+                Ok(entry) => {
+                    if let Some(entry) = self.admit(entry) {
+                        return Some(Ok(entry));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        loop {
+            let dir = self.stack.last_mut()?;
+            let depth = dir.depth + 1;
+            let children = match &mut dir.children {
+                Some(children) => children,
+                None => match self.fs.read_dir_with_types(&dir.path) {
+                    Ok(listed) => dir.children.insert(listed.into_iter()),
+                    Err(e) => {
+                        self.stack.pop();
+                        return Some(Err(GlobError::Io(e)));
+                    }
+                },
+            };
+            let Some((path, own)) = children.next() else {
+                self.stack.pop();
+                continue;
+            };
+            match self.child_entry(path, own, depth) {
+                Ok(entry) => {
+                    if let Some(entry) = self.admit(entry) {
+                        return Some(Ok(entry));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Returns the device id of the filesystem containing `path`
+#[cfg(unix)]
+pub(crate) fn device_id(fs: &dyn FileSystem, path: &Path) -> Option<u64> {
+    fs.metadata(path).ok().map(|m| m.dev)
+}
+
+/// Filesystem boundaries are only detected on Unix
+#[cfg(not(unix))]
+pub(crate) fn device_id(_fs: &dyn FileSystem, _path: &Path) -> Option<u64> {
+    None
+}
@@ -3,7 +3,10 @@
 ///
 /// On Windows, paths longer than MAX_PATH need the "\\?\" prefix
 /// to avoid path length limitations. This function adds the prefix
-/// if it's not already present.
+/// if it's not already present. Verbatim paths skip all normalization,
+/// so `.`, `..` and `/` are resolved first. UNC paths (`\\server\share`)
+/// become `\\?\UNC\server\share`. Relative paths cannot carry the
+/// prefix and are returned unchanged, as are device paths (`\\.\`).
 ///
 /// # Arguments
 ///
@@ -14,18 +17,39 @@
 /// The path with the long path prefix if needed
 #[cfg(windows)]
 pub fn ensure_long_path_prefix(p: &std::path::Path) -> std::path::PathBuf {
-    use std::path::PathBuf;
-    let s = p.to_string_lossy();
+    use std::{
+        ffi::OsString,
+        path::{Component, PathBuf, Prefix},
+    };
 
-    // Return unchanged if already has prefix
-    if s.starts_with("\\\\?\\") {
+    if !p.is_absolute() {
         return p.to_path_buf();
     }
-
-    // Add the long path prefix
-    let mut pref = String::from("\\\\?\\");
-    pref.push_str(&s);
-    PathBuf::from(pref)
+    let full = std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf());
+    let mut components = full.components();
+    let Some(Component::Prefix(prefix)) = components.next() else {
+        return full;
+    };
+    match prefix.kind() {
+        Prefix::Disk(_) => {
+            let mut out = OsString::from("\\\\?\\");
+            out.push(full.as_os_str());
+            PathBuf::from(out)
+        }
+        Prefix::UNC(server, share) => {
+            let mut out = OsString::from("\\\\?\\UNC\\");
+            out.push(server);
+            out.push("\\");
+            out.push(share);
+            let mut out = PathBuf::from(out);
+            for part in components.filter(|c| matches!(c, Component::Normal(_))) {
+                out.push(part);
+            }
+            out
+        }
+        // Already verbatim, or a device path the prefix doesn't apply to
+        _ => full,
+    }
 }
 
 /// No-op implementation for non-Windows platforms
@@ -41,3 +65,174 @@ pub fn ensure_long_path_prefix(p: &std::path::Path) -> std::path::PathBuf {
 pub fn ensure_long_path_prefix(p: &std::path::Path) -> std::path::PathBuf {
     p.to_path_buf()
 }
+
+/// Removes the long path prefix added by [`ensure_long_path_prefix`]
+///
+/// Patterns are written against ordinary paths, so traversal strips the
+/// prefix again before matching. `\\?\UNC\server\share` becomes
+/// `\\server\share`; other paths are borrowed unchanged. Paths that
+/// aren't valid Unicode, such as ones holding a lone surrogate, are
+/// rebuilt from their components so lossy matching sees them unprefixed.
+///
+/// # Arguments
+///
+/// * `p` - The path to process
+///
+/// # Returns
+///
+/// The path without the long path prefix
+#[cfg(windows)]
+pub fn strip_long_path_prefix(p: &std::path::Path) -> std::borrow::Cow<'_, std::path::Path> {
+    use std::{
+        borrow::Cow,
+        ffi::OsString,
+        path::{Component, PathBuf, Prefix},
+    };
+    let Some(s) = p.to_str() else {
+        let mut components = p.components();
+        let prefix = match components.next() {
+            Some(Component::Prefix(prefix)) => match prefix.kind() {
+                Prefix::VerbatimDisk(letter) => OsString::from(format!("{}:", letter as char)),
+                Prefix::VerbatimUNC(server, share) => {
+                    let mut out = OsString::from("\\\\");
+                    out.push(server);
+                    out.push("\\");
+                    out.push(share);
+                    out
+                }
+                _ => return Cow::Borrowed(p),
+            },
+            _ => return Cow::Borrowed(p),
+        };
+        let mut stripped = PathBuf::from(prefix);
+        stripped.extend(components);
+        return Cow::Owned(stripped);
+    };
+    if let Some(unc) = s.strip_prefix("\\\\?\\UNC\\") {
+        Cow::Owned(PathBuf::from(format!("\\\\{}", unc)))
+    } else if let Some(rest) = s.strip_prefix("\\\\?\\") {
+        Cow::Borrowed(std::path::Path::new(rest))
+    } else {
+        Cow::Borrowed(p)
+    }
+}
+
+/// No-op implementation for non-Windows platforms
+///
+/// # Arguments
+///
+/// * `p` - The path to process
+///
+/// # Returns
+///
+/// The unchanged path
+#[cfg(not(windows))]
+pub fn strip_long_path_prefix(p: &std::path::Path) -> std::borrow::Cow<'_, std::path::Path> {
+    std::borrow::Cow::Borrowed(p)
+}
+
+/// Uppercases a leading drive letter, so `c:\data` reads as `C:\data`
+///
+/// Drive letters are case-insensitive on Windows but compared as text by
+/// patterns, so candidates and patterns both go through this.
+///
+/// # Arguments
+///
+/// * `s` - The pattern or path string to normalize
+///
+/// # Returns
+///
+/// The string with an uppercase drive letter, borrowed if already so
+#[cfg(windows)]
+pub fn normalize_drive_letter(s: &str) -> std::borrow::Cow<'_, str> {
+    match s.as_bytes() {
+        [letter, b':', ..] if letter.is_ascii_lowercase() => std::borrow::Cow::Owned(format!(
+            "{}{}",
+            letter.to_ascii_uppercase() as char,
+            &s[1..]
+        )),
+        _ => std::borrow::Cow::Borrowed(s),
+    }
+}
+
+/// No-op implementation for non-Windows platforms
+///
+/// # Arguments
+///
+/// * `s` - The pattern or path string to normalize
+///
+/// # Returns
+///
+/// The unchanged string
+#[cfg(not(windows))]
+pub fn normalize_drive_letter(s: &str) -> std::borrow::Cow<'_, str> {
+    std::borrow::Cow::Borrowed(s)
+}
+
+/// Uppercases the drive letter of a path, verbatim (`\\?\c:`) or not
+///
+/// # Arguments
+///
+/// * `p` - The path to normalize
+///
+/// # Returns
+///
+/// The path with an uppercase drive letter, borrowed if already so
+#[cfg(windows)]
+pub fn normalize_drive_letter_path(p: &std::path::Path) -> std::borrow::Cow<'_, std::path::Path> {
+    use std::{
+        borrow::Cow,
+        path::{Component, PathBuf, Prefix},
+    };
+    let mut components = p.components();
+    let prefix = match components.next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(letter) if letter.is_ascii_lowercase() => {
+                format!("{}:", letter.to_ascii_uppercase() as char)
+            }
+            Prefix::VerbatimDisk(letter) if letter.is_ascii_lowercase() => {
+                format!(r"\\?\{}:", letter.to_ascii_uppercase() as char)
+            }
+            _ => return Cow::Borrowed(p),
+        },
+        _ => return Cow::Borrowed(p),
+    };
+    let mut normalized = PathBuf::from(prefix);
+    normalized.extend(components);
+    Cow::Owned(normalized)
+}
+
+/// No-op implementation for non-Windows platforms
+///
+/// # Arguments
+///
+/// * `p` - The path to normalize
+///
+/// # Returns
+///
+/// The unchanged path
+#[cfg(not(windows))]
+pub fn normalize_drive_letter_path(p: &std::path::Path) -> std::borrow::Cow<'_, std::path::Path> {
+    std::borrow::Cow::Borrowed(p)
+}
+
+/// Rewrites `\` path separators to `/`
+///
+/// Patterns and candidate paths are compared as strings, so both sides
+/// must agree on the separator. This borrows the input when it contains
+/// no backslashes.
+///
+/// # Arguments
+///
+/// * `s` - The pattern or path string to normalize
+///
+/// # Returns
+///
+/// The string with every `\` replaced by `/`
+pub fn normalize_separators(s: &str) -> std::borrow::Cow<'_, str> {
+    if s.contains('\\') {
+        std::borrow::Cow::Owned(s.replace('\\', "/"))
+    } else {
+        std::borrow::Cow::Borrowed(s)
+    }
+}
@@ -0,0 +1,650 @@
+// tests/async_glob.rs
+#![cfg(feature = "async")]
+
+mod common;
+
+use futures::StreamExt;
+use patternhunt::{
+    ErrorPolicy, GlobError, GlobOptionsBuilder, OnNonUtf8, PatternHunt, PredicatesBuilder,
+    TraversalOrder,
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_stream_reports_timeout() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .timeout(Duration::from_nanos(500))
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+
+    assert!(items
+        .iter()
+        .any(|item| matches!(item, Err(GlobError::Timeout))));
+    assert!(matches!(items.last(), Some(Err(GlobError::Timeout))));
+}
+
+#[tokio::test]
+async fn test_stream_max_total_bytes() {
+    let dir = common::fixture(&[("a.log", "12345"), ("b.log", "12345"), ("c.log", "12345")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .max_total_bytes(12)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.log"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.is_ok()));
+}
+
+#[tokio::test]
+async fn test_stream_without_timeout_completes() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .timeout(Duration::from_secs(60))
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.is_ok()));
+}
+
+#[tokio::test]
+async fn test_stream_permit_timeout_never_drops_entries() {
+    let dir = common::fixture(&[
+        ("a.txt", "a"),
+        ("b.txt", "b"),
+        ("sub/c.txt", "c"),
+        ("notes.md", "n"),
+        ("sub/d.rs", "d"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .max_inflight(1)
+        .permit_timeout(Duration::from_millis(50))
+        .build();
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert_eq!(items.len(), 3);
+    assert!(items.iter().all(|item| item.is_ok()));
+
+    // No permit is ever free, so every matching entry is reported rather
+    // than skipped; entries no pattern matches never wait for one
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .max_inflight(0)
+        .permit_timeout(Duration::from_millis(10))
+        .build();
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert_eq!(items.len(), 3);
+    assert!(items
+        .iter()
+        .all(|item| matches!(item, Err(GlobError::PermitTimeout(_)))));
+}
+
+#[tokio::test]
+async fn test_stream_skip_hidden() {
+    let dir = common::fixture(&[("visible.txt", "v"), (".hidden", "h"), (".git/config", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .skip_hidden(true)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*"], &["."], opts).unwrap();
+    let paths: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    assert_eq!(common::relative(dir.path(), &paths), vec!["visible.txt"]);
+}
+
+#[tokio::test]
+async fn test_stream_same_filesystem_descends_into_subdirs() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/deeper/b.txt", "b")]);
+    for cross in [false, true] {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .cross_filesystems(cross)
+            .build();
+        let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+        let paths: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(
+            common::relative(dir.path(), &paths),
+            vec!["a.txt", "sub/deeper/b.txt"]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_match_directories_agrees_with_sync() {
+    let dir = common::fixture(&[
+        ("node_modules/x.js", "x"),
+        ("pkg/node_modules/y.js", "y"),
+        ("pkg/lib.js", "l"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .match_directories(true)
+        .build();
+
+    let sync = PatternHunt::sync(&["**/node_modules"], &["."], opts.clone()).unwrap();
+    let stream = PatternHunt::stream(&["**/node_modules"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        vec!["node_modules", "pkg/node_modules"]
+    );
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        common::relative(dir.path(), &sync)
+    );
+}
+
+#[tokio::test]
+async fn test_stream_max_depth_agrees_with_sync() {
+    let dir = common::fixture(&[("a.txt", "a"), ("x/b.txt", "b"), ("x/y/c.txt", "c")]);
+    let expected: [&[&str]; 3] = [&[], &["a.txt"], &["a.txt", "x/b.txt"]];
+
+    for (max_depth, expected) in expected.iter().enumerate() {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .max_depth(max_depth)
+            .build();
+
+        let sync = PatternHunt::sync(&["**/*.txt"], &["."], opts.clone()).unwrap();
+        let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+        let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+
+        assert_eq!(common::relative(dir.path(), &streamed), *expected);
+        assert_eq!(
+            common::relative(dir.path(), &streamed),
+            common::relative(dir.path(), &sync),
+            "max_depth({max_depth})"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_min_depth() {
+    let dir = common::fixture(&[("a.txt", "a"), ("x/b.txt", "b"), ("x/y/c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .min_depth(3)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    assert_eq!(common::relative(dir.path(), &streamed), vec!["x/y/c.txt"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stream_non_utf8_error_and_lossy() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = common::fixture(&[("ok.txt", "ok")]);
+    let bad = dir.path().join(OsStr::from_bytes(b"bad\xffname.txt"));
+    std::fs::write(&bad, "bad").unwrap();
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .non_utf8(OnNonUtf8::Error)
+        .build();
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let items: Vec<_> = stream.collect().await;
+    assert!(items
+        .iter()
+        .any(|r| matches!(r, Err(GlobError::NonUtf8Path(p)) if *p == bad)));
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .non_utf8(OnNonUtf8::Lossy)
+        .build();
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    assert_eq!(streamed.len(), 2);
+    assert!(streamed.contains(&bad));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stream_symlink_predicate() {
+    use patternhunt::predicates::FileType;
+
+    let dir = common::fixture(&[("target.txt", "t")]);
+    std::os::unix::fs::symlink(dir.path().join("target.txt"), dir.path().join("link.txt")).unwrap();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(
+            PredicatesBuilder::new()
+                .file_type(FileType::Symlink)
+                .build(),
+        )
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    assert_eq!(streamed, vec![dir.path().join("link.txt")]);
+}
+
+#[tokio::test]
+async fn test_stream_inline_and_blocking_matching_agree() {
+    let dir = common::fixture(&[("a.rs", "a"), ("b.txt", "b"), ("sub/c.rs", "c")]);
+    let run = |opts| async move {
+        let stream = PatternHunt::stream(&["**/*.rs"], &["."], opts).unwrap();
+        let mut found: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        found.sort();
+        found
+    };
+
+    // Without predicates the patterns are matched on the executor
+    let inline = run(GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build())
+    .await;
+    // A stat is needed here, so entries go through the blocking pool
+    let blocking = run(GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(patternhunt::PredicatesBuilder::new().min_size(1).build())
+        .build())
+    .await;
+    assert_eq!(inline.len(), 2);
+    assert_eq!(inline, blocking);
+}
+
+#[tokio::test]
+async fn test_async_collect_matches_sync() {
+    let dir = common::fixture(&[
+        ("a.txt", "a"),
+        ("b.md", "b"),
+        ("sub/c.txt", "c"),
+        ("sub/deep/d.txt", "d"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let sync = PatternHunt::sync(&["**/*.txt"], &["."], opts.clone()).unwrap();
+    let collected = PatternHunt::async_collect(&["**/*.txt"], &["."], opts)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        common::relative(dir.path(), &collected),
+        common::relative(dir.path(), &sync)
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_async_collect_error_policy() {
+    let dir = common::fixture(&[("a.txt", "a")]);
+    // Stat'ing the dangling link for the predicate fails with NotFound
+    std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join("ghost.txt")).unwrap();
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(PredicatesBuilder::new().follow_symlinks(true).build())
+            .on_error(policy)
+            .build()
+    };
+
+    let err = PatternHunt::async_collect(&["**/*.txt"], &["."], opts(ErrorPolicy::Abort))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, GlobError::Io(_)));
+
+    let results = PatternHunt::async_collect(&["**/*.txt"], &["."], opts(ErrorPolicy::Skip))
+        .await
+        .unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["a.txt"]);
+
+    let (results, errors) =
+        PatternHunt::async_collect_with_errors(&["**/*.txt"], &["."], opts(ErrorPolicy::Collect))
+            .await
+            .unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["a.txt"]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[tokio::test]
+async fn test_async_collect_missing_root_is_an_error() {
+    let dir = common::fixture(&[("a.txt", "a")]);
+    let missing = dir.path().join("missing");
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .on_error(policy)
+            .build()
+    };
+
+    // As in the sync facades, matches under the other roots aren't returned
+    for policy in [ErrorPolicy::Abort, ErrorPolicy::Skip, ErrorPolicy::Collect] {
+        let err = PatternHunt::sync(&["**/*.txt"], &[".", "missing"], opts(policy)).unwrap_err();
+        assert!(matches!(err, GlobError::RootNotFound(ref root) if *root == missing));
+
+        let err = PatternHunt::async_collect(&["**/*.txt"], &[".", "missing"], opts(policy))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GlobError::RootNotFound(ref root) if *root == missing));
+    }
+}
+
+#[tokio::test]
+async fn test_stream_trailing_slash_matches_directories_only() {
+    let dir = common::fixture(&[("build/out.o", "o"), ("src/build", "file named build")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .match_directories(true)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/build/"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    assert_eq!(common::relative(dir.path(), &streamed), vec!["build"]);
+}
+
+#[tokio::test]
+async fn test_stream_breadth_first_yields_shallow_matches_first() {
+    let dir = common::fixture(&[
+        ("a/b/deep1.txt", "d"),
+        ("c/d/deep2.txt", "d"),
+        ("a/mid.txt", "m"),
+        ("top1.txt", "t"),
+        ("top2.txt", "t"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .walk_order(TraversalOrder::BreadthFirst)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+    let depths: Vec<usize> = streamed
+        .iter()
+        .map(|p| p.strip_prefix(dir.path()).unwrap().components().count())
+        .collect();
+
+    assert_eq!(depths.len(), 5);
+    assert!(depths.windows(2).all(|w| w[0] <= w[1]), "{:?}", streamed);
+    assert_eq!(depths.first(), Some(&1));
+    assert_eq!(depths.last(), Some(&3));
+}
+
+#[tokio::test]
+async fn test_stream_compiled_reuses_patterns() {
+    let dir = common::fixture(&[("a.rs", "a"), ("src/b.rs", "b"), ("c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+    let pats = patternhunt::Patterns::compile_one("**/*.rs", &opts).unwrap();
+
+    for _ in 0..3 {
+        let stream = PatternHunt::stream_compiled(&pats, &["."], opts.clone());
+        let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(
+            common::relative(dir.path(), &streamed),
+            vec!["a.rs", "src/b.rs"]
+        );
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stream_max_symlink_depth_agrees_with_sync() {
+    let (dir, _outside) = common::symlink_chain();
+
+    for hops in [0, 2, 4] {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .follow_symlinks(true)
+            .max_symlink_depth(hops)
+            .build();
+
+        let sync = PatternHunt::sync(&["**/*.txt"], &["."], opts.clone()).unwrap();
+        let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+        let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(
+            common::relative(dir.path(), &streamed),
+            common::relative(dir.path(), &sync),
+            "hops = {}",
+            hops
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_count_async_matches_sync_len() {
+    let dir = common::fixture(&[("a.rs", "a"), ("src/b.rs", "b"), ("src/c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let count = PatternHunt::count_async(&["**/*.rs"], &["."], opts.clone())
+        .await
+        .unwrap();
+    let paths = PatternHunt::sync(&["**/*.rs"], &["."], opts).unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(count, paths.len());
+}
+
+#[tokio::test]
+async fn test_stream_content_prefix_agrees_with_sync() {
+    let dir = common::fixture(&[
+        ("doc.pdf", "%PDF-1.7 body"),
+        ("misnamed.txt", "%PDF-1.4"),
+        ("short.pdf", "%PD"),
+        ("other.pdf", "not a pdf"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(
+            patternhunt::PredicatesBuilder::new()
+                .content_prefix(*b"%PDF-")
+                .build(),
+        )
+        .build();
+
+    let sync = PatternHunt::sync(&["**/*"], &["."], opts.clone()).unwrap();
+    let stream = PatternHunt::stream(&["**/*"], &["."], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        vec!["doc.pdf", "misnamed.txt"]
+    );
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        common::relative(dir.path(), &sync)
+    );
+}
+
+#[tokio::test]
+async fn test_stream_walks_every_root_relative_to_it() {
+    let dir = common::fixture(&[
+        ("a/x.rs", "x"),
+        ("a/sub/y.rs", "y"),
+        ("b/sub/w.txt", "w"),
+        ("c/sub/skipped.rs", "s"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .patterns_relative_to_root(true)
+        .build();
+
+    let sync = PatternHunt::sync(&["sub/*"], &["a", "b"], opts.clone()).unwrap();
+    let stream = PatternHunt::stream(&["sub/*"], &["a", "b"], opts).unwrap();
+    let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        vec!["a/sub/y.rs", "b/sub/w.txt"]
+    );
+    assert_eq!(
+        common::relative(dir.path(), &streamed),
+        common::relative(dir.path(), &sync)
+    );
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_stream_long_paths_on_windows() {
+    let segment = "d".repeat(50);
+    let deep = format!("{}/deep.txt", [segment.as_str(); 6].join("/"));
+    let dir = common::fixture(&[(deep.as_str(), "x")]);
+    assert!(dir.path().join(&deep).as_os_str().len() > 260);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let stream = PatternHunt::stream(&["**/deep.txt"], &["."], opts).unwrap();
+    let found: Vec<_> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(common::relative(dir.path(), &found), vec![deep]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_stream_match_resolved_symlink_target() {
+    use std::os::unix::fs::symlink;
+
+    let dir = common::fixture(&[("data/real.csv", "a,b")]);
+    symlink(
+        dir.path().join("data/real.csv"),
+        dir.path().join("alias.txt"),
+    )
+    .unwrap();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .match_resolved_path(true)
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.csv"], &["."], opts).unwrap();
+    let found: Vec<_> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(
+        common::relative(dir.path(), &found),
+        vec!["alias.txt", "data/real.csv"]
+    );
+}
+
+#[tokio::test]
+async fn test_stream_include_root() {
+    let dir = common::fixture(&[("sub/a.txt", "a")]);
+    let opts = |include_root| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .match_directories(true)
+            .patterns_relative_to_root(true)
+            .include_root(include_root)
+            .build()
+    };
+
+    let stream = PatternHunt::stream(&["."], &["."], opts(true)).unwrap();
+    let found: Vec<_> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(found, vec![dir.path().to_path_buf()]);
+
+    let stream = PatternHunt::stream(&["."], &["."], opts(false)).unwrap();
+    assert_eq!(stream.count().await, 0);
+}
+
+#[tokio::test]
+async fn test_stream_cancel_mid_stream() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let names: Vec<String> = (0..50).map(|i| format!("f{}.txt", i)).collect();
+    let files: Vec<(&str, &str)> = names.iter().map(|n| (n.as_str(), "x")).collect();
+    let dir = common::fixture(&files);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .cancel(cancel.clone())
+        .build();
+
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    futures::pin_mut!(stream);
+    assert!(stream.next().await.unwrap().is_ok());
+
+    cancel.store(true, Ordering::Relaxed);
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(GlobError::Cancelled))
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_stream_bounded_open_dirs_visits_everything() {
+    let dir = common::fixture(&[
+        ("top.txt", ""),
+        ("a/one.txt", ""),
+        ("a/b/two.txt", ""),
+        ("a/b/c/d/three.txt", ""),
+        ("a/e/four.txt", ""),
+        ("f/g/five.txt", ""),
+        ("f/six.txt", ""),
+    ]);
+    let opts = |limit| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .max_open_dirs(limit)
+            .build()
+    };
+
+    let unbounded = PatternHunt::async_collect(
+        &["**/*.txt"],
+        &["."],
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .build(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(unbounded.len(), 7);
+
+    for limit in [0, 1, 2] {
+        let bounded = PatternHunt::async_collect(&["**/*.txt"], &["."], opts(limit))
+            .await
+            .unwrap();
+        assert_eq!(
+            common::relative(dir.path(), &bounded),
+            common::relative(dir.path(), &unbounded),
+            "max_open_dirs({limit})"
+        );
+
+        let sync = PatternHunt::sync(&["**/*.txt"], &["."], opts(limit)).unwrap();
+        assert_eq!(
+            common::relative(dir.path(), &sync),
+            common::relative(dir.path(), &unbounded)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_literal_prefix_pruning_matches_sync() {
+    let dir = common::fixture(&[
+        ("src/a/mod.rs", ""),
+        ("src/mod.rs", ""),
+        ("lib/mod.rs", ""),
+        ("docs/src/mod.rs", ""),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .patterns_relative_to_root(true)
+        .build();
+
+    for pattern in ["src/**/mod.rs", "{lib,docs}/**/*.rs", "**/src/*.rs"] {
+        let expected = PatternHunt::sync(&[pattern], &["."], opts.clone()).unwrap();
+        let stream = PatternHunt::stream(&[pattern], &["."], opts.clone()).unwrap();
+        let streamed: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        assert_eq!(
+            common::relative(dir.path(), &streamed),
+            common::relative(dir.path(), &expected),
+            "{pattern}"
+        );
+    }
+}
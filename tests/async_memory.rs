@@ -0,0 +1,69 @@
+// tests/async_memory.rs
+#![cfg(feature = "async")]
+
+mod common;
+
+use futures::StreamExt;
+use patternhunt::{GlobOptionsBuilder, PatternHunt, TraversalOrder};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocator that tracks the peak number of live heap bytes
+struct PeakAlloc;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+/// Streams a wide tree and returns the match count and peak heap growth
+async fn stream_peak(root: &std::path::Path, order: TraversalOrder) -> (usize, usize) {
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(root.to_path_buf())
+        .walk_order(order)
+        .build();
+    let stream = PatternHunt::stream(&["**/*.txt"], &["."], opts).unwrap();
+    let baseline = LIVE.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let count = stream
+        .fold(0, |n, item| async move { n + usize::from(item.is_ok()) })
+        .await;
+    (count, PEAK.load(Ordering::Relaxed) - baseline)
+}
+
+// A single test, so no other test allocates while the peak is measured
+#[tokio::test]
+async fn test_depth_first_memory_independent_of_breadth() {
+    let names: Vec<String> = (0..4000).map(|i| format!("dir{:04}/f.txt", i)).collect();
+    let files: Vec<(&str, &str)> = names.iter().map(|n| (n.as_str(), "")).collect();
+    let dir = common::fixture(&files);
+
+    let (count, dfs_peak) = stream_peak(dir.path(), TraversalOrder::DepthFirst).await;
+    assert_eq!(count, 4000);
+    let (count, bfs_peak) = stream_peak(dir.path(), TraversalOrder::BreadthFirst).await;
+    assert_eq!(count, 4000);
+
+    // Breadth-first has to queue every sibling directory; depth-first
+    // holds one open directory per level
+    assert!(
+        dfs_peak * 4 < bfs_peak,
+        "depth-first peak {dfs_peak} vs breadth-first {bfs_peak}"
+    );
+}
@@ -0,0 +1,84 @@
+// tests/common/mod.rs
+#![allow(dead_code)]
+
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+/// Creates a temporary directory populated with the given files
+///
+/// Each entry is a relative path and its contents; parent directories
+/// are created as needed.
+pub fn fixture(files: &[(&str, &str)]) -> TempDir {
+    populate(tempfile::tempdir().unwrap(), files)
+}
+
+/// Like [`fixture`], but inside `base`
+pub fn fixture_in(base: &Path, files: &[(&str, &str)]) -> TempDir {
+    populate(tempfile::tempdir_in(base).unwrap(), files)
+}
+
+/// Like [`fixture`], but directly inside the current directory
+///
+/// The directory's relative path is just its file name.
+pub fn local_fixture(files: &[(&str, &str)]) -> TempDir {
+    let cwd = std::env::current_dir().unwrap();
+    let dir = tempfile::Builder::new()
+        .prefix("fixture-")
+        .tempdir_in(cwd)
+        .unwrap();
+    populate(dir, files)
+}
+
+/// Builds a root whose symlinks chain through four directories outside it
+///
+/// `root/a -> t1`, `t1/b -> t2`, `t2/c -> t3` and `t3/d -> t4`, with
+/// `top.txt` in the root and one `.txt` file in each `tN`. Returns the
+/// root and the directory holding the targets, which must be kept alive.
+#[cfg(unix)]
+pub fn symlink_chain() -> (TempDir, TempDir) {
+    use std::os::unix::fs::symlink;
+
+    let outside = fixture(&[
+        ("t1/one.txt", "1"),
+        ("t2/two.txt", "2"),
+        ("t3/three.txt", "3"),
+        ("t4/deep.txt", "4"),
+    ]);
+    let dir = fixture(&[("top.txt", "t")]);
+    symlink(outside.path().join("t1"), dir.path().join("a")).unwrap();
+    for (from, name, to) in [("t1", "b", "t2"), ("t2", "c", "t3"), ("t3", "d", "t4")] {
+        symlink(
+            outside.path().join(to),
+            outside.path().join(from).join(name),
+        )
+        .unwrap();
+    }
+    (dir, outside)
+}
+
+fn populate(dir: TempDir, files: &[(&str, &str)]) -> TempDir {
+    for (rel, contents) in files {
+        let path = dir.path().join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(&path, contents).unwrap();
+    }
+    dir
+}
+
+/// Returns the paths relative to `root`, sorted, with `/` separators
+pub fn relative<P: AsRef<Path>>(root: &Path, paths: &[P]) -> Vec<String> {
+    let mut out: Vec<String> = paths
+        .iter()
+        .map(|p| {
+            p.as_ref()
+                .strip_prefix(root)
+                .unwrap_or(p.as_ref())
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    out.sort();
+    out
+}
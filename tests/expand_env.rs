@@ -0,0 +1,55 @@
+// tests/expand_env.rs
+//! Kept alone in its own binary: it sets environment variables, which
+//! would race with any test running next to it.
+#![cfg(unix)]
+
+mod common;
+
+use patternhunt::{GlobError, GlobOptionsBuilder, OnUndefinedEnv, PatternHunt};
+
+#[test]
+fn test_expand_env_in_roots() {
+    let dir = common::fixture(&[("logs/a.log", "a"), ("logs/old/b.log", "b")]);
+    std::env::set_var("PATTERNHUNT_TEST_BASE", dir.path());
+    std::env::remove_var("PATTERNHUNT_TEST_UNSET");
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .expand_env(true)
+            .on_undefined_env(policy)
+            .build()
+    };
+
+    for root in [
+        "$PATTERNHUNT_TEST_BASE/logs",
+        "${PATTERNHUNT_TEST_BASE}/logs",
+    ] {
+        let results = PatternHunt::sync(&["**/*.log"], &[root], opts(OnUndefinedEnv::Error));
+        assert_eq!(
+            common::relative(dir.path(), &results.unwrap()),
+            vec!["logs/a.log", "logs/old/b.log"],
+            "{root}"
+        );
+    }
+
+    let err = PatternHunt::sync(
+        &["**/*.log"],
+        &["$PATTERNHUNT_TEST_UNSET/logs"],
+        opts(OnUndefinedEnv::Error),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, GlobError::UndefinedEnvVar(ref name) if name == "PATTERNHUNT_TEST_UNSET")
+    );
+
+    // An empty substitution leaves a root relative to `root_dir`
+    let results = PatternHunt::sync(
+        &["**/*.log"],
+        &["${PATTERNHUNT_TEST_UNSET}logs/old"],
+        opts(OnUndefinedEnv::Empty),
+    );
+    assert_eq!(
+        common::relative(dir.path(), &results.unwrap()),
+        vec!["logs/old/b.log"]
+    );
+}
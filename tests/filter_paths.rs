@@ -0,0 +1,77 @@
+// tests/filter_paths.rs
+use patternhunt::{GlobOptions, GlobOptionsBuilder, PatternHunt, PredicatesBuilder};
+use std::path::PathBuf;
+
+fn listing() -> Vec<PathBuf> {
+    [
+        "README.md",
+        "src/lib.rs",
+        "src/main.rs",
+        "src/patterns/mod.rs",
+        "tests/sync_glob.rs",
+        "docs/guide.md",
+        "docs/index.html",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .collect()
+}
+
+#[test]
+fn test_filter_paths_glob() {
+    let results =
+        PatternHunt::filter_paths(&["src/**/*.rs"], listing(), GlobOptions::default()).unwrap();
+    assert_eq!(
+        results,
+        vec![
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("src/patterns/mod.rs"),
+        ]
+    );
+}
+
+#[test]
+fn test_filter_paths_extglob() {
+    let results =
+        PatternHunt::filter_paths(&["docs/*.@(md|html)"], listing(), GlobOptions::default())
+            .unwrap();
+    assert_eq!(
+        results,
+        vec![
+            PathBuf::from("docs/guide.md"),
+            PathBuf::from("docs/index.html")
+        ]
+    );
+}
+
+#[test]
+fn test_filter_paths_applies_path_predicates_only() {
+    // Size predicates need metadata and are ignored for in-memory paths
+    let opts = GlobOptionsBuilder::new()
+        .predicates(
+            PredicatesBuilder::new()
+                .path_glob("**/tests/**")
+                .min_size(1 << 30)
+                .build(),
+        )
+        .build();
+    let results = PatternHunt::filter_paths(&["**/*.rs"], listing(), opts).unwrap();
+    assert_eq!(results, vec![PathBuf::from("tests/sync_glob.rs")]);
+}
+
+#[test]
+fn test_filter_paths_relative_to_root_dir() {
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(PathBuf::from("/data"))
+        .patterns_relative_to_root(true)
+        .build();
+    let paths = vec![
+        PathBuf::from("/data/src/lib.rs"),
+        PathBuf::from("/data/docs/lib.rs"),
+        PathBuf::from("/elsewhere/src/lib.rs"),
+    ];
+
+    let kept = PatternHunt::filter_paths(&["src/*.rs"], paths, opts).unwrap();
+    assert_eq!(kept, vec![PathBuf::from("/data/src/lib.rs")]);
+}
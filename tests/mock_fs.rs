@@ -0,0 +1,351 @@
+// tests/mock_fs.rs
+use patternhunt::predicates::FileType;
+use patternhunt::{
+    ErrorPolicy, FileSystem, FsMetadata, GlobError, GlobOptionsBuilder, PatternHunt,
+    PredicatesBuilder, RealFileSystem,
+};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+mod common;
+
+#[derive(Clone)]
+enum Node {
+    Dir,
+    DeniedDir,
+    File(u64),
+    /// Listed by its directory, but removed before it could be stat'ed
+    Vanished,
+    /// A directory whose canonical path is another directory's
+    Alias(&'static str),
+}
+
+/// In-memory tree keyed by absolute path
+struct MockFs {
+    nodes: BTreeMap<PathBuf, Node>,
+}
+
+impl MockFs {
+    fn new(nodes: &[(&str, Node)]) -> Self {
+        let mut map = BTreeMap::new();
+        map.insert(PathBuf::from("/mem"), Node::Dir);
+        for (path, node) in nodes {
+            map.insert(PathBuf::from(path), node.clone());
+        }
+        Self { nodes: map }
+    }
+
+    fn node(&self, path: &Path) -> io::Result<&Node> {
+        self.nodes
+            .get(path)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+impl FileSystem for MockFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        match self.node(path)? {
+            Node::Dir | Node::Alias(_) => Ok(self
+                .nodes
+                .keys()
+                .filter(|p| p.parent() == Some(path))
+                .cloned()
+                .collect()),
+            Node::DeniedDir => Err(io::Error::from(io::ErrorKind::PermissionDenied)),
+            Node::File(_) | Node::Vanished => Err(io::Error::other("not a directory")),
+        }
+    }
+
+    fn read_dir_with_types(&self, path: &Path) -> io::Result<Vec<(PathBuf, FileType)>> {
+        let types = |p: PathBuf| {
+            let file_type = match self.nodes[&p] {
+                Node::Dir | Node::DeniedDir | Node::Alias(_) => FileType::Dir,
+                Node::File(_) | Node::Vanished => FileType::File,
+            };
+            (p, file_type)
+        };
+        Ok(self.read_dir(path)?.into_iter().map(types).collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        let (file_type, len) = match self.node(path)? {
+            Node::Dir | Node::DeniedDir | Node::Alias(_) => (FileType::Dir, 0),
+            Node::File(len) => (FileType::File, *len),
+            Node::Vanished => return Err(io::Error::from(io::ErrorKind::NotFound)),
+        };
+        Ok(FsMetadata {
+            file_type,
+            len,
+            modified: None,
+            created: None,
+            #[cfg(unix)]
+            uid: 0,
+            #[cfg(unix)]
+            gid: 0,
+            #[cfg(unix)]
+            mode: 0o644,
+            #[cfg(unix)]
+            ino: 0,
+            #[cfg(unix)]
+            nlink: 1,
+            #[cfg(unix)]
+            dev: 0,
+        })
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.metadata(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.node(path)?;
+        for ancestor in path.ancestors() {
+            if let Some(Node::Alias(target)) = self.nodes.get(ancestor) {
+                let rest = path.strip_prefix(ancestor).unwrap();
+                return Ok(Path::new(target).join(rest));
+            }
+        }
+        Ok(path.to_path_buf())
+    }
+}
+
+fn sorted(mut paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths.sort();
+    paths
+}
+
+#[test]
+fn test_traversal_over_mock_fs() {
+    let fs = MockFs::new(&[
+        ("/mem/a.rs", Node::File(10)),
+        ("/mem/src", Node::Dir),
+        ("/mem/src/lib.rs", Node::File(2000)),
+        ("/mem/src/notes.md", Node::File(5)),
+    ]);
+    let opts = GlobOptionsBuilder::new().root_dir("/mem".into()).build();
+
+    let results = PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts, &fs).unwrap();
+    assert_eq!(
+        sorted(results),
+        vec![PathBuf::from("/mem/a.rs"), PathBuf::from("/mem/src/lib.rs")]
+    );
+}
+
+#[test]
+fn test_mock_fs_predicates_use_mock_metadata() {
+    let fs = MockFs::new(&[
+        ("/mem/small.rs", Node::File(10)),
+        ("/mem/big.rs", Node::File(2000)),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir("/mem".into())
+        .predicates(PredicatesBuilder::new().min_size(1000).build())
+        .build();
+
+    let results = PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts, &fs).unwrap();
+    assert_eq!(results, vec![PathBuf::from("/mem/big.rs")]);
+}
+
+#[test]
+fn test_mock_fs_permission_denied() {
+    let fs = MockFs::new(&[
+        ("/mem/a.rs", Node::File(1)),
+        ("/mem/locked", Node::DeniedDir),
+    ]);
+    let opts = GlobOptionsBuilder::new().root_dir("/mem".into()).build();
+
+    let err = PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts, &fs).unwrap_err();
+    assert!(matches!(err, GlobError::Io(e) if e.kind() == io::ErrorKind::PermissionDenied));
+}
+
+#[test]
+fn test_real_fs_matches_walkdir_traversal() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b"), ("sub/c.md", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let walked = PatternHunt::sync(&["**/*.txt"], &["."], opts.clone()).unwrap();
+    let via_fs = PatternHunt::sync_with_fs(&["**/*.txt"], &["."], opts, &RealFileSystem).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &via_fs),
+        common::relative(dir.path(), &walked)
+    );
+}
+
+#[test]
+fn test_mock_fs_permission_denied_skipped() {
+    let fs = MockFs::new(&[
+        ("/mem/a.rs", Node::File(1)),
+        ("/mem/locked", Node::DeniedDir),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir("/mem".into())
+        .on_error(ErrorPolicy::Skip)
+        .build();
+
+    let results = PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts, &fs).unwrap();
+    assert_eq!(results, vec![PathBuf::from("/mem/a.rs")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_real_fs_follows_link_to_walked_sibling() {
+    use std::os::unix::fs::symlink;
+
+    let dir = common::fixture(&[("a/x.txt", "x"), ("b/y.txt", "y")]);
+    symlink(dir.path().join("b"), dir.path().join("a/link_to_b")).unwrap();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .follow_symlinks(true)
+        .build();
+
+    let walked = PatternHunt::sync(&["**/*.txt"], &["."], opts.clone()).unwrap();
+    let via_fs = PatternHunt::sync_with_fs(&["**/*.txt"], &["."], opts, &RealFileSystem).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &via_fs),
+        vec!["a/link_to_b/y.txt", "a/x.txt", "b/y.txt"]
+    );
+    assert_eq!(
+        common::relative(dir.path(), &via_fs),
+        common::relative(dir.path(), &walked)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_real_fs_reports_link_to_ancestor_as_cycle() {
+    use std::os::unix::fs::symlink;
+
+    let dir = common::fixture(&[("a/x.txt", "x")]);
+    symlink(dir.path().join("a"), dir.path().join("a/back")).unwrap();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .follow_symlinks(true)
+        .build();
+
+    let err = PatternHunt::sync_with_fs(&["**/*.txt"], &["."], opts, &RealFileSystem).unwrap_err();
+    assert!(matches!(err, GlobError::SymlinkCycle));
+}
+
+#[test]
+fn test_mock_fs_byte_budget_follows_error_policy() {
+    let fs = MockFs::new(&[
+        ("/mem/a.rs", Node::File(10)),
+        ("/mem/gone.rs", Node::Vanished),
+    ]);
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir("/mem".into())
+            .max_total_bytes(1000)
+            .on_error(policy)
+            .build()
+    };
+
+    let err =
+        PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts(ErrorPolicy::Abort), &fs).unwrap_err();
+    assert!(matches!(err, GlobError::Io(e) if e.kind() == io::ErrorKind::NotFound));
+
+    let results =
+        PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts(ErrorPolicy::Skip), &fs).unwrap();
+    assert_eq!(results, vec![PathBuf::from("/mem/a.rs")]);
+}
+
+#[test]
+fn test_mock_fs_readable_predicate_needs_support() {
+    let fs = MockFs::new(&[("/mem/a.rs", Node::File(10))]);
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir("/mem".into())
+            .predicates(PredicatesBuilder::new().readable(true).build())
+            .on_error(policy)
+            .build()
+    };
+
+    // The default `is_readable` can't tell, which is an error, not a "no"
+    let err =
+        PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts(ErrorPolicy::Abort), &fs).unwrap_err();
+    assert!(matches!(err, GlobError::Io(e) if e.kind() == io::ErrorKind::Unsupported));
+}
+
+#[test]
+fn test_mock_fs_dedupes_by_mock_canonical_path() {
+    let fs = MockFs::new(&[
+        ("/mem/src", Node::Dir),
+        ("/mem/src/lib.rs", Node::File(1)),
+        ("/mem/mirror", Node::Alias("/mem/src")),
+        ("/mem/mirror/lib.rs", Node::File(1)),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir("/mem".into())
+        .dedupe_by_canonical(true)
+        .build();
+
+    let results = PatternHunt::sync_with_fs(&["**/*.rs"], &["src", "mirror"], opts, &fs).unwrap();
+    assert_eq!(results, vec![PathBuf::from("/mem/src/lib.rs")]);
+}
+
+/// Counts directory reads, each slowed enough for a stop to be noticed
+#[cfg(feature = "parallel")]
+struct SlowFs {
+    inner: MockFs,
+    reads: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(feature = "parallel")]
+impl FileSystem for SlowFs {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        self.inner.read_dir(path)
+    }
+
+    fn read_dir_with_types(&self, path: &Path) -> io::Result<Vec<(PathBuf, FileType)>> {
+        self.reads
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        self.inner.read_dir_with_types(path)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.inner.metadata(path)
+    }
+
+    fn symlink_metadata(&self, path: &Path) -> io::Result<FsMetadata> {
+        self.inner.symlink_metadata(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        self.inner.canonicalize(path)
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_walk_stops_reading_at_byte_budget() {
+    let paths: Vec<_> = (0..40)
+        .flat_map(|i| {
+            [
+                (format!("/mem/d{:02}", i), Node::Dir),
+                (format!("/mem/d{:02}/a", i), Node::Dir),
+                (format!("/mem/d{:02}/a/b", i), Node::Dir),
+                (format!("/mem/d{:02}/a/b/file.rs", i), Node::File(10)),
+            ]
+        })
+        .collect();
+    let nodes: Vec<_> = paths.iter().map(|(p, n)| (p.as_str(), n.clone())).collect();
+    let fs = SlowFs {
+        inner: MockFs::new(&nodes),
+        reads: Default::default(),
+    };
+    let opts = GlobOptionsBuilder::new()
+        .root_dir("/mem".into())
+        .threads(2)
+        .max_total_bytes(15)
+        .build();
+
+    let results = PatternHunt::sync_with_fs(&["**/*.rs"], &["."], opts, &fs).unwrap();
+    assert_eq!(results, vec![PathBuf::from("/mem/d00/a/b/file.rs")]);
+    // A full walk reads the root and three directories per subtree
+    let reads = fs.reads.load(std::sync::atomic::Ordering::Relaxed);
+    assert!(reads < 40, "read {} directories", reads);
+}
@@ -0,0 +1,40 @@
+// tests/ndjson.rs
+#![cfg(feature = "serde")]
+
+mod common;
+
+use patternhunt::{GlobOptionsBuilder, PatternHunt};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_sync_ndjson_entry_shape() {
+    let dir = common::fixture(&[("sub/data.txt", "hello")]);
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(dir.path().join("sub/data.txt"))
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let mut out = Vec::new();
+    let written = PatternHunt::sync_ndjson(&["**/*.txt"], &["."], opts, &mut out).unwrap();
+    assert_eq!(written, 1);
+
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.ends_with('\n'));
+    let value: serde_json::Value = serde_json::from_str(text.trim_end()).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "path": dir.path().join("sub/data.txt").to_string_lossy(),
+            "depth": 2,
+            "type": "file",
+            "size": 5,
+            "mtime": 1_700_000_000u64,
+        })
+    );
+}
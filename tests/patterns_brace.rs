@@ -35,3 +35,168 @@ fn test_brace_expansion_count_limit() {
     let result = brace::expand("{1..10000}");
     assert!(result.is_err());
 }
+
+#[test]
+fn test_brace_expansion_empty_middle_alternative() {
+    let v = brace::expand("x{a,,b}y").unwrap();
+    assert_eq!(v, vec!["xay", "xy", "xby"]);
+}
+
+#[test]
+fn test_brace_expansion_empty_leading_alternative() {
+    let v = brace::expand("x{,a}").unwrap();
+    assert_eq!(v, vec!["x", "xa"]);
+}
+
+#[test]
+fn test_brace_expansion_empty_trailing_alternative() {
+    let v = brace::expand("x{a,}").unwrap();
+    assert_eq!(v, vec!["xa", "x"]);
+}
+
+#[test]
+fn test_brace_expansion_escaped_braces_untouched() {
+    let v = brace::expand(r"file\{not,expanded\}.txt").unwrap();
+    assert_eq!(v, vec![r"file\{not,expanded\}.txt"]);
+}
+
+#[test]
+fn test_brace_expansion_escaped_comma() {
+    let v = brace::expand(r"{a\,b,c}").unwrap();
+    assert_eq!(v, vec![r"a\,b", "c"]);
+}
+
+#[test]
+fn test_brace_expansion_escaped_brace_inside_alternative() {
+    let v = brace::expand(r"x{\{,\}}y").unwrap();
+    assert_eq!(v, vec![r"x\{y", r"x\}y"]);
+}
+
+#[test]
+fn test_quantifier_braces_left_verbatim() {
+    assert_eq!(
+        brace::expand_with("a{2}.{txt,md}", true).unwrap(),
+        vec!["a{2}.txt", "a{2}.md"]
+    );
+    assert_eq!(brace::expand_with("x{1,}y", true).unwrap(), vec!["x{1,}y"]);
+    assert_eq!(
+        brace::expand_with("x{1,3}y", false).unwrap(),
+        vec!["x1y", "x3y"]
+    );
+}
+
+#[test]
+fn test_braces_in_classes_and_extglobs_left_to_compiler() {
+    assert_eq!(brace::expand("[{,}]x").unwrap(), vec!["[{,}]x"]);
+    assert_eq!(brace::expand("@(a|{b,c})").unwrap(), vec!["@(a|{b,c})"]);
+    assert_eq!(
+        brace::expand("{[a,b],@(c,d)}.{rs,md}").unwrap(),
+        vec!["[a,b].rs", "[a,b].md", "@(c,d).rs", "@(c,d).md"]
+    );
+}
+
+#[test]
+fn test_nested_braces_compile_consistently() {
+    use patternhunt::{GlobOptions, Patterns};
+
+    let opts = GlobOptions::default();
+    let extglob = Patterns::compile_one("src/@(lib|{main,mod}).rs", &opts).unwrap();
+    for path in ["src/lib.rs", "src/main.rs", "src/mod.rs"] {
+        assert!(extglob.matches_str(path), "{path}");
+    }
+    assert!(!extglob.matches_str("src/{main,mod}.rs"));
+    assert!(!extglob.matches_str("src/util.rs"));
+
+    // Inside a class, braces and commas are members rather than alternatives
+    let class = Patterns::compile_one("[{,}]x", &opts).unwrap();
+    for path in ["{x", ",x", "}x"] {
+        assert!(class.matches_str(path), "{path}");
+    }
+    assert!(!class.matches_str("x"));
+
+    let negated = Patterns::compile_one("!(a|{b,c}).txt", &opts).unwrap();
+    assert!(negated.matches_str("d.txt"));
+    assert!(!negated.matches_str("b.txt"));
+}
+
+#[test]
+fn test_unmatched_braces_are_literal() {
+    assert_eq!(brace::expand("}{a,b}").unwrap(), vec!["}a", "}b"]);
+    assert_eq!(brace::expand("{x{a,b}").unwrap(), vec!["{xa", "{xb"]);
+    assert_eq!(brace::expand("{a,b").unwrap(), vec!["{a,b"]);
+}
+
+/// Pieces random brace patterns are assembled from, stray braces included
+const FUZZ_PIECES: &[&str] = &[
+    "a", "b", ",", "{", "}", "\\{", "\\}", "\\,", "{a,b}", "{,a}", "{1..2}", "[}]", "[,]",
+];
+
+/// Reads an expansion as the literal string it stands for
+fn fuzz_literal(expansion: &str) -> String {
+    let mut out = String::new();
+    let mut chars = expansion.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            // Only single-member classes are generated
+            '[' => {
+                out.extend(chars.next());
+                chars.next();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[test]
+fn test_fuzz_expansion_and_regex_agree() {
+    use patternhunt::patterns::micromatch;
+    use patternhunt::{GlobOptions, Patterns};
+    use std::collections::HashSet;
+
+    let mut rng = fastrand::Rng::with_seed(0x6272_6163);
+    let opts = GlobOptions::default();
+    for _ in 0..2000 {
+        let pattern: String = (0..rng.usize(1..8))
+            .map(|_| FUZZ_PIECES[rng.usize(..FUZZ_PIECES.len())])
+            .collect();
+        let Ok(expansions) = brace::expand(&pattern) else {
+            continue;
+        };
+        let expected: HashSet<String> = expansions.iter().map(|e| fuzz_literal(e)).collect();
+
+        let mut candidates: Vec<String> = expected.iter().cloned().collect();
+        for e in &expected {
+            candidates.push(format!("{e}a"));
+            candidates.extend(e.get(1..).map(str::to_string));
+        }
+        for _ in 0..8 {
+            candidates.push(
+                (0..rng.usize(1..6))
+                    .map(|_| ['a', 'b', ',', '{', '}', '1', '2'][rng.usize(..7)])
+                    .collect(),
+            );
+        }
+        candidates.retain(|c| !c.is_empty());
+
+        let regex =
+            micromatch::micromatch_to_regex(&pattern).unwrap_or_else(|e| panic!("{pattern}: {e}"));
+        let regex = regex::Regex::new(&regex).unwrap_or_else(|e| panic!("{pattern}: {e}"));
+        let compiled =
+            Patterns::compile_one(&pattern, &opts).unwrap_or_else(|e| panic!("{pattern}: {e}"));
+        for candidate in &candidates {
+            let want = expected.contains(candidate);
+            assert_eq!(
+                regex.is_match(candidate),
+                want,
+                "{pattern} regex vs {candidate}"
+            );
+            assert_eq!(
+                compiled.matches_str(candidate),
+                want,
+                "{pattern} compiled vs {candidate}"
+            );
+        }
+    }
+}
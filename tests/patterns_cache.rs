@@ -0,0 +1,101 @@
+// tests/patterns_cache.rs
+use patternhunt::patterns::cache::{self, PatternCache};
+use patternhunt::{GlobOptions, Patterns};
+use std::{sync::Mutex, thread, time::Duration};
+
+/// Serializes tests that mutate the process-global caches
+static GLOBAL_CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_set_ttl_expires_new_entries() {
+    let _guard = GLOBAL_CACHE_LOCK.lock().unwrap();
+
+    cache::set_ttl(Duration::from_millis(1));
+    cache::get_or_compile_regex("^ttl_probe_[0-9]+$").unwrap();
+    let before = cache::regex_cache_metrics();
+
+    thread::sleep(Duration::from_millis(20));
+    cache::get_or_compile_regex("^ttl_probe_[0-9]+$").unwrap();
+    let after = cache::regex_cache_metrics();
+    cache::set_ttl(Duration::from_secs(300));
+
+    assert_eq!(after.misses, before.misses + 1);
+    assert_eq!(after.hits, before.hits);
+    assert_eq!(after.evictions, before.evictions + 1);
+}
+
+#[test]
+fn test_warm_populates_caches() {
+    let _guard = GLOBAL_CACHE_LOCK.lock().unwrap();
+    let opts = GlobOptions::default();
+    let patterns = ["warm_probe/*.rs", "warm_probe/@(a|b).txt", "re:warm_probe"];
+
+    let before = cache::regex_cache_metrics();
+    cache::warm(&patterns, &opts).unwrap();
+    let after = cache::regex_cache_metrics();
+
+    for p in patterns {
+        assert!(cache::contains(p), "{} should be cached", p);
+    }
+    assert!(!cache::contains("warm_probe/never_warmed"));
+    assert!(cache::glob_cache_keys().contains(&"warm_probe/*.rs".to_string()));
+    assert!(cache::regex_cache_keys().contains(&"^(?:warm_probe)$".to_string()));
+    assert_eq!(after.misses, before.misses + 2);
+
+    // Compiling warmed patterns again only produces hits
+    Patterns::compile_many(patterns, &opts).unwrap();
+    let again = cache::regex_cache_metrics();
+    assert_eq!(again.misses, after.misses);
+    assert_eq!(again.hits, after.hits + 2);
+}
+
+#[test]
+fn test_warm_reports_invalid_pattern() {
+    let _guard = GLOBAL_CACHE_LOCK.lock().unwrap();
+    let result = cache::warm(&["re:(unclosed"], &GlobOptions::default());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_pattern_caches_evict_independently() {
+    let first = PatternCache::new(1, Duration::from_secs(60));
+    let second = PatternCache::new(1, Duration::from_secs(60));
+    let opts = GlobOptions::default();
+
+    Patterns::compile_many_with_cache(["re:^a$"], &opts, &first).unwrap();
+    Patterns::compile_many_with_cache(["re:^a$"], &opts, &second).unwrap();
+    Patterns::compile_many_with_cache(["re:^b$"], &opts, &first).unwrap();
+
+    assert!(!first.contains("re:^a$"));
+    assert!(first.contains("re:^b$"));
+    assert_eq!(first.regex_metrics().evictions, 1);
+
+    assert!(second.contains("re:^a$"));
+    assert!(!second.contains("re:^b$"));
+    assert_eq!(second.regex_metrics().evictions, 0);
+}
+
+#[test]
+fn test_pattern_cache_is_isolated_from_global() {
+    let local = PatternCache::new(10, Duration::from_secs(60));
+    Patterns::compile_many_with_cache(["re:^isolated_probe$"], &GlobOptions::default(), &local)
+        .unwrap();
+
+    assert!(local.contains("re:^isolated_probe$"));
+    assert!(!cache::contains("re:^isolated_probe$"));
+    assert_eq!(local.regex_metrics().misses, 1);
+    assert_eq!(local.regex_metrics().size, 1);
+}
+
+#[test]
+fn test_anchored_and_unanchored_regexes_cached_separately() {
+    let local = PatternCache::new(10, Duration::from_secs(60));
+    Patterns::compile_many_with_cache(["re:probe"], &GlobOptions::default(), &local).unwrap();
+
+    assert!(local.contains("re:probe"));
+    assert!(!local.contains("re~:probe"));
+
+    Patterns::compile_many_with_cache(["re~:probe"], &GlobOptions::default(), &local).unwrap();
+    assert!(local.contains("re~:probe"));
+    assert_eq!(local.regex_metrics().size, 2);
+}
@@ -0,0 +1,64 @@
+// tests/patterns_case.rs
+use patternhunt::{GlobOptionsBuilder, Patterns};
+
+#[test]
+fn test_case_insensitive_ascii_glob() {
+    let opts = GlobOptionsBuilder::new().case_sensitive(false).build();
+    let p = Patterns::compile_one("src/*.RS", &opts).unwrap();
+    assert!(p.matches_str("SRC/lib.rs"));
+
+    let opts = GlobOptionsBuilder::new().case_sensitive(true).build();
+    let p = Patterns::compile_one("src/*.RS", &opts).unwrap();
+    assert!(!p.matches_str("src/lib.rs"));
+}
+
+#[test]
+fn test_case_insensitive_extglob_folds_unicode() {
+    let opts = GlobOptionsBuilder::new().case_sensitive(false).build();
+    let p = Patterns::compile_one("@(été|σοφία).txt", &opts).unwrap();
+    assert!(p.matches_str("ÉTÉ.TXT"));
+    assert!(p.matches_str("ΣΟΦΊΑ.txt"));
+}
+
+#[test]
+fn test_unicode_case_fold_for_plain_globs() {
+    let opts = GlobOptionsBuilder::new()
+        .case_sensitive(false)
+        .unicode_case_fold(true)
+        .build();
+    let p = Patterns::compile_one("docs/Été*.txt", &opts).unwrap();
+    assert!(p.set.is_empty());
+    assert!(p.matches_str("DOCS/ÉTÉ-2024.TXT"));
+    assert!(p.matches_str("docs/été.txt"));
+    assert!(!p.matches_str("docs/ete.txt"));
+}
+
+#[test]
+fn test_unicode_case_fold_ignored_when_case_sensitive() {
+    let opts = GlobOptionsBuilder::new()
+        .case_sensitive(true)
+        .unicode_case_fold(true)
+        .build();
+    let p = Patterns::compile_one("Été.txt", &opts).unwrap();
+    assert!(p.matches_str("Été.txt"));
+    assert!(!p.matches_str("été.txt"));
+}
+
+#[test]
+fn test_unicode_case_fold_is_simple_folding_only() {
+    let opts = GlobOptionsBuilder::new()
+        .case_sensitive(false)
+        .unicode_case_fold(true)
+        .build();
+    let p = Patterns::compile_one("straße.txt", &opts).unwrap();
+    assert!(p.matches_str("STRAßE.TXT"));
+    assert!(!p.matches_str("STRASSE.TXT"));
+}
+
+#[test]
+fn test_plain_glob_folds_ascii_only() {
+    let opts = GlobOptionsBuilder::new().case_sensitive(false).build();
+    let p = Patterns::compile_one("docs/Été*.txt", &opts).unwrap();
+    assert!(p.matches_str("DOCS/Été-2024.TXT"));
+    assert!(!p.matches_str("DOCS/ÉTÉ-2024.TXT"));
+}
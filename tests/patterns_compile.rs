@@ -0,0 +1,1046 @@
+// tests/patterns_compile.rs
+use patternhunt::patterns::{micromatch, PatternKind, WarningKind};
+use patternhunt::{
+    GlobError, GlobOptions, GlobOptionsBuilder, MatchMode, PathTraversalPolicy, Patterns,
+    RegexLimits,
+};
+
+#[test]
+fn test_nested_unbounded_repetition_rejected() {
+    let result = Patterns::compile_many(["re:^(a+)+$"], &GlobOptions::default());
+    assert!(matches!(result, Err(GlobError::RegexTooComplex)));
+
+    let result = Patterns::compile_many(["re:^(?:x*y*)*$"], &GlobOptions::default());
+    assert!(matches!(result, Err(GlobError::RegexTooComplex)));
+}
+
+#[test]
+fn test_benign_large_regex_accepted() {
+    let alternatives: Vec<String> = (0..100).map(|i| format!("file{}", i)).collect();
+    let pattern = format!("re:^(?:{})\\.txt$", alternatives.join("|"));
+    assert!(pattern.len() > 500);
+
+    let pats = Patterns::compile_many([pattern.as_str()], &GlobOptions::default()).unwrap();
+    assert!(pats.regexes[0].is_match("file42.txt"));
+}
+
+#[test]
+fn test_regex_limits_configurable() {
+    let relaxed = GlobOptionsBuilder::new()
+        .regex_limits(RegexLimits {
+            max_repetitions: 4,
+            ..RegexLimits::default()
+        })
+        .build();
+    assert!(Patterns::compile_many(["re:^(a+)+$"], &relaxed).is_ok());
+
+    let strict = GlobOptionsBuilder::new()
+        .regex_limits(RegexLimits {
+            max_len: 8,
+            ..RegexLimits::default()
+        })
+        .build();
+    let result = Patterns::compile_many(["re:^abcdefghij$"], &strict);
+    assert!(matches!(result, Err(GlobError::RegexTooComplex)));
+
+    let few_groups = GlobOptionsBuilder::new()
+        .regex_limits(RegexLimits {
+            max_groups: 1,
+            ..RegexLimits::default()
+        })
+        .build();
+    let result = Patterns::compile_many(["re:^(a)(?:b)$"], &few_groups);
+    assert!(matches!(result, Err(GlobError::RegexTooComplex)));
+}
+
+#[test]
+fn test_compile_one_matches_str_glob() {
+    let pats = Patterns::compile_one("logs/*.log", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("logs/app.log"));
+    assert!(!pats.matches_str("logs/app.txt"));
+}
+
+#[test]
+fn test_compile_one_matches_str_extglob() {
+    let pats = Patterns::compile_one("bucket/@(a|b).json", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("bucket/a.json"));
+    assert!(pats.matches_str("bucket/b.json"));
+    assert!(!pats.matches_str("bucket/c.json"));
+}
+
+#[test]
+fn test_top_level_alternation_is_anchored() {
+    assert_eq!(
+        micromatch::micromatch_to_regex("*.rs|*.toml").unwrap(),
+        "^(?:.*\\.rs|.*\\.toml)$"
+    );
+    let pats = Patterns::compile_one("*.rs|*.toml", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("main.rs"));
+    assert!(pats.matches_str("Cargo.toml"));
+    // The end anchor applies to the `*.rs` side too
+    assert!(!pats.matches_str("main.rs.bak"));
+    assert!(!pats.matches_str("notes.txt"));
+}
+
+#[test]
+fn test_negated_extglob_matches_other_segments() {
+    let pats = Patterns::compile_one("!(test)", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("src"));
+    assert!(pats.matches_str("tes"));
+    assert!(pats.matches_str("testing"));
+    assert!(!pats.matches_str("test"));
+    assert!(!pats.matches_str("src/lib"));
+
+    let pats = Patterns::compile_one("src/!(test|bench)/*.rs", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("src/a/x.rs"));
+    assert!(pats.matches_str("src/testing/x.rs"));
+    assert!(!pats.matches_str("src/test/x.rs"));
+    assert!(!pats.matches_str("src/bench/x.rs"));
+}
+
+#[test]
+fn test_negated_extglob_with_wildcards() {
+    let pats = Patterns::compile_one("!(*.rs)", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("main.c"));
+    assert!(pats.matches_str("rs"));
+    assert!(pats.matches_str("main.rs.bak"));
+    assert!(!pats.matches_str("main.rs"));
+    assert!(!pats.matches_str(".rs"));
+    assert!(!pats.matches_str("src/main.c"));
+
+    let pats = Patterns::compile_one("src/!(*.{js,ts})", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("src/a.css"));
+    assert!(pats.matches_str("src/a.jsx"));
+    assert!(!pats.matches_str("src/a.js"));
+    assert!(!pats.matches_str("src/a.ts"));
+
+    // Alternatives may mix classes, `?` and literal text
+    let pats = Patterns::compile_one("!(test?|[ab]*)", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("test"));
+    assert!(pats.matches_str("tests1"));
+    assert!(pats.matches_str("cat"));
+    assert!(!pats.matches_str("test1"));
+    assert!(!pats.matches_str("bat"));
+
+    // The negated part can be followed by more of the segment
+    let pats = Patterns::compile_one("!(*.min).js", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("app.js"));
+    assert!(!pats.matches_str("app.min.js"));
+
+    // Hidden files stay hidden, and case follows the options
+    let hidden = GlobOptionsBuilder::new().match_dotfiles(false).build();
+    let pats = Patterns::compile_one("!(*.rs)", &hidden).unwrap();
+    assert!(!pats.matches_str(".hidden"));
+    assert!(pats.matches_str("main.c"));
+    let insensitive = GlobOptionsBuilder::new().case_sensitive(false).build();
+    let pats = Patterns::compile_one("!(*.rs)", &insensitive).unwrap();
+    assert!(!pats.matches_str("MAIN.RS"));
+    assert!(pats.matches_str("main.c"));
+    let pats = Patterns::compile_one("!(*.rs)", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("MAIN.RS"));
+
+    // Nothing is left when an alternative matches every segment
+    let pats = Patterns::compile_one("!(*)", &GlobOptions::default()).unwrap();
+    assert!(!pats.matches_str("a"));
+
+    // Ruling out a string anywhere in the segment takes nested repetition
+    let result = Patterns::compile_one("!(*test*)", &GlobOptions::default());
+    assert!(matches!(result, Err(GlobError::RegexTooComplex)));
+    let relaxed = GlobOptionsBuilder::new()
+        .regex_limits(RegexLimits {
+            max_len: 4000,
+            max_repetitions: 4,
+            ..RegexLimits::default()
+        })
+        .build();
+    let pats = Patterns::compile_one("!(*test*)", &relaxed).unwrap();
+    assert!(pats.matches_str("tes"));
+    assert!(!pats.matches_str("a_test_b"));
+}
+
+#[test]
+fn test_extension_list_pattern() {
+    for mode in [MatchMode::FullPath, MatchMode::Basename] {
+        let opts = GlobOptionsBuilder::new().match_mode(mode).build();
+        let pats = Patterns::compile_one("ext:rs, .toml", &opts).unwrap();
+        assert!(pats.matches_str("a/b/c.rs"));
+        assert!(pats.matches_str("x.toml"));
+        assert!(!pats.matches_str("y.txt"));
+        assert!(!pats.matches_str("c.rs.bak"));
+        assert!(!pats.matches_str("crs"));
+    }
+
+    let insensitive = GlobOptionsBuilder::new().case_sensitive(false).build();
+    let pats = Patterns::compile_one("ext:rs", &insensitive).unwrap();
+    assert!(pats.matches_str("MAIN.RS"));
+}
+
+#[test]
+fn test_compile_one_matches_str_regex() {
+    let pats = Patterns::compile_one("re:^img_[0-9]+\\.png$", &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("img_001.png"));
+    assert!(!pats.matches_str("img_x.png"));
+}
+
+#[test]
+fn test_explain_extglob_pattern() {
+    let pattern = "src/**/*.@(rs|toml)";
+    let explained = Patterns::explain(&[pattern], &GlobOptions::default());
+    assert_eq!(explained.len(), 1);
+
+    let e = &explained[0];
+    assert_eq!(e.pattern, pattern);
+    assert_eq!(e.expansions, vec![pattern.to_string()]);
+    assert!(e.error.is_none());
+    assert_eq!(e.compiled.len(), 1);
+    assert_eq!(e.compiled[0].kind, PatternKind::ExtGlob);
+
+    let expected = micromatch::micromatch_to_regex(pattern).unwrap();
+    assert_eq!(e.compiled[0].regex.as_deref(), Some(expected.as_str()));
+    let re = regex::Regex::new(&expected).unwrap();
+    assert!(re.is_match("src/a/lib.rs"));
+    assert!(!re.is_match("src/a/lib.py"));
+}
+
+#[test]
+fn test_explain_brace_and_regex_patterns() {
+    let explained = Patterns::explain(&["*.{rs,md}", "re:x"], &GlobOptions::default());
+    assert_eq!(explained[0].expansions, vec!["*.rs", "*.md"]);
+    assert!(explained[0]
+        .compiled
+        .iter()
+        .all(|c| c.kind == PatternKind::SimpleGlob && c.regex.is_none()));
+    assert_eq!(explained[1].compiled[0].kind, PatternKind::Regex);
+    assert_eq!(explained[1].compiled[0].regex.as_deref(), Some("^(?:x)$"));
+}
+
+#[test]
+fn test_escaped_metacharacters_stay_on_glob_path() {
+    let opts = GlobOptions::default();
+    for pattern in [r"file\[1\].txt", r"file\?.txt", r"a\+b\(c\).txt"] {
+        let explained = Patterns::explain(&[pattern], &opts);
+        assert_eq!(explained[0].compiled[0].kind, PatternKind::SimpleGlob);
+    }
+
+    let pats = Patterns::compile_one(r"file\[1\].txt", &opts).unwrap();
+    assert!(pats.regexes.is_empty());
+    assert!(pats.matches_str("file[1].txt"));
+    assert!(!pats.matches_str("file1.txt"));
+}
+
+#[test]
+fn test_unescaped_metacharacters_trigger_regex() {
+    let opts = GlobOptions::default();
+    for pattern in ["file[1].txt", "file?.txt", r"\[x\]/@(a|b)"] {
+        let explained = Patterns::explain(&[pattern], &opts);
+        assert_eq!(explained[0].compiled[0].kind, PatternKind::ExtGlob);
+    }
+}
+
+#[test]
+fn test_regex_set_agrees_with_individual_regexes() {
+    let patterns = [
+        "re:^a+$",
+        "re:\\.rs$",
+        "src/@(lib|main).rs",
+        "docs/+(x|y).md",
+        "re:^tmp/",
+    ];
+    let pats = Patterns::compile_many(patterns, &GlobOptions::default()).unwrap();
+    assert_eq!(pats.regex_set.len(), pats.regexes.len());
+
+    for candidate in [
+        "aaa",
+        "src/lib.rs",
+        "src/other.py",
+        "docs/xyx.md",
+        "docs/z.md",
+        "tmp/file",
+        "",
+    ] {
+        let sequential = pats.regexes.iter().any(|re| re.is_match(candidate));
+        assert_eq!(pats.matches_str(candidate), sequential, "{}", candidate);
+    }
+}
+
+#[test]
+fn test_path_traversal_rejected() {
+    let opts = GlobOptions::default();
+    for pattern in ["../x", "**/..", "a/../../x", "./../x", "{a,..}/x"] {
+        let result = Patterns::compile_one(pattern, &opts);
+        assert!(
+            matches!(result, Err(GlobError::PathTraversal)),
+            "{pattern} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_path_traversal_within_root_resolved() {
+    let opts = GlobOptions::default();
+
+    let p = Patterns::compile_one("a/../b", &opts).unwrap();
+    assert!(p.matches_str("b"));
+    assert!(!p.matches_str("a/../b"));
+
+    let p = Patterns::compile_one("foo..bar", &opts).unwrap();
+    assert!(p.matches_str("foo..bar"));
+
+    let p = Patterns::compile_one("src/*/../*.rs", &opts).unwrap();
+    assert!(p.matches_str("src/lib.rs"));
+}
+
+#[test]
+fn test_path_traversal_policies() {
+    let normalize = GlobOptionsBuilder::new()
+        .path_traversal(PathTraversalPolicy::Normalize)
+        .build();
+    let p = Patterns::compile_one("../x", &normalize).unwrap();
+    assert!(p.matches_str("x"));
+
+    let allow = GlobOptionsBuilder::new()
+        .path_traversal(PathTraversalPolicy::Allow)
+        .build();
+    let p = Patterns::compile_one("../x", &allow).unwrap();
+    assert!(p.matches_str("../x"));
+}
+
+#[test]
+fn test_globstar_agrees_between_glob_and_regex_paths() {
+    let opts = GlobOptions::default();
+    let candidates = [
+        "src/test",
+        "src/a/test",
+        "src/a/b/test",
+        "src/a/btest",
+        "srctest",
+        "test",
+        "a/test",
+        "src/a/b",
+    ];
+    let pairs = [
+        ("src/**/test", "src/**/@(test)"),
+        ("**/test", "**/@(test)"),
+        ("src/**", "@(src)/**"),
+    ];
+
+    for (glob, extglob) in pairs {
+        let glob_path = Patterns::compile_one(glob, &opts).unwrap();
+        let regex_path = Patterns::compile_one(extglob, &opts).unwrap();
+        assert!(
+            regex_path.set.is_empty(),
+            "{extglob} should compile to a regex"
+        );
+
+        for candidate in candidates {
+            assert_eq!(
+                glob_path.matches_str(candidate),
+                regex_path.matches_str(candidate),
+                "{glob} vs {extglob} on {candidate}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_match_dotfiles() {
+    let shown = GlobOptions::default();
+    let hidden = GlobOptionsBuilder::new().match_dotfiles(false).build();
+
+    let star = |opts| Patterns::compile_one("*", opts).unwrap();
+    assert!(star(&shown).matches_str(".env"));
+    assert!(!star(&hidden).matches_str(".env"));
+    assert!(star(&hidden).matches_str("env"));
+
+    let cases = [
+        ("*.rs", ".rs", false),
+        ("*.rs", "a.rs", true),
+        ("?env", ".env", false),
+        (".*", ".env", true),
+        ("**/*.rs", "src/.a.rs", false),
+        ("**/*.rs", ".git/a.rs", false),
+        ("**/*.rs", "src/a/b.rs", true),
+        ("src/**", "src/.cache/x", false),
+        ("@(*.rs)", ".a.rs", false),
+        ("!(x)", ".env", false),
+        ("!(x)", "env", true),
+        ("a*", "a.env", true),
+    ];
+    for (pattern, candidate, expected) in cases {
+        let pats = Patterns::compile_one(pattern, &hidden).unwrap();
+        assert_eq!(
+            pats.matches_str(candidate),
+            expected,
+            "{pattern} on {candidate}"
+        );
+        // By default every candidate matches
+        let pats = Patterns::compile_one(pattern, &shown).unwrap();
+        assert!(pats.matches_str(candidate), "{pattern} on {candidate}");
+    }
+}
+
+#[test]
+fn test_leading_globstar_in_extglob() {
+    let opts = GlobOptions::default();
+    for pattern in ["**/@(a|b).rs", "**/**/@(a|b).rs", "x/**/@(a|b).rs"] {
+        let pats = Patterns::compile_one(pattern, &opts).unwrap();
+        let nested = pattern.starts_with('x');
+        assert_eq!(pats.matches_str("a.rs"), !nested, "{pattern}");
+        assert!(pats.matches_str("x/y/a.rs"), "{pattern}");
+        assert!(pats.matches_str("x/b.rs"), "{pattern}");
+        assert!(!pats.matches_str("x/y/c.rs"), "{pattern}");
+        assert!(!pats.matches_str("xa.rs"), "{pattern}");
+    }
+}
+
+#[test]
+fn test_separator_normalization() {
+    let opts = GlobOptionsBuilder::new().normalize_separators(true).build();
+    let p = Patterns::compile_one("src\\**\\*.rs", &opts).unwrap();
+    assert!(p.matches_str("src/a/b.rs"));
+    assert!(p.matches_str("src\\a\\b.rs"));
+    assert!(p.matches_str("src\\a/b.rs"));
+    assert!(!p.matches_str("lib\\a.rs"));
+
+    assert_eq!(
+        patternhunt::windows::normalize_separators("a\\b/c\\d"),
+        "a/b/c/d"
+    );
+}
+
+// globset itself never treats `\` as an escape on Windows
+#[cfg(not(windows))]
+#[test]
+fn test_backslash_escapes_without_normalization() {
+    let opts = GlobOptionsBuilder::new()
+        .normalize_separators(false)
+        .build();
+    let p = Patterns::compile_one("a\\*", &opts).unwrap();
+    assert!(p.matches_str("a*"));
+    assert!(!p.matches_str("ab"));
+}
+
+#[test]
+fn test_invalid_pattern_reports_index_and_text() {
+    let opts = GlobOptions::default();
+    let patterns = ["*.rs", "src/**", "docs/*.md", "[unclosed", "*.txt"];
+
+    match Patterns::compile_many(patterns, &opts) {
+        Err(GlobError::InvalidPatternAt { index, pattern, .. }) => {
+            assert_eq!(index, 3);
+            assert_eq!(pattern, "[unclosed");
+        }
+        other => panic!("expected InvalidPatternAt, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_invalid_brace_expansion_reports_source_pattern() {
+    let opts = GlobOptions::default();
+    let patterns = ["a", "b", "c", "{src,lib}/@(x", "d"];
+
+    let err = Patterns::compile_many(patterns, &opts).err().unwrap();
+    assert!(matches!(
+        err,
+        GlobError::InvalidPatternAt { index: 3, ref pattern, .. } if pattern == "{src,lib}/@(x"
+    ));
+    assert!(err.to_string().contains("{src,lib}/@(x"));
+}
+
+#[test]
+fn test_regex_quantifier_braces() {
+    let opts = GlobOptionsBuilder::new()
+        .regex_quantifier_braces(true)
+        .build();
+
+    let p = Patterns::compile_one("a{2}", &opts).unwrap();
+    assert!(p.matches_str("aa"));
+    assert!(!p.matches_str("a"));
+    assert!(!p.matches_str("a2"));
+
+    let p = Patterns::compile_one("x{1,3}y", &opts).unwrap();
+    assert!(p.matches_str("xy"));
+    assert!(p.matches_str("xxxy"));
+    assert!(!p.matches_str("xxxxy"));
+
+    // Non-numeric braces are still alternation
+    let p = Patterns::compile_one("*.{txt,md}", &opts).unwrap();
+    assert!(p.matches_str("notes.txt"));
+    assert!(p.matches_str("notes.md"));
+
+    // Without the option, numeric braces expand like any other
+    let p = Patterns::compile_one("a{2}", &GlobOptions::default()).unwrap();
+    assert!(p.matches_str("a2"));
+    assert!(!p.matches_str("aa"));
+}
+
+#[test]
+fn test_posix_character_classes() {
+    let opts = GlobOptions::default();
+
+    let p = Patterns::compile_one("file[[:digit:]].txt", &opts).unwrap();
+    assert!(p.matches_str("file7.txt"));
+    assert!(!p.matches_str("fileA.txt"));
+
+    let p = Patterns::compile_one("a[![:space:]]b", &opts).unwrap();
+    assert!(p.matches_str("a-b"));
+    assert!(!p.matches_str("a b"));
+
+    let p = Patterns::compile_one("v[a-z[:digit:]]", &opts).unwrap();
+    assert!(p.matches_str("vq"));
+    assert!(p.matches_str("v3"));
+    assert!(!p.matches_str("vQ"));
+}
+
+#[test]
+fn test_negated_class_forms_agree() {
+    let opts = GlobOptions::default();
+    let bang = Patterns::compile_one("x[!a]", &opts).unwrap();
+    let caret = Patterns::compile_one("x[^a]", &opts).unwrap();
+    for candidate in ["xa", "xb", "x^", "x!"] {
+        assert_eq!(bang.matches_str(candidate), caret.matches_str(candidate));
+    }
+    assert!(!bang.matches_str("xa"));
+    assert!(bang.matches_str("x^"));
+
+    // A caret that isn't leading is an ordinary member
+    let p = Patterns::compile_one("x[a^b]", &opts).unwrap();
+    assert!(p.matches_str("x^"));
+    assert!(p.matches_str("xb"));
+    assert!(!p.matches_str("xc"));
+}
+
+#[test]
+fn test_patterns_and_or() {
+    let opts = GlobOptions::default();
+    let rust = || Patterns::compile_one("**/*.rs", &opts).unwrap();
+    let src = || Patterns::compile_one("src/**", &opts).unwrap();
+
+    let both = rust().and(src());
+    assert!(both.matches_str("src/lib.rs"));
+    assert!(!both.matches_str("tests/lib.rs"));
+    assert!(!both.matches_str("src/notes.md"));
+
+    let either = rust().or(src());
+    assert!(either.matches_str("tests/lib.rs"));
+    assert!(either.matches_str("src/notes.md"));
+    assert!(!either.matches_str("docs/notes.md"));
+
+    // Chaining flattens and mixing nests
+    let three = rust()
+        .and(src())
+        .and(Patterns::compile_one("**/lib*", &opts).unwrap());
+    assert_eq!(three.groups.len(), 3);
+    assert!(three.matches_str("src/lib.rs"));
+    assert!(!three.matches_str("src/main.rs"));
+
+    let mixed = rust()
+        .and(src())
+        .or(Patterns::compile_one("*.md", &opts).unwrap());
+    assert!(mixed.matches_str("README.md"));
+    assert!(mixed.matches_str("src/main.rs"));
+    assert!(!mixed.matches_str("tests/main.rs"));
+}
+
+#[test]
+fn test_pattern_cache_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_file = dir.path().join("patterns.cache");
+    let opts = GlobOptionsBuilder::new()
+        .case_sensitive(false)
+        .match_mode(patternhunt::MatchMode::Both)
+        .build();
+    let inputs = [
+        "src/**/*.{rs,toml}",
+        "*.MD",
+        "@(foo|bar)*.txt",
+        "re~:^build/",
+        "target/",
+    ];
+
+    let compiled = Patterns::compile_many(inputs, &opts).unwrap();
+    compiled.save_cache(&cache_file).unwrap();
+    let loaded = Patterns::from_cached(&cache_file, inputs, &opts)
+        .unwrap()
+        .expect("cache should be current");
+
+    let candidates = [
+        "src/lib.rs",
+        "src/a/Cargo.TOML",
+        "src/lib.py",
+        "docs/readme.md",
+        "README.md",
+        "foo1.txt",
+        "baz1.txt",
+        "build/out.o",
+        "target/build/out.o",
+        "target",
+        "target/",
+    ];
+    for candidate in candidates {
+        assert_eq!(
+            loaded.matches_str(candidate),
+            compiled.matches_str(candidate),
+            "{}",
+            candidate
+        );
+    }
+    assert!(loaded.matches_str("readme.md"));
+    assert!(loaded.matches_entry_str("target", true));
+    assert_eq!(loaded.pattern_kinds(), compiled.pattern_kinds());
+}
+
+#[test]
+fn test_pattern_cache_stale_or_missing() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache_file = dir.path().join("patterns.cache");
+    let opts = GlobOptions::default();
+
+    assert!(Patterns::from_cached(&cache_file, ["*.rs"], &opts)
+        .unwrap()
+        .is_none());
+
+    Patterns::compile_one("*.rs", &opts)
+        .unwrap()
+        .save_cache(&cache_file)
+        .unwrap();
+    assert!(Patterns::from_cached(&cache_file, ["*.rs"], &opts)
+        .unwrap()
+        .is_some());
+
+    // Different patterns or options invalidate the cache
+    assert!(Patterns::from_cached(&cache_file, ["*.txt"], &opts)
+        .unwrap()
+        .is_none());
+    let insensitive = GlobOptionsBuilder::new().case_sensitive(false).build();
+    assert!(Patterns::from_cached(&cache_file, ["*.rs"], &insensitive)
+        .unwrap()
+        .is_none());
+
+    // compile_cached rewrites a stale file
+    let fresh = Patterns::compile_cached(&cache_file, &["*.txt"], &opts).unwrap();
+    assert!(fresh.matches_str("a.txt"));
+    assert!(Patterns::from_cached(&cache_file, ["*.txt"], &opts)
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_trailing_slash_patterns_are_directory_only() {
+    let opts = GlobOptionsBuilder::new()
+        .match_mode(patternhunt::MatchMode::Both)
+        .build();
+    let pats = Patterns::compile_many(["build/", "*.log"], &opts).unwrap();
+    assert!(pats.dir_only.is_some());
+
+    assert!(pats.matches_entry_str("build", true));
+    assert!(pats.matches_entry_str("src/build", true));
+    assert!(!pats.matches_entry_str("build", false));
+    assert!(!pats.matches_str("build"));
+
+    // A trailing slash on the candidate marks it as a directory
+    assert!(pats.matches_str("build/"));
+
+    // Other patterns match regardless of kind
+    assert!(pats.matches_entry_str("out.log", false));
+    assert!(pats.matches_entry_str("out.log", true));
+}
+
+#[test]
+fn test_has_regex_flag() {
+    let opts = GlobOptions::default();
+    let globs = Patterns::compile_many(["**/*.rs", "docs/*.md"], &opts).unwrap();
+    assert!(!globs.has_regex);
+    assert!(globs.matches_str("src/lib.rs"));
+    assert!(!globs.matches_str("src/lib.py"));
+
+    let mixed = Patterns::compile_many(["**/*.rs", "re~:\\.py$"], &opts).unwrap();
+    assert!(mixed.has_regex);
+    assert!(mixed.matches_str("src/lib.py"));
+
+    let extglob = Patterns::compile_one("*.@(rs|py)", &opts).unwrap();
+    assert!(extglob.has_regex);
+}
+
+#[test]
+fn test_anchored_and_unanchored_regex_prefixes() {
+    let opts = GlobOptions::default();
+
+    let anchored = Patterns::compile_one("re:foo", &opts).unwrap();
+    assert!(anchored.matches_str("foo"));
+    assert!(!anchored.matches_str("barfoobaz"));
+
+    let unanchored = Patterns::compile_one("re~:foo", &opts).unwrap();
+    assert!(unanchored.matches_str("foo"));
+    assert!(unanchored.matches_str("barfoobaz"));
+
+    // Alternation is anchored as a whole
+    let alternation = Patterns::compile_one("re:a|b", &opts).unwrap();
+    assert!(alternation.matches_str("b"));
+    assert!(!alternation.matches_str("ab"));
+
+    // Both forms live side by side in one RegexSet
+    let both = Patterns::compile_many(["re:foo", "re~:foo"], &opts).unwrap();
+    assert_eq!(both.regex_set.len(), 2);
+    assert_eq!(
+        both.regex_set
+            .matches("barfoobaz")
+            .iter()
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+}
+
+#[test]
+fn test_literal_patterns_escape_metacharacters() {
+    let opts = GlobOptionsBuilder::new().literal(true).build();
+    let pats = Patterns::compile_many(
+        [
+            "src/a*b?.txt",
+            "x/{1,2}.md",
+            "re:foo",
+            "dir/file[1].txt",
+            "back\\slash",
+        ],
+        &opts,
+    )
+    .unwrap();
+    assert!(!pats.has_regex);
+
+    assert!(pats.matches_str("src/a*b?.txt"));
+    assert!(!pats.matches_str("src/axbc.txt"));
+    assert!(pats.matches_str("x/{1,2}.md"));
+    assert!(!pats.matches_str("x/1.md"));
+    assert!(pats.matches_str("re:foo"));
+    assert!(!pats.matches_str("foo"));
+    assert!(pats.matches_str("dir/file[1].txt"));
+    assert!(!pats.matches_str("dir/file1.txt"));
+    assert!(pats.matches_str("back\\slash"));
+
+    // Unicode case folding goes through the regex engine, still literally
+    let folded = GlobOptionsBuilder::new()
+        .literal(true)
+        .case_sensitive(false)
+        .unicode_case_fold(true)
+        .build();
+    let pats = Patterns::compile_one("Ünï*.txt", &folded).unwrap();
+    assert!(pats.matches_str("üNÏ*.TXT"));
+    assert!(!pats.matches_str("ünïcode.txt"));
+}
+
+#[test]
+fn test_normalize_collapses_duplicates() {
+    let opts = GlobOptions::default();
+    let normalized = Patterns::normalize(&["*.{rs,md}", " *.rs", "*.md", "re:^x$"], &opts);
+    assert_eq!(normalized, vec!["*.rs", "*.md", "re:^x$"]);
+}
+
+#[test]
+fn test_normalize_keeps_globs_under_dot_directories() {
+    let patterns = [".hidden/*.rs", "src/.cache/*.rs", "**/*.rs", "src/*.rs"];
+    let opts = GlobOptionsBuilder::new().match_dotfiles(false).build();
+    assert_eq!(
+        Patterns::normalize(&patterns, &opts),
+        vec![".hidden/*.rs", "src/.cache/*.rs", "**/*.rs"]
+    );
+
+    let globstar = Patterns::compile_one("**/*.rs", &opts).unwrap();
+    assert!(!globstar.matches_str(".hidden/a.rs"));
+
+    let dotfiles = GlobOptionsBuilder::new().match_dotfiles(true).build();
+    assert_eq!(Patterns::normalize(&patterns, &dotfiles), vec!["**/*.rs"]);
+    let globstar = Patterns::compile_one("**/*.rs", &dotfiles).unwrap();
+    assert!(globstar.matches_str(".hidden/a.rs"));
+}
+
+#[test]
+fn test_normalize_drops_subsumed_patterns() {
+    let opts = GlobOptions::default();
+    let normalized = Patterns::normalize(
+        &[
+            "*.rs", "**/*.rs", "src/*.rs", "src/*.md", "**/*.rs/", "lib/?.rs",
+        ],
+        &opts,
+    );
+    assert_eq!(
+        normalized,
+        vec!["**/*.rs", "src/*.md", "**/*.rs/", "lib/?.rs"]
+    );
+
+    let literal = GlobOptions {
+        literal: true,
+        ..GlobOptions::default()
+    };
+    let normalized = Patterns::normalize(&["*.rs", "**/*.rs", "*.rs"], &literal);
+    assert_eq!(normalized, vec!["*.rs", "**/*.rs"]);
+}
+
+#[test]
+fn test_lint_flags_likely_mistakes() {
+    let opts = GlobOptions::default();
+    let kinds = |pattern: &str| -> Vec<WarningKind> {
+        Patterns::lint(&[pattern], &opts)
+            .into_iter()
+            .map(|w| w.kind)
+            .collect()
+    };
+
+    assert_eq!(kinds("**.rs"), vec![WarningKind::RedundantGlobstar]);
+    assert_eq!(
+        kinds("src/**/**/*.rs"),
+        vec![WarningKind::RedundantGlobstar]
+    );
+    assert_eq!(kinds("file{,}.txt"), vec![WarningKind::EmptyAlternation]);
+    assert_eq!(kinds("[z-a].txt"), vec![WarningKind::EmptyClass]);
+    assert_eq!(kinds("a[]b"), vec![WarningKind::EmptyClass]);
+    assert_eq!(kinds(r"re~:\.py$"), vec![WarningKind::UnanchoredRegex]);
+
+    let warnings = Patterns::lint(&["ok/*.rs", "**.md"], &opts);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].pattern, "**.md");
+    assert!(warnings[0].message.contains("acts like `*`"));
+}
+
+#[test]
+fn test_lint_accepts_well_formed_patterns() {
+    let opts = GlobOptions::default();
+    let clean = [
+        "src/**/*.rs",
+        "**",
+        "file.{txt,}",
+        "[]]x",
+        "[!a-z]*",
+        r"\{,\}",
+        "re:.*\\.rs",
+        "re~:^build/",
+        "@(a|b).txt",
+    ];
+    assert_eq!(Patterns::lint(&clean, &opts), vec![]);
+
+    // Literal patterns have no syntax to flag
+    let literal = GlobOptionsBuilder::new().literal(true).build();
+    assert!(Patterns::lint(&["**.rs", "{,}"], &literal).is_empty());
+}
+
+#[test]
+fn test_trace_match_glob_miss() {
+    let patterns =
+        Patterns::compile_many(["src/**/*.rs", "*.md"], &GlobOptions::default()).unwrap();
+
+    let hit = patterns.trace_match("src/foo/bar.rs");
+    assert!(hit.matched);
+    assert_eq!(hit.globs.len(), 1);
+    assert_eq!(hit.globs[0].source, "src/**/*.rs");
+
+    let miss = patterns.trace_match("src/foo/bar.py");
+    assert!(!miss.matched);
+    assert!(miss.globs.is_empty() && miss.regexes.is_empty());
+    assert_eq!(miss.candidate, "src/foo/bar.py");
+}
+
+#[test]
+fn test_trace_match_regex_miss() {
+    let opts = GlobOptionsBuilder::new()
+        .match_mode(patternhunt::MatchMode::Basename)
+        .build();
+    let patterns = Patterns::compile_many(["re:src/.*\\.rs", "+(a|b).txt"], &opts).unwrap();
+
+    let hit = patterns.trace_match("src/lib.rs");
+    assert!(hit.matched);
+    assert_eq!(hit.regexes.len(), 1);
+    assert_eq!(hit.regexes[0].source, "^(?:src/.*\\.rs)$");
+
+    let miss = patterns.trace_match("lib/src.rs");
+    assert!(!miss.matched);
+    assert!(miss.regexes.is_empty());
+    assert_eq!(miss.basename.as_deref(), Some("src.rs"));
+    assert!(miss.basename_regexes.is_empty());
+
+    let basename = patterns.trace_match("x/ab.txt");
+    assert!(basename.matched);
+    assert_eq!(basename.basename_regexes.len(), 1);
+    assert_eq!(
+        basename.matched,
+        patterns.matches_str("x/ab.txt"),
+        "trace agrees with matches_str"
+    );
+}
+
+#[test]
+fn test_separator_keeps_star_within_segment() {
+    let default = Patterns::compile_many(["docs/*.md"], &GlobOptions::default()).unwrap();
+    assert!(default.matches_str("docs/a/b.md"));
+
+    let opts = GlobOptionsBuilder::new().separator('/').build();
+    // Plain globs go through globset, extglobs through the regex translation
+    let patterns = Patterns::compile_many(["docs/*.md", "img/@(a|b)/?.png"], &opts).unwrap();
+    assert!(patterns.matches_str("docs/readme.md"));
+    assert!(!patterns.matches_str("docs/a/b.md"));
+    assert!(patterns.matches_str("img/a/1.png"));
+    assert!(!patterns.matches_str("img/a/x/1.png"));
+
+    let deep = Patterns::compile_many(["docs/**/*.md"], &opts).unwrap();
+    assert!(deep.matches_str("docs/a/b/c.md"));
+}
+
+#[test]
+fn test_custom_separator() {
+    let opts = GlobOptionsBuilder::new().separator(':').build();
+    let patterns = Patterns::compile_many(["app:*:name", "cfg:**:*.json"], &opts).unwrap();
+
+    assert!(patterns.matches_str("app:web:name"));
+    assert!(!patterns.matches_str("app:web:eu:name"));
+    // `/` is an ordinary character under a custom separator
+    assert!(patterns.matches_str("app:a/b:name"));
+    assert!(patterns.matches_str("cfg:x:y:z.json"));
+    assert!(!patterns.matches_str("cfg/x.json"));
+}
+
+#[test]
+fn test_literal_separator_star_vs_globstar() {
+    let opts = GlobOptionsBuilder::new().literal_separator(true).build();
+    // `+(...)` routes the second pattern through the regex translation
+    for pattern in ["src/*.rs", "src/*+(.rs)"] {
+        let crossing = Patterns::compile_many([pattern], &GlobOptions::default()).unwrap();
+        assert!(crossing.matches_str("src/a/b.rs"), "{}", pattern);
+
+        let single = Patterns::compile_many([pattern], &opts).unwrap();
+        assert!(single.matches_str("src/b.rs"), "{}", pattern);
+        assert!(!single.matches_str("src/a/b.rs"), "{}", pattern);
+    }
+
+    for pattern in ["src/**/*.rs", "src/**/*+(.rs)"] {
+        let deep = Patterns::compile_many([pattern], &opts).unwrap();
+        assert!(deep.matches_str("src/b.rs"), "{}", pattern);
+        assert!(deep.matches_str("src/a/b/c.rs"), "{}", pattern);
+    }
+
+    let class = Patterns::compile_many(["a[!x]b@(c)"], &opts).unwrap();
+    assert!(class.matches_str("aybc"));
+    assert!(!class.matches_str("a/bc"));
+}
+
+#[test]
+fn test_patterns_file_reports_line_number() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("patterns");
+    std::fs::write(&file, "# header\n*.rs\n\n!target/**\n[unclosed\n").unwrap();
+
+    match Patterns::compile_from_file(&file, &GlobOptions::default()) {
+        Err(GlobError::InvalidPatternInFile { line, pattern, .. }) => {
+            assert_eq!(line, 5);
+            assert_eq!(pattern, "[unclosed");
+        }
+        other => panic!("expected InvalidPatternInFile, got {:?}", other.err()),
+    }
+
+    std::fs::write(&file, "*.rs\n!target/**\n\\!bang\n").unwrap();
+    let pats = Patterns::compile_from_file(&file, &GlobOptions::default()).unwrap();
+    assert!(pats.matches_str("src/lib.rs"));
+    assert!(!pats.matches_str("target/debug/build.rs"));
+    assert!(pats.matches_str("!bang"));
+}
+
+#[test]
+fn test_pattern_kinds_after_compilation() {
+    let opts = GlobOptions::default();
+    let pats = Patterns::compile_many(["*.rs", "@(a|b)", "re:foo", "a{1,2}", ""], &opts).unwrap();
+    assert_eq!(
+        pats.pattern_kinds(),
+        vec![
+            PatternKind::SimpleGlob,
+            PatternKind::ExtGlob,
+            PatternKind::Regex,
+            PatternKind::BraceExpanded,
+        ]
+    );
+
+    // Numeric braces are quantifiers, not expansions, when asked for
+    let quantifier = GlobOptionsBuilder::new()
+        .regex_quantifier_braces(true)
+        .build();
+    let pats = Patterns::compile_one("a{2}", &quantifier).unwrap();
+    assert_eq!(pats.pattern_kinds(), vec![PatternKind::ExtGlob]);
+
+    let combined = Patterns::compile_one("*.rs", &opts)
+        .unwrap()
+        .or(Patterns::compile_one("re:x", &opts).unwrap());
+    assert_eq!(
+        combined.pattern_kinds(),
+        vec![PatternKind::SimpleGlob, PatternKind::Regex]
+    );
+}
+
+#[test]
+fn test_could_match_uses_literal_prefixes() {
+    use camino::Utf8PathBuf;
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache_file = dir.path().join("patterns.cache");
+    let opts = GlobOptions::default();
+    let inputs = ["src/**/mod.rs", "{docs,book}/index.md", "target/"];
+    let compiled = Patterns::compile_many(inputs, &opts).unwrap();
+    compiled.save_cache(&cache_file).unwrap();
+    let loaded = Patterns::from_cached(&cache_file, inputs, &opts)
+        .unwrap()
+        .expect("cache should be current");
+
+    for pats in [&compiled, &loaded] {
+        for reachable in ["src", "src/a/b", "docs", "docs/index.md", "target"] {
+            assert!(
+                pats.could_match(&Utf8PathBuf::from(reachable)),
+                "{reachable}"
+            );
+        }
+        for unreachable in [
+            "lib",
+            "vendor/src",
+            "docs/api",
+            "book/index.md/x",
+            "target/x",
+        ] {
+            assert!(
+                !pats.could_match(&Utf8PathBuf::from(unreachable)),
+                "{unreachable}"
+            );
+        }
+    }
+
+    // Slash-less patterns match file names anywhere, so nothing is ruled out
+    let anywhere = Patterns::compile_many(["src/**/mod.rs", "*.md"], &opts).unwrap();
+    assert!(anywhere.could_match(&Utf8PathBuf::from("vendor")));
+
+    // `src` may come below any directory, so no directory is ruled out, but
+    // entries without `src` and a final `mod.rs` are rejected unmatched
+    let interior = Patterns::compile_one("**/src/**/mod.rs", &opts).unwrap();
+    assert!(interior.could_match(&Utf8PathBuf::from("vendor/deep")));
+    assert!(interior.matches_str("vendor/src/a/mod.rs"));
+    assert!(interior.matches_str("src/mod.rs"));
+    assert!(!interior.matches_str("vendor/lib/mod.rs"));
+    assert!(!interior.matches_str("src/mod.rs/x"));
+
+    // With wildcards stopping at `/`, `*` fills exactly one segment
+    let opts = GlobOptionsBuilder::new().literal_separator(true).build();
+    let nested = Patterns::compile_one("*/src/**", &opts).unwrap();
+    assert!(nested.could_match(&Utf8PathBuf::from("crates/src/a")));
+    assert!(!nested.could_match(&Utf8PathBuf::from("crates/lib")));
+    assert!(nested.matches_str("crates/src/lib.rs"));
+    assert!(!nested.matches_str("crates/lib/src/lib.rs"));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_explicit_regex_sees_drive_letter_as_written() {
+    let opts = GlobOptionsBuilder::new().normalize_separators(true).build();
+    let pats = Patterns::compile_many([r"re:^c:/data/.*\.txt$", "c:/other/*.txt"], &opts).unwrap();
+
+    // Globs ignore the drive letter's case; the regex says exactly what it wants
+    assert!(pats.matches_str("c:/data/a.txt"));
+    assert!(!pats.matches_str("C:/data/a.txt"));
+    assert!(pats.matches_str("C:/other/b.txt"));
+    assert!(pats.matches_str(r"c:\other\b.txt"));
+
+    let trace = pats.trace_match("c:/data/a.txt");
+    assert_eq!(trace.regexes.len(), 1);
+    assert!(trace.matched);
+}
@@ -0,0 +1,44 @@
+// tests/patterns_match_mode.rs
+use camino::Utf8PathBuf;
+use patternhunt::{GlobOptionsBuilder, MatchMode, Patterns};
+
+fn matches(pattern: &str, mode: MatchMode, path: &str) -> bool {
+    let opts = GlobOptionsBuilder::new().match_mode(mode).build();
+    let pats = Patterns::compile_many([pattern], &opts).unwrap();
+    pats.is_match(&Utf8PathBuf::from(path))
+}
+
+#[test]
+fn test_basename_mode_matches_file_name() {
+    assert!(matches("f*.rs", MatchMode::Basename, "src/deep/foo.rs"));
+    assert!(!matches("f*.rs", MatchMode::FullPath, "src/deep/foo.rs"));
+    assert!(matches("f*.rs", MatchMode::Both, "src/deep/foo.rs"));
+}
+
+#[test]
+fn test_basename_mode_complex_patterns() {
+    assert!(matches(
+        "@(foo|bar).rs",
+        MatchMode::Basename,
+        "src/deep/foo.rs"
+    ));
+    assert!(!matches(
+        "@(foo|bar).rs",
+        MatchMode::FullPath,
+        "src/deep/foo.rs"
+    ));
+}
+
+#[test]
+fn test_basename_mode_keeps_slashed_patterns_on_full_path() {
+    assert!(matches(
+        "src/**/*.rs",
+        MatchMode::Basename,
+        "src/deep/foo.rs"
+    ));
+    assert!(!matches(
+        "deep/*.rs",
+        MatchMode::Basename,
+        "src/deep/foo.rs"
+    ));
+}
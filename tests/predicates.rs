@@ -0,0 +1,436 @@
+// tests/predicates.rs
+mod common;
+
+use patternhunt::predicates::FileType;
+use patternhunt::{GlobOptionsBuilder, PatternHunt, PredicatesBuilder};
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn test_path_glob_combined_with_size() {
+    let dir = common::fixture(&[
+        ("src/big.rs", "0123456789"),
+        ("src/small.rs", "0"),
+        ("other/big.rs", "0123456789"),
+    ]);
+    let predicates = PredicatesBuilder::new()
+        .path_glob("**/src/*")
+        .min_size(5)
+        .build();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(predicates)
+        .build();
+
+    let results = PatternHunt::sync(&["**/*.rs"], &["."], opts).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["src/big.rs"]);
+}
+
+#[test]
+fn test_invalid_path_glob_matches_nothing() {
+    let dir = common::fixture(&[("a.rs", "x")]);
+    let predicates = PredicatesBuilder::new().path_glob("[unclosed").build();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(predicates)
+        .build();
+
+    let results = PatternHunt::sync(&["**/*.rs"], &["."], opts).unwrap();
+    assert!(results.is_empty());
+}
+
+#[test]
+fn test_builder_sets_fields_fluently() {
+    let now = SystemTime::now();
+    let predicates = PredicatesBuilder::new()
+        .min_size(1)
+        .max_size(10)
+        .file_type(FileType::File)
+        .mtime_after(now - Duration::from_secs(60))
+        .mtime_before(now + Duration::from_secs(60))
+        .follow_symlinks(true)
+        .build();
+
+    assert_eq!(predicates.min_size, Some(1));
+    assert_eq!(predicates.max_size, Some(10));
+    assert_eq!(predicates.file_type, Some(FileType::File));
+    assert!(predicates.ctime_after.is_none());
+    assert!(predicates.follow_symlinks);
+
+    let dir = common::fixture(&[("fresh.txt", "hello"), ("empty.txt", "")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(predicates)
+        .build();
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["fresh.txt"]);
+}
+
+#[test]
+fn test_relative_mtime_predicates() {
+    let dir = common::fixture(&[("new.log", "n"), ("week.log", "w"), ("year.log", "y")]);
+    let now = SystemTime::now();
+    let day = Duration::from_secs(24 * 60 * 60);
+    for (name, age) in [("week.log", 7 * day), ("year.log", 365 * day)] {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(dir.path().join(name))
+            .unwrap();
+        file.set_modified(now - age).unwrap();
+    }
+    let select = |predicates| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(predicates)
+            .build();
+        let results = PatternHunt::sync(&["*.log"], &["."], opts).unwrap();
+        common::relative(dir.path(), &results)
+    };
+
+    let recent = PredicatesBuilder::new().mtime_within(day).build();
+    assert_eq!(select(recent), vec!["new.log"]);
+
+    let stale = PredicatesBuilder::new().mtime_older_than(30 * day).build();
+    assert_eq!(select(stale), vec!["year.log"]);
+
+    let between = PredicatesBuilder::new()
+        .mtime_older_than(day)
+        .mtime_within(30 * day)
+        .build();
+    assert_eq!(select(between), vec!["week.log"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_uid_gid_predicates() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = common::fixture(&[("mine.txt", "hello"), ("tiny.txt", "")]);
+    let meta = std::fs::metadata(dir.path().join("mine.txt")).unwrap();
+    let (uid, gid) = (meta.uid(), meta.gid());
+
+    let run = |predicates| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(predicates)
+            .build();
+        let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+        common::relative(dir.path(), &results)
+    };
+
+    let own = PredicatesBuilder::new()
+        .uid(uid)
+        .gid(gid)
+        .min_size(1)
+        .build();
+    assert_eq!(run(own), vec!["mine.txt"]);
+
+    let other = PredicatesBuilder::new().uid(uid.wrapping_add(1)).build();
+    assert!(run(other).is_empty());
+
+    let other_group = PredicatesBuilder::new().gid(gid.wrapping_add(1)).build();
+    assert!(run(other_group).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_mode_predicates() {
+    use std::fs::{self, Permissions};
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = common::fixture(&[("plain", "x"), ("shared", "x"), ("tool", "x")]);
+    for (name, mode) in [("plain", 0o644), ("shared", 0o666), ("tool", 0o755)] {
+        fs::set_permissions(dir.path().join(name), Permissions::from_mode(mode)).unwrap();
+    }
+
+    let run = |predicates| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(predicates)
+            .build();
+        let results = PatternHunt::sync(&["**/*"], &["."], opts).unwrap();
+        common::relative(dir.path(), &results)
+    };
+
+    let world_writable = PredicatesBuilder::new().mode(0o002, 0o002).build();
+    assert_eq!(run(world_writable), vec!["shared"]);
+
+    let executable = PredicatesBuilder::new().mode(0o111, 0o111).build();
+    assert_eq!(run(executable), vec!["tool"]);
+
+    let not_group_writable = PredicatesBuilder::new().mode(0o020, 0).build();
+    assert_eq!(run(not_group_writable), vec!["plain", "tool"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nlink_and_inode_predicates() {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = common::fixture(&[("original", "x"), ("single", "x")]);
+    std::fs::hard_link(dir.path().join("original"), dir.path().join("alias")).unwrap();
+
+    let run = |predicates| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(predicates)
+            .build();
+        let results = PatternHunt::sync(&["**/*"], &["."], opts).unwrap();
+        common::relative(dir.path(), &results)
+    };
+
+    let hardlinked = PredicatesBuilder::new().nlink_min(2).build();
+    assert_eq!(run(hardlinked), vec!["alias", "original"]);
+
+    let ino = std::fs::metadata(dir.path().join("single")).unwrap().ino();
+    let same_inode = PredicatesBuilder::new().inode(ino).build();
+    assert_eq!(run(same_inode), vec!["single"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_readable_predicate_skips_unreadable_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = common::fixture(&[("open.txt", "x"), ("locked.txt", "x")]);
+    let locked = dir.path().join("locked.txt");
+    std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+    // Privileged users can read it regardless
+    if std::fs::File::open(&locked).is_ok() {
+        return;
+    }
+
+    let run = |readable| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(PredicatesBuilder::new().readable(readable).build())
+            .build();
+        let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+        common::relative(dir.path(), &results)
+    };
+
+    assert_eq!(run(false), vec!["locked.txt", "open.txt"]);
+    assert_eq!(run(true), vec!["open.txt"]);
+
+    let patterns =
+        patternhunt::Patterns::compile_many(["**/*.txt"], &patternhunt::GlobOptions::default())
+            .unwrap();
+    let readable = PredicatesBuilder::new().readable(true).build();
+    let trace = patterns.trace_match_with(&locked, &readable).unwrap();
+    assert!(!trace.matched);
+    assert_eq!(trace.failed_predicate, Some("readable"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_predicate_matches_unfollowed_link() {
+    let dir = common::fixture(&[("target.txt", "t")]);
+    std::os::unix::fs::symlink(dir.path().join("target.txt"), dir.path().join("link.txt")).unwrap();
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(
+            PredicatesBuilder::new()
+                .file_type(FileType::Symlink)
+                .build(),
+        )
+        .build();
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(results, vec![dir.path().join("link.txt")]);
+
+    // Following for the stat makes the link look like its target
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(
+            PredicatesBuilder::new()
+                .file_type(FileType::File)
+                .follow_symlinks(true)
+                .build(),
+        )
+        .build();
+    let mut results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    results.sort();
+    assert_eq!(
+        results,
+        vec![dir.path().join("link.txt"), dir.path().join("target.txt")]
+    );
+}
+
+#[test]
+fn test_content_prefix_matches_magic_bytes() {
+    const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    let dir = common::fixture(&[("notes.txt", "plain text"), ("sub/empty.bin", "")]);
+    let mut png = PNG_MAGIC.to_vec();
+    png.extend_from_slice(b"rest of the image");
+    std::fs::write(dir.path().join("image.dat"), &png).unwrap();
+    std::fs::write(dir.path().join("sub/renamed.txt"), &png).unwrap();
+    // Shorter than the signature, but a prefix of it
+    std::fs::write(dir.path().join("short.png"), &PNG_MAGIC[..3]).unwrap();
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(PredicatesBuilder::new().content_prefix(PNG_MAGIC).build())
+        .build();
+
+    let results = PatternHunt::sync(&["**/*"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["image.dat", "sub/renamed.txt"]
+    );
+}
+
+#[test]
+fn test_trace_match_reports_failed_predicate() {
+    let dir = common::fixture(&[("src/small.rs", "0")]);
+    let path = dir.path().join("src/small.rs");
+    let patterns =
+        patternhunt::Patterns::compile_many(["**/*.rs"], &patternhunt::GlobOptions::default())
+            .unwrap();
+
+    let too_small = PredicatesBuilder::new().min_size(5).build();
+    let trace = patterns.trace_match_with(&path, &too_small).unwrap();
+    assert!(!trace.matched);
+    assert_eq!(trace.globs.len(), 1, "the pattern itself matched");
+    assert_eq!(trace.failed_predicate, Some("min_size"));
+
+    let wrong_magic = PredicatesBuilder::new().content_prefix(*b"#!").build();
+    let trace = patterns.trace_match_with(&path, &wrong_magic).unwrap();
+    assert_eq!(trace.failed_predicate, Some("content_prefix"));
+
+    let ok = PredicatesBuilder::new()
+        .file_type(FileType::File)
+        .max_size(5)
+        .build();
+    let trace = patterns.trace_match_with(&path, &ok).unwrap();
+    assert!(trace.matched);
+    assert_eq!(trace.failed_predicate, None);
+}
+
+// `cancel` hashes by pointer, so its interior mutability can't change a key
+#[allow(clippy::mutable_key_type)]
+#[test]
+fn test_option_sets_dedup_in_hash_set() {
+    use std::collections::HashSet;
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    let build = |min_size| {
+        GlobOptionsBuilder::new()
+            .max_depth(3)
+            .timeout(Duration::from_secs(5))
+            .predicates(
+                PredicatesBuilder::new()
+                    .min_size(min_size)
+                    .file_type(FileType::File)
+                    .mtime_after(SystemTime::UNIX_EPOCH)
+                    .build(),
+            )
+            .build()
+    };
+    let set: HashSet<_> = [build(1), build(1), build(2)].into_iter().collect();
+    assert_eq!(set.len(), 2);
+
+    // Cancellation tokens compare by identity
+    let token = Arc::new(AtomicBool::new(false));
+    let with_token = |token| GlobOptionsBuilder::new().cancel(token).build();
+    assert_eq!(with_token(token.clone()), with_token(token));
+    assert_ne!(
+        with_token(Arc::new(AtomicBool::new(false))),
+        with_token(Arc::new(AtomicBool::new(false)))
+    );
+}
+
+#[test]
+fn test_shared_batch_io_cache_clearing() {
+    use patternhunt::batch_io::BatchIO;
+    use std::sync::Arc;
+
+    let dir = common::fixture(&[("grow.txt", "12345")]);
+    let run = |io: &Arc<BatchIO>| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(PredicatesBuilder::new().min_size(10).build())
+            .batch_io(io.clone())
+            .build();
+        PatternHunt::sync(&["*.txt"], &["."], opts).unwrap().len()
+    };
+
+    let io = Arc::new(BatchIO::new(100, false));
+    assert_eq!(run(&io), 0);
+    std::fs::write(dir.path().join("grow.txt"), "0123456789abcdef").unwrap();
+    // The stale size is still cached until the cache is cleared
+    assert_eq!(run(&io), 0);
+    io.clear_cache();
+    assert_eq!(run(&io), 1);
+
+    let uncached = Arc::new(BatchIO::new(100, false).with_ttl(Duration::ZERO));
+    assert_eq!(run(&uncached), 1);
+    std::fs::write(dir.path().join("grow.txt"), "123").unwrap();
+    assert_eq!(run(&uncached), 0);
+}
+
+/// Not every filesystem backing the temp dir takes user attributes. Run
+/// with `--ignored`, setting `PATTERNHUNT_XATTR_DIR` to a directory on
+/// one that does if the default temp dir doesn't.
+#[cfg(target_os = "linux")]
+#[test]
+#[ignore = "needs user extended attributes; see PATTERNHUNT_XATTR_DIR"]
+fn test_has_xattr_predicate() {
+    use rustix::fs::{setxattr, XattrFlags};
+
+    let files = [("tagged.txt", "t"), ("other.txt", "o"), ("blue.txt", "b")];
+    let dir = match std::env::var_os("PATTERNHUNT_XATTR_DIR") {
+        Some(base) => common::fixture_in(base.as_ref(), &files),
+        None => common::fixture(&files),
+    };
+    let tag = |name: &str, value: &[u8]| {
+        setxattr(
+            dir.path().join(name),
+            "user.tag",
+            value,
+            XattrFlags::empty(),
+        )
+        .expect("the fixture's filesystem supports user attributes")
+    };
+    tag("tagged.txt", b"red");
+    tag("blue.txt", b"blue");
+    let select = |predicates| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .predicates(predicates)
+            .build();
+        let found = PatternHunt::sync(&["*.txt"], &["."], opts).unwrap();
+        common::relative(dir.path(), &found)
+    };
+
+    assert_eq!(
+        select(PredicatesBuilder::new().has_xattr("user.tag").build()),
+        vec!["blue.txt", "tagged.txt"]
+    );
+    assert_eq!(
+        select(
+            PredicatesBuilder::new()
+                .xattr_value("user.tag", "red")
+                .build()
+        ),
+        vec!["tagged.txt"]
+    );
+    assert!(select(PredicatesBuilder::new().has_xattr("user.missing").build()).is_empty());
+
+    let patterns =
+        patternhunt::Patterns::compile_many(["*.txt"], &patternhunt::GlobOptions::default())
+            .unwrap();
+    let trace = |name: &str, predicates| {
+        patterns
+            .trace_match_with(&dir.path().join(name), &predicates)
+            .unwrap()
+            .failed_predicate
+    };
+    let red = || {
+        PredicatesBuilder::new()
+            .xattr_value("user.tag", "red")
+            .build()
+    };
+    assert_eq!(trace("tagged.txt", red()), None);
+    assert_eq!(trace("blue.txt", red()), Some("xattr_value"));
+    assert_eq!(trace("other.txt", red()), Some("has_xattr"));
+}
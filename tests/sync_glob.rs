@@ -0,0 +1,1239 @@
+// tests/sync_glob.rs
+mod common;
+
+use patternhunt::predicates::FileType;
+use patternhunt::{
+    ErrorPolicy, GlobError, GlobOptionsBuilder, MatchMode, OnNonUtf8, PatternHunt, Patterns,
+    PredicatesBuilder,
+};
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
+
+#[test]
+fn test_sync_entries_depth() {
+    let dir = common::fixture(&[
+        ("a.txt", "a"),
+        ("sub/b.txt", "b"),
+        ("sub/deeper/c.txt", "c"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let mut entries = PatternHunt::sync_entries(&["**/*.txt"], &["."], opts).unwrap();
+    entries.sort_by_key(|e| e.depth);
+
+    let depths: Vec<usize> = entries.iter().map(|e| e.depth).collect();
+    assert_eq!(depths, vec![1, 2, 3]);
+    assert!(entries[2].path.ends_with("sub/deeper/c.txt"));
+    assert!(entries.iter().all(|e| e.file_type == FileType::File));
+    assert!(entries.iter().all(|e| e.metadata.is_none()));
+}
+
+#[test]
+fn test_sync_entries_metadata_with_predicates() {
+    let dir = common::fixture(&[("a.txt", "hello")]);
+    let predicates = PredicatesBuilder::new().min_size(1).build();
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(predicates)
+        .build();
+
+    let entries = PatternHunt::sync_entries(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].metadata.as_ref().map(|m| m.len), Some(5));
+}
+
+#[test]
+fn test_sync_timeout_exceeded() {
+    let files: Vec<(String, &str)> = (0..200)
+        .map(|i| (format!("d{}/f{}.txt", i % 10, i), "x"))
+        .collect();
+    let refs: Vec<(&str, &str)> = files.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+    let dir = common::fixture(&refs);
+
+    // A zero budget is already spent by the first deadline check
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .timeout(Duration::ZERO)
+        .build();
+
+    let result = PatternHunt::sync(&["**/*.txt"], &["."], opts);
+    assert!(matches!(result, Err(GlobError::Timeout)));
+}
+
+#[test]
+fn test_sync_generous_timeout_completes() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .timeout(Duration::from_secs(60))
+        .build();
+
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn test_for_each_visits_every_match() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b"), ("sub/c.md", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let mut seen = Vec::new();
+    PatternHunt::for_each(&["**/*.txt"], &["."], opts, |p| {
+        seen.push(p.to_path_buf());
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+
+    assert_eq!(
+        common::relative(dir.path(), &seen),
+        vec!["a.txt", "sub/b.txt"]
+    );
+}
+
+#[test]
+fn test_for_each_break_stops_traversal() {
+    let dir = common::fixture(&[("a.txt", "a"), ("b.txt", "b"), ("c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let mut calls = 0;
+    PatternHunt::for_each(&["**/*.txt"], &["."], opts, |_| {
+        calls += 1;
+        ControlFlow::Break(())
+    })
+    .unwrap();
+
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_skip_hidden_prunes_dotfiles_and_dotdirs() {
+    let dir = common::fixture(&[
+        ("visible.txt", "v"),
+        (".hidden", "h"),
+        (".git/config", "c"),
+        ("sub/.env", "e"),
+        ("sub/kept.txt", "k"),
+    ]);
+    let base = GlobOptionsBuilder::new().root_dir(dir.path().to_path_buf());
+
+    let all = PatternHunt::sync(&["**/*"], &["."], base.build()).unwrap();
+    assert_eq!(all.len(), 5);
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .skip_hidden(true)
+        .build();
+    let visible = PatternHunt::sync(&["**/*"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &visible),
+        vec!["sub/kept.txt", "visible.txt"]
+    );
+}
+
+#[test]
+fn test_cross_filesystems_toggle_on_single_filesystem() {
+    // Without mount points in the fixture both settings see the same tree
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/deeper/b.txt", "b")]);
+    let run = |cross| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .cross_filesystems(cross)
+            .build();
+        common::relative(
+            dir.path(),
+            &PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap(),
+        )
+    };
+
+    assert_eq!(run(false), vec!["a.txt", "sub/deeper/b.txt"]);
+    assert_eq!(run(true), run(false));
+    assert!(!patternhunt::GlobOptions::default().cross_filesystems);
+}
+
+#[test]
+fn test_match_directories() {
+    let dir = common::fixture(&[
+        ("node_modules/x.js", "x"),
+        ("pkg/node_modules/y.js", "y"),
+        ("pkg/lib.js", "l"),
+    ]);
+
+    let files_only = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+    let results = PatternHunt::sync(&["**/node_modules"], &["."], files_only).unwrap();
+    assert!(results.is_empty());
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .match_directories(true)
+        .build();
+    let results = PatternHunt::sync(&["**/node_modules"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["node_modules", "pkg/node_modules"]
+    );
+}
+
+#[test]
+fn test_dir_predicate_enables_directory_matching() {
+    let dir = common::fixture(&[("build/out.o", "o"), ("src/build", "file named build")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(PredicatesBuilder::new().file_type(FileType::Dir).build())
+        .build();
+
+    let results = PatternHunt::sync(&["**/build"], &["."], opts).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["build"]);
+}
+
+#[test]
+fn test_trailing_slash_matches_directories_only() {
+    let dir = common::fixture(&[("build/out.o", "o"), ("src/build", "file named build")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .match_directories(true)
+        .build();
+
+    let results = PatternHunt::sync(&["**/build/"], &["."], opts.clone()).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["build"]);
+
+    let results = PatternHunt::sync(&["**/build"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["build", "src/build"]
+    );
+}
+
+#[test]
+fn test_min_depth() {
+    let dir = common::fixture(&[("a.txt", "a"), ("x/b.txt", "b"), ("x/y/c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .min_depth(2)
+        .build();
+
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["x/b.txt", "x/y/c.txt"]
+    );
+}
+
+#[test]
+fn test_min_depth_with_max_depth() {
+    let dir = common::fixture(&[("a.txt", "a"), ("x/b.txt", "b"), ("x/y/c.txt", "c")]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .min_depth(2)
+        .max_depth(2)
+        .build();
+
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["x/b.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_policies() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = common::fixture(&[("ok.txt", "ok")]);
+    let bad = dir.path().join(OsStr::from_bytes(b"bad\xffname.txt"));
+    std::fs::write(&bad, "bad").unwrap();
+
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .non_utf8(policy)
+            .build()
+    };
+
+    let skipped = PatternHunt::sync(&["**/*.txt"], &["."], opts(OnNonUtf8::Skip)).unwrap();
+    assert_eq!(common::relative(dir.path(), &skipped), vec!["ok.txt"]);
+
+    let err = PatternHunt::sync(&["**/*.txt"], &["."], opts(OnNonUtf8::Error)).unwrap_err();
+    assert!(matches!(err, GlobError::NonUtf8Path(p) if p == bad));
+
+    let lossy = PatternHunt::sync(&["**/*.txt"], &["."], opts(OnNonUtf8::Lossy)).unwrap();
+    assert_eq!(lossy.len(), 2);
+    assert!(lossy.contains(&bad));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_lossy_matches_replacement_char_and_stats_original_path() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = common::fixture(&[("ok.txt", "ok")]);
+    let bad = dir.path().join(OsStr::from_bytes(b"bad\xffname.txt"));
+    std::fs::write(&bad, "bad").unwrap();
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .non_utf8(OnNonUtf8::Lossy)
+        .predicates(PredicatesBuilder::new().min_size(3).build())
+        .build();
+    let results = PatternHunt::sync(&["**/bad\u{FFFD}name.txt"], &["."], opts).unwrap();
+    assert_eq!(results, vec![bad]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_write_paths_nul_keeps_raw_bytes() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = common::fixture(&[("ok.txt", "ok"), ("skip.md", "no")]);
+    let bad = dir.path().join(OsStr::from_bytes(b"bad\xffname.txt"));
+    std::fs::write(&bad, "bad").unwrap();
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .non_utf8(OnNonUtf8::Lossy)
+        .build();
+    let mut out = Vec::new();
+    let written = PatternHunt::write_paths_nul(&["**/*.txt"], &["."], opts, &mut out).unwrap();
+    assert_eq!(written, 2);
+    assert_eq!(out.last(), Some(&0));
+
+    let mut paths: Vec<&[u8]> = out[..out.len() - 1].split(|&b| b == 0).collect();
+    paths.sort();
+    let ok = dir.path().join("ok.txt");
+    let mut expected = vec![bad.as_os_str().as_bytes(), ok.as_os_str().as_bytes()];
+    expected.sort();
+    assert_eq!(paths, expected);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_lossy_matches_lone_surrogate_names() {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+
+    let dir = common::fixture(&[("ok.txt", "ok")]);
+    let mut name: Vec<u16> = "bad".encode_utf16().collect();
+    name.push(0xD800);
+    name.extend("name.txt".encode_utf16());
+    let bad = dir.path().join(OsString::from_wide(&name));
+    std::fs::write(&bad, "bad").unwrap();
+
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .non_utf8(policy)
+            .predicates(PredicatesBuilder::new().min_size(3).build())
+            .build()
+    };
+    let skipped = PatternHunt::sync(&["**/bad?name.txt"], &["."], opts(OnNonUtf8::Skip)).unwrap();
+    assert!(skipped.is_empty());
+
+    let lossy = PatternHunt::sync(&["**/bad?name.txt"], &["."], opts(OnNonUtf8::Lossy)).unwrap();
+    assert_eq!(lossy, vec![bad]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_confine_to_root_excludes_escaping_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let outside = common::fixture(&[("secret.txt", "s")]);
+    let dir = common::fixture(&[("inside.txt", "i"), ("sub/nested.txt", "n")]);
+    symlink(outside.path(), dir.path().join("out_dir")).unwrap();
+    symlink(
+        outside.path().join("secret.txt"),
+        dir.path().join("out_file.txt"),
+    )
+    .unwrap();
+    symlink(dir.path().join("sub"), dir.path().join("in_dir")).unwrap();
+
+    let opts = |confine| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .follow_symlinks(true)
+            .confine_to_root(confine)
+            .build()
+    };
+
+    let open = PatternHunt::sync(&["**/*.txt"], &["."], opts(false)).unwrap();
+    assert!(common::relative(dir.path(), &open).contains(&"out_dir/secret.txt".to_string()));
+
+    let confined = PatternHunt::sync(&["**/*.txt"], &["."], opts(true)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &confined),
+        vec!["in_dir/nested.txt", "inside.txt", "sub/nested.txt"]
+    );
+}
+
+#[test]
+fn test_sync_with_stats_counters() {
+    let dir = common::fixture(&[
+        ("a.txt", "a"),
+        ("b.md", "b"),
+        ("sub/c.txt", "c"),
+        ("sub/deep/d.txt", "d"),
+    ]);
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+    let (results, stats) = PatternHunt::sync_with_stats(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(stats.dirs_visited, 3); // root, sub, sub/deep
+    assert_eq!(stats.entries_examined, 7); // 3 dirs + 4 files
+    assert_eq!(stats.stat_calls, 0);
+    assert_eq!(stats.matches, 3);
+
+    // Predicates stat only the entries that matched a pattern
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(PredicatesBuilder::new().min_size(0).build())
+        .build();
+    let (_, stats) = PatternHunt::sync_with_stats(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(stats.stat_calls, 3);
+    assert_eq!(stats.matches, 3);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_error_policy_with_broken_symlink() {
+    use std::os::unix::fs::symlink;
+
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b")]);
+    symlink(dir.path().join("missing"), dir.path().join("dangling")).unwrap();
+
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .follow_symlinks(true)
+            .on_error(policy)
+            .build()
+    };
+
+    let err = PatternHunt::sync(&["**/*.txt"], &["."], opts(ErrorPolicy::Abort)).unwrap_err();
+    assert!(matches!(err, GlobError::Io(e) if e.kind() == std::io::ErrorKind::NotFound));
+
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts(ErrorPolicy::Skip)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["a.txt", "sub/b.txt"]
+    );
+
+    let (results, errors) =
+        PatternHunt::sync_with_errors(&["**/*.txt"], &["."], opts(ErrorPolicy::Collect)).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(errors.len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unreadable_subdirectory_does_not_abort_with_skip() {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = common::fixture(&[("a.txt", "a"), ("locked/b.txt", "b")]);
+    let locked = dir.path().join("locked");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Privileged users can read the directory anyway; nothing to test then
+    if fs::read_dir(&locked).is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    let opts = |policy| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .on_error(policy)
+            .build()
+    };
+    let aborted = PatternHunt::sync(&["**/*.txt"], &["."], opts(ErrorPolicy::Abort));
+    let (results, errors) =
+        PatternHunt::sync_with_errors(&["**/*.txt"], &["."], opts(ErrorPolicy::Collect)).unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert!(aborted.is_err());
+    assert_eq!(common::relative(dir.path(), &results), vec!["a.txt"]);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_sync_compiled_reuses_patterns() {
+    let first = common::fixture(&[("a.rs", "a"), ("src/b.rs", "b"), ("c.txt", "c")]);
+    let second = common::fixture(&[("x.rs", "x"), ("y.md", "y")]);
+    let opts_for = |dir: &std::path::Path| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.to_path_buf())
+            .build()
+    };
+    let pats = Patterns::compile_one("**/*.rs", &opts_for(first.path())).unwrap();
+
+    let mut runs = Vec::new();
+    for _ in 0..3 {
+        let results = PatternHunt::sync_compiled(&pats, &["."], opts_for(first.path())).unwrap();
+        runs.push(common::relative(first.path(), &results));
+    }
+    assert_eq!(runs[0], vec!["a.rs", "src/b.rs"]);
+    assert!(runs.iter().all(|run| run == &runs[0]));
+
+    let fresh = PatternHunt::sync(&["**/*.rs"], &["."], opts_for(first.path())).unwrap();
+    assert_eq!(common::relative(first.path(), &fresh), runs[0]);
+
+    // The same instance against another root
+    let results = PatternHunt::sync_compiled(&pats, &["."], opts_for(second.path())).unwrap();
+    assert_eq!(common::relative(second.path(), &results), vec!["x.rs"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_max_symlink_depth_limits_hops() {
+    let (dir, _outside) = common::symlink_chain();
+
+    let unlimited = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .follow_symlinks(true)
+        .build();
+    let unlimited = PatternHunt::sync(&["**/*.txt"], &["."], unlimited).unwrap();
+    assert!(common::relative(dir.path(), &unlimited).contains(&"a/b/c/d/deep.txt".to_string()));
+
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .follow_symlinks(true)
+        .max_symlink_depth(2)
+        .build();
+    let results = PatternHunt::sync(&["**/*.txt"], &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["a/b/two.txt", "a/one.txt", "top.txt"]
+    );
+}
+
+#[test]
+fn test_count_matches_sync_len() {
+    let dir = common::fixture(&[
+        ("a.rs", "a"),
+        ("src/b.rs", "bb"),
+        ("src/c.txt", "c"),
+        ("src/deep/d.rs", "dddd"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .predicates(PredicatesBuilder::new().min_size(2).build())
+        .build();
+
+    for patterns in [&["**/*.rs"][..], &["**/*.txt", "*.rs"], &["**/*.md"]] {
+        let count = PatternHunt::count(patterns, &["."], opts.clone()).unwrap();
+        let paths = PatternHunt::sync(patterns, &["."], opts.clone()).unwrap();
+        assert_eq!(count, paths.len(), "{:?}", patterns);
+    }
+}
+
+#[test]
+fn test_literal_mode_matches_exact_names() {
+    let dir = common::fixture(&[
+        ("a*b.txt", "star"),
+        ("axb.txt", "x"),
+        ("ab.txt", "none"),
+        ("file[1].txt", "bracket"),
+        ("file1.txt", "plain"),
+    ]);
+    let opts = |literal| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .match_mode(MatchMode::Basename)
+            .literal(literal)
+            .build()
+    };
+
+    let globbed = PatternHunt::sync(&["a*b.txt"], &["."], opts(false)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &globbed),
+        vec!["a*b.txt", "ab.txt", "axb.txt"]
+    );
+
+    let literal = PatternHunt::sync(&["a*b.txt", "file[1].txt"], &["."], opts(true)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &literal),
+        vec!["a*b.txt", "file[1].txt"]
+    );
+}
+
+#[test]
+fn test_sync_with_progress_reports_running_stats() {
+    let names: Vec<String> = (0..600)
+        .map(|i| format!("d{}/f{}.txt", i % 10, i))
+        .collect();
+    let files: Vec<(&str, &str)> = names.iter().map(|n| (n.as_str(), "x")).collect();
+    let dir = common::fixture(&files);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let mut reports = Vec::new();
+    let (results, stats) = PatternHunt::sync_with_progress(&["**/*.txt"], &["."], opts, |s| {
+        reports.push((s.entries_examined, s.dirs_visited, s.elapsed))
+    })
+    .unwrap();
+
+    assert_eq!(results.len(), 600);
+    assert!(reports.len() >= 2, "{} reports", reports.len());
+    assert!(reports
+        .windows(2)
+        .all(|w| w[0].0 <= w[1].0 && w[0].2 <= w[1].2));
+    let last = reports.last().unwrap();
+    assert_eq!(last.0, stats.entries_examined);
+    assert_eq!(last.1, stats.dirs_visited);
+    assert_eq!(stats.dirs_visited, 11);
+}
+
+#[test]
+fn test_patterns_relative_to_each_root() {
+    let dir = common::fixture(&[
+        ("a/x.rs", "x"),
+        ("a/sub/y.rs", "y"),
+        ("b/z.rs", "z"),
+        ("b/sub/w.txt", "w"),
+        ("c/sub/skipped.rs", "s"),
+    ]);
+    let opts = |relative| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .patterns_relative_to_root(relative)
+            .build()
+    };
+
+    // Absolute matching needs the root in the pattern
+    let absolute = PatternHunt::sync(&["sub/*"], &["a", "b"], opts(false)).unwrap();
+    assert!(absolute.is_empty());
+
+    let relative = PatternHunt::sync(&["sub/*"], &["a", "b"], opts(true)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &relative),
+        vec!["a/sub/y.rs", "b/sub/w.txt"]
+    );
+
+    let rust = PatternHunt::sync(&["*.rs"], &["a", "b"], opts(true)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &rust),
+        vec!["a/sub/y.rs", "a/x.rs", "b/z.rs"]
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn test_sync_long_paths_on_windows() {
+    let segment = "d".repeat(50);
+    let deep = format!("{}/deep.txt", [segment.as_str(); 6].join("/"));
+    let dir = common::fixture(&[(deep.as_str(), "x")]);
+    assert!(dir.path().join(&deep).as_os_str().len() > 260);
+    let opts = |keep| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .keep_long_path_prefix(keep)
+            .build()
+    };
+
+    let found = PatternHunt::sync(&["**/deep.txt"], &["."], opts(false)).unwrap();
+    assert_eq!(common::relative(dir.path(), &found), vec![deep.clone()]);
+
+    // The prefix is stripped for matching either way
+    let prefixed = PatternHunt::sync(&["**/deep.txt"], &["."], opts(true)).unwrap();
+    assert_eq!(prefixed.len(), 1);
+    assert!(prefixed[0].to_string_lossy().starts_with(r"\\?\"));
+    assert_eq!(
+        patternhunt::windows::strip_long_path_prefix(&prefixed[0]),
+        found[0].as_path()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn test_long_path_prefix_for_unc_and_relative_paths() {
+    use patternhunt::windows::{ensure_long_path_prefix, strip_long_path_prefix};
+    use std::path::Path;
+
+    let unc = ensure_long_path_prefix(Path::new(r"\\server\share\dir\..\file.txt"));
+    assert_eq!(unc, Path::new(r"\\?\UNC\server\share\file.txt"));
+    assert_eq!(
+        strip_long_path_prefix(&unc),
+        Path::new(r"\\server\share\file.txt")
+    );
+    assert_eq!(ensure_long_path_prefix(&unc), unc);
+
+    let disk = ensure_long_path_prefix(Path::new("C:/data/./logs/a.txt"));
+    assert_eq!(disk, Path::new(r"\\?\C:\data\logs\a.txt"));
+
+    // Verbatim paths must be absolute, so relative ones stay as they are
+    let relative = Path::new(r"sub\..\file.txt");
+    assert_eq!(ensure_long_path_prefix(relative), relative);
+}
+
+#[cfg(windows)]
+#[test]
+fn test_drive_letter_case_is_ignored() {
+    let dir = common::fixture(&[("a.txt", "a"), ("sub/b.txt", "b")]);
+    let root = dir.path().to_str().unwrap();
+    let with_drive = |upper: bool| {
+        let (letter, rest) = root.split_at(1);
+        let letter = if upper {
+            letter.to_ascii_uppercase()
+        } else {
+            letter.to_ascii_lowercase()
+        };
+        format!("{}{}", letter, rest)
+    };
+
+    for (root_upper, pattern_upper) in [(false, true), (true, false)] {
+        let pattern = format!("{}/**/*.txt", with_drive(pattern_upper).replace('\\', "/"));
+        let opts = GlobOptionsBuilder::new().normalize_separators(true).build();
+        let root = with_drive(root_upper);
+        let results = PatternHunt::sync(&[pattern.as_str()], &[root.as_str()], opts);
+        let mut names: Vec<_> = results
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "b.txt"], "pattern {pattern}");
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_match_resolved_symlink_target() {
+    use std::os::unix::fs::symlink;
+
+    let dir = common::fixture(&[("data/real.csv", "a,b")]);
+    symlink(
+        dir.path().join("data/real.csv"),
+        dir.path().join("alias.txt"),
+    )
+    .unwrap();
+    let opts = |resolve_match, resolve_return| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .match_resolved_path(resolve_match)
+            .return_resolved_path(resolve_return)
+            .build()
+    };
+
+    let by_link = PatternHunt::sync(&["**/*.csv"], &["."], opts(false, false)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &by_link),
+        vec!["data/real.csv"]
+    );
+
+    // The link is matched through its target but returned as itself
+    let by_target = PatternHunt::sync(&["**/*.csv"], &["."], opts(true, false)).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &by_target),
+        vec!["alias.txt", "data/real.csv"]
+    );
+
+    let real = std::fs::canonicalize(dir.path().join("data/real.csv")).unwrap();
+    let returned = PatternHunt::sync(&["**/*.csv"], &["."], opts(true, true)).unwrap();
+    assert_eq!(returned.len(), 2);
+    assert!(returned.contains(&real));
+}
+
+#[test]
+fn test_include_root() {
+    let dir = common::fixture(&[("sub/a.txt", "a")]);
+    let root = dir.path().to_path_buf();
+    let opts = |include_root| {
+        GlobOptionsBuilder::new()
+            .root_dir(root.clone())
+            .match_directories(true)
+            .patterns_relative_to_root(true)
+            .include_root(include_root)
+    };
+
+    // The root is never a result unless asked for
+    let without = PatternHunt::sync(&["**"], &["."], opts(false).build()).unwrap();
+    assert_eq!(common::relative(&root, &without), vec!["sub", "sub/a.txt"]);
+
+    let with = PatternHunt::sync(&["**"], &["."], opts(true).build()).unwrap();
+    assert_eq!(with.len(), 3);
+    assert!(with.contains(&root));
+
+    // Relative to itself, the root is `.`
+    let dot = PatternHunt::sync(&["."], &["."], opts(true).build()).unwrap();
+    assert_eq!(dot, vec![root.clone()]);
+
+    let below = PatternHunt::sync(&["."], &["."], opts(true).min_depth(1).build()).unwrap();
+    assert!(below.is_empty());
+}
+
+#[test]
+fn test_sync_with_owned_and_borrowed_strings() {
+    let dir = common::fixture(&[("a.rs", ""), ("b.md", ""), ("sub/c.rs", ""), ("d.txt", "")]);
+    let opts = || {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .build()
+    };
+
+    let patterns: Vec<String> = ["rs", "md"].iter().map(|e| format!("**/*.{}", e)).collect();
+    let roots = vec![String::from(".")];
+    let owned = PatternHunt::sync_with(patterns, roots, opts()).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &owned),
+        vec!["a.rs", "b.md", "sub/c.rs"]
+    );
+
+    let borrowed = PatternHunt::sync_with(["**/*.txt"], ["sub", "."], opts()).unwrap();
+    assert_eq!(common::relative(dir.path(), &borrowed), vec!["d.txt"]);
+}
+
+#[test]
+fn test_root_spelling_settings() {
+    let dir = common::local_fixture(&[("a.rs", "")]);
+    let cwd = std::env::current_dir().unwrap();
+    let rel = Path::new(dir.path().file_name().unwrap());
+    let root = format!("./{}", rel.to_str().unwrap());
+    let run = |opts: GlobOptionsBuilder| PatternHunt::sync(&["**/*.rs"], &[&root], opts.build());
+
+    // By default the root is returned as spelled
+    let as_given = run(GlobOptionsBuilder::new()).unwrap();
+    assert_eq!(as_given, vec![Path::new(&root).join("a.rs")]);
+
+    let stripped = run(GlobOptionsBuilder::new().strip_dot_prefix(true)).unwrap();
+    assert_eq!(stripped, vec![rel.join("a.rs")]);
+
+    let absolute = run(GlobOptionsBuilder::new().canonicalize_root(true)).unwrap();
+    assert_eq!(absolute, vec![cwd.join(rel).join("a.rs")]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dedupe_by_canonical_across_roots() {
+    let dir = common::fixture(&[("real/a.txt", "a"), ("real/sub/b.txt", "b")]);
+    std::os::unix::fs::symlink(dir.path().join("real"), dir.path().join("alias")).unwrap();
+    let run = |dedupe| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .dedupe_by_canonical(dedupe)
+            .build();
+        PatternHunt::sync(&["**/*.txt"], &["real", "alias"], opts).unwrap()
+    };
+
+    assert_eq!(run(false).len(), 4);
+    let unique = run(true);
+    assert_eq!(
+        common::relative(dir.path(), &unique),
+        vec!["real/a.txt", "real/sub/b.txt"]
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_walk_matches_sequential() {
+    let dir = common::fixture(&[
+        ("top.rs", "fn main() {}"),
+        ("a/one.rs", ""),
+        ("a/b/two.rs", "two"),
+        ("a/b/c/three.rs", "three"),
+        ("d/four.rs", "four"),
+        ("d/notes.md", ""),
+        (".hidden/five.rs", "five"),
+        ("e/f/g/h/six.rs", "six"),
+    ]);
+    let run = |threads, build: fn(GlobOptionsBuilder) -> GlobOptionsBuilder| {
+        let opts = build(
+            GlobOptionsBuilder::new()
+                .root_dir(dir.path().to_path_buf())
+                .threads(threads),
+        );
+        let mut found = PatternHunt::sync(&["**/*.rs", "**/b"], &["."], opts.build()).unwrap();
+        found.sort();
+        found
+    };
+
+    let variants: [fn(GlobOptionsBuilder) -> GlobOptionsBuilder; 5] = [
+        |o| o,
+        |o| o.skip_hidden(true),
+        |o| o.max_depth(3).min_depth(2),
+        |o| o.match_directories(true),
+        |o| o.predicates(patternhunt::PredicatesBuilder::new().min_size(1).build()),
+    ];
+    for build in variants {
+        let sequential = run(1, build);
+        assert!(!sequential.is_empty());
+        for threads in [2, 4] {
+            assert_eq!(run(threads, build), sequential);
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_walk_reports_early_stop() {
+    use patternhunt::{sync::glob_sync_visit, GlobOptions, Patterns};
+
+    let dir = common::fixture(&[
+        ("a/one.rs", "one"),
+        ("b/two.rs", "two"),
+        ("c/three.rs", "three"),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .threads(2)
+        .build();
+    let patterns = Patterns::compile_many(["**/*.rs"], &opts).unwrap();
+
+    let mut seen = 0;
+    let flow = glob_sync_visit(&patterns, &opts, None, |_| {
+        seen += 1;
+        ControlFlow::Break(())
+    });
+    assert_eq!(flow.unwrap(), ControlFlow::Break(()));
+    assert_eq!(seen, 1);
+
+    let budget = GlobOptions {
+        max_total_bytes: Some(4),
+        ..opts
+    };
+    let mut found = Vec::new();
+    let flow = glob_sync_visit(&patterns, &budget, None, |e| {
+        found.push(e.path);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(flow.unwrap(), ControlFlow::Break(()));
+    // Whichever file comes first fits the budget, and no second one does
+    assert_eq!(found.len(), 1);
+}
+
+#[test]
+fn test_rescan_reports_changes_since_previous_run() {
+    use std::time::SystemTime;
+
+    let dir = common::fixture(&[("a.txt", "a"), ("b.txt", "b"), ("sub/c.txt", "c")]);
+    let set_age = |name: &str, age: Duration| {
+        std::fs::File::options()
+            .write(true)
+            .open(dir.path().join(name))
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    };
+    let hour = Duration::from_secs(3600);
+    for name in ["a.txt", "b.txt", "sub/c.txt"] {
+        set_age(name, 2 * hour);
+    }
+    let opts = || {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .build()
+    };
+    let previous = PatternHunt::sync(&["**/*.txt"], &["."], opts()).unwrap();
+    let since = SystemTime::now() - hour;
+    assert!(
+        PatternHunt::sync_since(&["**/*.txt"], &["."], opts(), since)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Touch one file, create another and delete a third
+    set_age("a.txt", Duration::ZERO);
+    std::fs::write(dir.path().join("sub/d.txt"), "d").unwrap();
+    std::fs::remove_file(dir.path().join("b.txt")).unwrap();
+
+    let (mut changed, removed) =
+        PatternHunt::sync_changes(&["**/*.txt"], &["."], opts(), since, &previous).unwrap();
+    changed.sort();
+    assert_eq!(
+        common::relative(dir.path(), &changed),
+        vec!["a.txt", "sub/d.txt"]
+    );
+    assert_eq!(common::relative(dir.path(), &removed), vec!["b.txt"]);
+}
+
+#[test]
+fn test_max_total_bytes_stops_walk() {
+    let ten = "0123456789";
+    let dir = common::fixture(&[
+        ("a.log", ten),
+        ("b.log", ten),
+        ("c.log", ten),
+        ("d.log", ten),
+        ("e.log", ten),
+    ]);
+    let count = |budget, inclusive| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .max_total_bytes(budget)
+            .max_total_bytes_inclusive(inclusive)
+            .build();
+        PatternHunt::sync(&["**/*.log"], &["."], opts)
+            .unwrap()
+            .len()
+    };
+
+    // The file that would cross the budget is dropped unless inclusive
+    assert_eq!(count(25, false), 2);
+    assert_eq!(count(25, true), 3);
+    // Reaching the budget exactly stops the walk either way
+    assert_eq!(count(30, false), 3);
+    assert_eq!(count(30, true), 3);
+    assert_eq!(count(1000, false), 5);
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    let names: Vec<String> = (0..500).map(|i| format!("f{}.txt", i)).collect();
+    let files: Vec<(&str, &str)> = names.iter().map(|n| (n.as_str(), "x")).collect();
+    let dir = common::fixture(&files);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .cancel(cancel.clone())
+        .build();
+
+    let stopper = {
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        })
+    };
+
+    // Each match takes a while, so the full walk would need over 2.5s
+    let started = std::time::Instant::now();
+    let mut seen = 0;
+    let result = PatternHunt::for_each(&["**/*.txt"], &["."], opts, |_| {
+        seen += 1;
+        std::thread::sleep(Duration::from_millis(5));
+        ControlFlow::Continue(())
+    });
+    stopper.join().unwrap();
+
+    assert!(matches!(result, Err(GlobError::Cancelled)));
+    assert!(seen < names.len());
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_sync_from_patterns_file() {
+    let dir = common::fixture(&[
+        ("src/lib.rs", ""),
+        ("src/generated/api.rs", ""),
+        ("README.md", ""),
+        ("notes.txt", ""),
+        (
+            "patterns.txt",
+            "# sources\n**/*.rs\n\n  *.md  \n# but not generated code\n!src/generated/**\n",
+        ),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .patterns_relative_to_root(true)
+        .build();
+
+    let results =
+        PatternHunt::sync_from_file(&dir.path().join("patterns.txt"), &["."], opts).unwrap();
+    assert_eq!(
+        common::relative(dir.path(), &results),
+        vec!["README.md", "src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_missing_or_file_root_fails_before_walking() {
+    let dir = common::fixture(&[("a.txt", "a"), ("file.txt", "f")]);
+    let opts = |skip| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .skip_missing_roots(skip)
+            .build()
+    };
+
+    for bad in ["missing", "file.txt"] {
+        let err = PatternHunt::sync(&["**/*.txt"], &[".", bad], opts(false)).unwrap_err();
+        assert!(
+            matches!(err, GlobError::RootNotFound(ref root) if *root == dir.path().join(bad)),
+            "{bad}: {err:?}"
+        );
+        assert!(err.to_string().contains(bad));
+    }
+
+    let results = PatternHunt::sync(&["**/*.txt"], &["missing", ".", "file.txt"], opts(true));
+    assert_eq!(
+        common::relative(dir.path(), &results.unwrap()),
+        vec!["a.txt", "file.txt"]
+    );
+}
+
+#[test]
+fn test_timeout_scope_per_root_gives_each_root_a_budget() {
+    use patternhunt::TimeoutScope;
+
+    let dir = common::fixture(&[("slow/a.txt", "a"), ("fast/b.txt", "b")]);
+    let run = |scope| {
+        let opts = GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .timeout(Duration::from_millis(200))
+            .timeout_scope(scope)
+            .build();
+        let mut seen = Vec::new();
+        let result = PatternHunt::for_each(&["**/*.txt"], &["slow", "fast"], opts, |p| {
+            // Matches under the first root use up the whole budget
+            if p.starts_with(dir.path().join("slow")) {
+                std::thread::sleep(Duration::from_millis(300));
+            }
+            seen.push(p.file_name().unwrap().to_string_lossy().into_owned());
+            ControlFlow::Continue(())
+        });
+        (result, seen)
+    };
+
+    assert_eq!(
+        patternhunt::GlobOptions::default().timeout_scope,
+        TimeoutScope::PerRoot
+    );
+    let (result, seen) = run(TimeoutScope::PerRoot);
+    result.unwrap();
+    assert_eq!(seen, vec!["a.txt", "b.txt"]);
+
+    let (result, seen) = run(TimeoutScope::Global);
+    assert!(matches!(result, Err(GlobError::Timeout)));
+    assert_eq!(seen, vec!["a.txt"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_expand_tilde_in_roots_and_patterns() {
+    // HOME is only read here: setting it would race with other tests
+    let home = std::path::PathBuf::from(std::env::var_os("HOME").expect("HOME is set"));
+    let opts = |expand| GlobOptionsBuilder::new().expand_tilde(expand).build();
+
+    let err =
+        PatternHunt::sync(&["**/*.txt"], &["~/patternhunt-no-such-dir"], opts(true)).unwrap_err();
+    let expected = home.join("patternhunt-no-such-dir");
+    assert!(matches!(err, GlobError::RootNotFound(ref root) if *root == expected));
+
+    let patterns = Patterns::compile_one("~/projects/*.txt", &opts(true)).unwrap();
+    let candidate = home.join("projects/a.txt");
+    assert!(patterns.matches_str(candidate.to_str().unwrap()));
+
+    // Without the option `~` is an ordinary directory name
+    let err = PatternHunt::sync(&["**/*.txt"], &["~/projects"], opts(false)).unwrap_err();
+    assert!(matches!(err, GlobError::RootNotFound(ref root) if root.starts_with("~")));
+
+    // Other users' home directories are not looked up
+    let err = PatternHunt::sync(&["**/*.txt"], &["~root/x"], opts(true)).unwrap_err();
+    assert!(matches!(err, GlobError::RootNotFound(ref root) if root.starts_with("~root")));
+}
+
+#[test]
+fn test_sync_grouped_by_root() {
+    let dir = common::fixture(&[
+        ("api/a.rs", ""),
+        ("api/b.rs", ""),
+        ("web/c.rs", ""),
+        ("docs/readme.md", ""),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .build();
+
+    let groups = PatternHunt::sync_grouped(&["**/*.rs"], &["api", "web", "docs"], opts).unwrap();
+    let summary: Vec<_> = groups
+        .iter()
+        .map(|(root, paths)| (root.clone(), common::relative(dir.path(), paths)))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                dir.path().join("api"),
+                vec!["api/a.rs".to_string(), "api/b.rs".to_string()]
+            ),
+            (dir.path().join("web"), vec!["web/c.rs".to_string()]),
+            (dir.path().join("docs"), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_literal_prefix_pruning_has_no_false_negatives() {
+    let dir = common::fixture(&[
+        ("src/mod.rs", ""),
+        ("src/a/mod.rs", ""),
+        ("src/a/b/mod.rs", ""),
+        ("src/a/notes.md", ""),
+        ("Src/x/mod.rs", ""),
+        ("lib/mod.rs", ""),
+        ("docs/readme.md", ""),
+        ("docs/src/mod.rs", ""),
+        ("vendor/deep/src/mod.rs", ""),
+    ]);
+    let opts = |case_sensitive| {
+        GlobOptionsBuilder::new()
+            .root_dir(dir.path().to_path_buf())
+            .patterns_relative_to_root(true)
+            .case_sensitive(case_sensitive)
+            .build()
+    };
+    // `**` has no literal prefix, so this listing is never pruned
+    let all = PatternHunt::sync(&["**"], &["."], opts(true)).unwrap();
+    assert_eq!(all.len(), 9);
+
+    for pattern in [
+        "src/**/mod.rs",
+        "src/a/mod.rs",
+        "src/*/mod.rs",
+        "{src,lib}/**/*.rs",
+        "src/@(a|b)/**",
+        "**/src/mod.rs",
+        "docs/readme.md",
+        "./src/**",
+        "re:^src/.*",
+        "*.rs",
+    ] {
+        for case_sensitive in [true, false] {
+            let expected =
+                PatternHunt::filter_paths(&[pattern], all.clone(), opts(case_sensitive)).unwrap();
+            let walked = PatternHunt::sync(&[pattern], &["."], opts(case_sensitive)).unwrap();
+            assert_eq!(
+                common::relative(dir.path(), &walked),
+                common::relative(dir.path(), &expected),
+                "{pattern} (case sensitive: {case_sensitive})"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_literal_prefix_prunes_unrelated_directories() {
+    let dir = common::fixture(&[
+        ("src/a/mod.rs", ""),
+        ("lib/mod.rs", ""),
+        ("docs/src/mod.rs", ""),
+        ("vendor/deep/src/mod.rs", ""),
+    ]);
+    let opts = GlobOptionsBuilder::new()
+        .root_dir(dir.path().to_path_buf())
+        .patterns_relative_to_root(true)
+        .build();
+
+    let (results, stats) =
+        PatternHunt::sync_with_stats(&["src/**/mod.rs"], &["."], opts.clone()).unwrap();
+    assert_eq!(common::relative(dir.path(), &results), vec!["src/a/mod.rs"]);
+    assert_eq!(stats.dirs_visited, 3); // root, src, src/a
+
+    let (_, unpruned) = PatternHunt::sync_with_stats(&["**/mod.rs"], &["."], opts).unwrap();
+    assert_eq!(unpruned.dirs_visited, 9);
+}